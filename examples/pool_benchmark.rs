@@ -0,0 +1,53 @@
+//! Compares bulk reads through one pooled instance with reads through a new instance each,
+//! which builds a new client and opens a new connection for every request.
+//!
+//! Run with `cargo run --release --example pool_benchmark --features test-util [reads]`.
+//! The documents are served by a local mock server, against `https` hosts
+//! every new connection also pays the DNS lookup and the TLS handshake.
+
+use std::time::{Duration, Instant};
+use jsonbank::{JsonBank, JsonObject};
+use jsonbank::error::JsbError;
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+
+// instance - a new instance with its own client
+fn instance(server: &MockServer) -> JsonBank {
+    JsonBank::builder()
+        .host(&server.url())
+        .keys(MOCK_PUBLIC_KEY, MOCK_PRIVATE_KEY)
+        .build()
+        .unwrap()
+}
+
+// read_all - reads every document once with `read` and returns the elapsed time
+fn read_all(paths: &[String], read: impl Fn(&str) -> Result<JsonObject, JsbError>) -> Duration {
+    let start = Instant::now();
+
+    for path in paths {
+        if let Err(err) = read(path) {
+            panic!("{:?}", err);
+        }
+    }
+
+    start.elapsed()
+}
+
+fn main() {
+    let reads: usize = std::env::args().nth(1).and_then(|reads| reads.parse().ok()).unwrap_or(100);
+
+    let server = MockServer::start();
+    let paths: Vec<String> = (0..reads)
+        .map(|i| {
+            server.add_document("bench", &format!("{}.json", i), &format!(r#"{{"index": {}}}"#, i));
+            format!("bench/{}.json", i)
+        })
+        .collect();
+
+    let pooled = instance(&server);
+    let with_pool = read_all(&paths, |path| pooled.get_own_content(path));
+    let without_pool = read_all(&paths, |path| instance(&server).get_own_content(path));
+
+    println!("{} reads", reads);
+    println!("one pooled instance:      {:>8.2?} ({:.0} reads/s)", with_pool, reads as f64 / with_pool.as_secs_f64());
+    println!("a new instance each read: {:>8.2?} ({:.0} reads/s)", without_pool, reads as f64 / without_pool.as_secs_f64());
+}
@@ -0,0 +1,379 @@
+use std::io::Read;
+use std::path::Path;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::{ContentsIter, Document, JsonBank, JsonPatch, JsonValue};
+use crate::error::*;
+use crate::request::decode_content_at;
+use crate::functions::*;
+use crate::structs::*;
+use crate::operations::{self, FolderPath, IfNotExists};
+use crate::{modify, sync, trace};
+use crate::modify::{Reply, Request};
+
+/// The document, folder and auth operations of a JsonBank backend.
+///
+/// [JsonBank](struct.JsonBank.html) is the http implementation,
+/// [MemoryStore](stores/struct.MemoryStore.html) and [FileSystemStore](stores/struct.FileSystemStore.html)
+/// keep everything in memory or in a local directory.
+/// Depend on this trait to pick the backend at runtime or to use a fake in unit tests.
+///
+/// The trait is object safe, use [get_own_content_value](#method.get_own_content_value)
+/// to read content through a `dyn JsonBankApi`.
+/// # Example
+/// ```
+/// use jsonbank::{JsonBankApi, JsonValue};
+/// use jsonbank::stores::MemoryStore;
+/// use jsonbank::structs::CreateDocumentBody;
+///
+/// fn save_settings(api: &dyn JsonBankApi) {
+///     api.create_document_if_not_exists(CreateDocumentBody {
+///         name: "settings.json".to_string(),
+///         project: "app".to_string(),
+///         content: r#"{"theme": "dark"}"#.to_string(),
+///         folder: None,
+///     }).unwrap();
+/// }
+///
+/// let store = MemoryStore::new();
+/// store.add_project("app", false);
+/// save_settings(&store);
+///
+/// let data = store.get_own_content_value("app/settings.json").unwrap();
+/// assert_eq!(data["theme"], "dark");
+/// ```
+pub trait JsonBankApi {
+    /// Authenticate user using current api key
+    fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError>;
+
+    /// Get username of authenticated user
+    fn get_username(&self) -> Result<String, JsbError>;
+
+    /// Check if user is authenticated
+    fn is_authenticated(&self) -> bool;
+
+    /// Get content meta of a document owned by authenticated user
+    fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError>;
+
+    /// Get content of a document owned by authenticated user as json string
+    fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError>;
+
+    /// Get content of a document owned by authenticated user as json string, never from a cache.
+    /// Backends without a cache read it like [get_own_content_as_string](#tymethod.get_own_content_as_string).
+    fn get_own_content_uncached(&self, id_or_path: &str) -> Result<String, JsbError> {
+        self.get_own_content_as_string(id_or_path)
+    }
+
+    /// Get json content of a document owned by authenticated user as a [JsonValue](type.JsonValue.html)
+    fn get_own_content_value(&self, id_or_path: &str) -> Result<JsonValue, JsbError> {
+        match self.get_own_content_as_string(id_or_path) {
+            Ok(content) => serde_json::from_str(&content).map_err(JsbError::from),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get json content of a document owned by authenticated user.
+    /// Not available on `dyn JsonBankApi`, use [get_own_content_value](#method.get_own_content_value) there.
+    fn get_own_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> where Self: Sized {
+        match self.get_own_content_as_string(id_or_path) {
+            Ok(content) => serde_json::from_str(&content).map_err(JsbError::from),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get the node of a document owned by authenticated user at a JSON Pointer, e.g. `/features/checkout`.
+    fn get_own_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> where Self: Sized {
+        match self.get_own_content_as_string(id_or_path) {
+            Ok(content) => decode_content_at(&content, pointer),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// A handle to a document owned by authenticated user, bound to the type of its content.
+    fn document<T: DeserializeOwned + Serialize>(&self, id_or_path: &str) -> Document<'_, T> where Self: Sized {
+        Document::new(self, id_or_path)
+    }
+
+    /// Check if user has document.
+    fn has_own_document(&self, id_or_path: &str) -> Result<bool, JsbError> {
+        operations::exists(self.get_own_document_meta(id_or_path))
+    }
+
+    /// Create a document.
+    fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError>;
+
+    /// Create a document if it does not exist
+    fn create_document_if_not_exists(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("create_document_if_not_exists");
+
+        match operations::if_not_exists(self.create_document(content.clone()), || make_document_path(&content)) {
+            IfNotExists::Done(res) => res,
+            IfNotExists::Read(doc_path) => {
+                let res = self.get_own_document_meta(doc_path.as_str())?;
+                Ok(document_meta_to_new_document(res, content.name))
+            }
+        }
+    }
+
+    /// Update a document that belongs to the authenticated user.
+    fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError>;
+
+    /// Update a document if it is still at the `expected` version,
+    /// otherwise fail with [ErrorKind::Conflict](error/enum.ErrorKind.html#variant.Conflict).
+    ///
+    /// # Best effort
+    /// The version is checked by a request before the update, a change made between both requests
+    /// is **not** detected and is overwritten.
+    /// Only [MemoryStore](stores/struct.MemoryStore.html) checks it under the same lock as the update.
+    fn update_own_document_after_check(&self, id_or_path: &str, content: String, expected: &ExpectedVersion) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("update_own_document_after_check");
+
+        modify::run_check_first(id_or_path, modify::AfterCheck::new(content, expected), |request| match request {
+            Request::Meta => self.get_own_document_meta(id_or_path).map(Reply::Meta),
+            Request::Content => self.get_own_content_uncached(id_or_path).map(Reply::Content),
+            Request::Update(content) => self.update_own_document(id_or_path, content).map(Reply::Updated),
+        })
+    }
+
+    /// Read a document, change it with `modify` and write it back if no other client changed it in between.
+    /// On a conflict the document is read and `modify` is called again, up to 5 times.
+    /// Best effort like [update_own_document_after_check](#method.update_own_document_after_check).
+    fn modify_document<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F) -> Result<UpdatedDocument, JsbError> where Self: Sized {
+        let _span = trace::operation("modify_document");
+
+        modify::run(self, id_or_path, modify::Modify::new(modify))
+    }
+
+    /// Apply RFC 6902 JSON Patch operations to a document, it is only updated if the patch changed it.
+    fn patch_own_document(&self, id_or_path: &str, ops: &JsonPatch) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("patch_own_document");
+
+        modify::run(self, id_or_path, modify::Change::new(|value| modify::patch(value, ops)))
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch to a document, it is only updated if the patch changed it.
+    fn merge_own_document(&self, id_or_path: &str, partial: &JsonValue) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("merge_own_document");
+
+        modify::run(self, id_or_path, modify::Change::new(|value| modify::merge(value, partial)))
+    }
+
+    /// Upload a json document
+    fn upload_document(&self, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("upload_document");
+
+        match read_upload_document(doc) {
+            Ok(content) => self.create_document(content),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Upload a json document read from a reader.
+    /// Not available on `dyn JsonBankApi`, use [upload_bytes](#method.upload_bytes) there.
+    fn upload_from_reader<R: Read>(&self, reader: R, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> where Self: Sized {
+        let _span = trace::operation("upload_from_reader");
+
+        match read_upload_reader(reader, doc) {
+            Ok(content) => self.create_document(content),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Upload a json document from a byte buffer
+    fn upload_bytes(&self, bytes: &[u8], doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("upload_bytes");
+
+        match read_upload_bytes(bytes, doc) {
+            Ok(content) => self.create_document(content),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Delete a document, `deleted` is false when the document does not exist
+    fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError>;
+
+    /// Create a folder
+    fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError>;
+
+    /// Get a folder
+    fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError>;
+
+    /// Get a folder with statistics count
+    fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError>;
+
+    /// List the documents and folders in the root of a project, one page at a time.
+    /// Only the local stores support listing, the jsonbank api has no listing endpoint
+    /// and fails with [ErrorKind::Unsupported](error/enum.ErrorKind.html#variant.Unsupported).
+    fn list_project_contents(&self, project: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        let _ = (project, options);
+        Err(err_unsupported("Listing contents"))
+    }
+
+    /// List the documents and folders of a folder, one page at a time.
+    /// See [list_project_contents](#method.list_project_contents).
+    fn list_folder_contents(&self, id_or_path: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        let _ = (id_or_path, options);
+        Err(err_unsupported("Listing contents"))
+    }
+
+    /// Iterate over the root of a project, fetching pages lazily.
+    /// Use [ContentsIter::project](struct.ContentsIter.html#method.project) on a `dyn JsonBankApi`.
+    fn iter_project_contents(&self, project: &str, options: ListOptions) -> ContentsIter<'_> where Self: Sized {
+        ContentsIter::project(self, project, options)
+    }
+
+    /// Iterate over a folder, fetching pages lazily.
+    /// Use [ContentsIter::folder](struct.ContentsIter.html#method.folder) on a `dyn JsonBankApi`.
+    fn iter_folder_contents(&self, id_or_path: &str, options: ListOptions) -> ContentsIter<'_> where Self: Sized {
+        ContentsIter::folder(self, id_or_path, options)
+    }
+
+    /// Create a folder if it does not exist, the bool is true when the folder already existed
+    fn create_folder_if_not_exists(&self, data: CreateFolderBody) -> Result<(Folder, bool), JsbError> {
+        let _span = trace::operation("create_folder_if_not_exists");
+
+        match operations::if_not_exists(self.create_folder(data.clone()), || make_folder_path(&data)) {
+            IfNotExists::Done(res) => res.map(|folder| (folder, false)),
+            IfNotExists::Read(folder_path) => Ok((self.get_folder(folder_path.as_str())?, true)),
+        }
+    }
+
+    /// Create every missing folder of a `project/a/b/c` path, like `mkdir -p`.
+    /// The project must exist.
+    fn ensure_folder_path(&self, path: &str) -> Result<EnsuredFolder, JsbError> {
+        let _span = trace::operation("ensure_folder_path");
+
+        let mut folders = FolderPath::new(path)?;
+
+        while let Some(data) = folders.next_folder() {
+            let (folder, exists) = self.create_folder_if_not_exists(data)?;
+            folders.add(folder, exists);
+        }
+
+        folders.finish()
+    }
+
+    /// Mirror the json files of a local directory to a project.
+    /// Sub directories become folders, documents are created or updated when their content changed.
+    /// Hidden files and files without the `.json` extension are skipped.
+    /// `delete` lists the project, see [list_project_contents](#method.list_project_contents).
+    fn sync_directory(&self, local_dir: &Path, project: &str, options: SyncOptions) -> Result<SyncReport, JsbError> {
+        sync::sync_directory(self, local_dir, project, &options)
+    }
+
+    /// Download every folder and document of a project into `dest_dir`.
+    /// A [MIRROR_MANIFEST](constant.MIRROR_MANIFEST.html) file keeps the `updated_at` of each document,
+    /// so the next run only downloads the documents that changed.
+    /// A root document named like the manifest is skipped and reported in `skipped`.
+    /// Lists the project, see [list_project_contents](#method.list_project_contents).
+    fn mirror_project(&self, project: &str, dest_dir: &Path) -> Result<MirrorReport, JsbError> {
+        sync::mirror_project(self, project, dest_dir)
+    }
+}
+
+// Http Implementation, every method calls the inherent method of the same name
+impl JsonBankApi for JsonBank {
+    fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError> {
+        JsonBank::authenticate(self)
+    }
+
+    fn get_username(&self) -> Result<String, JsbError> {
+        JsonBank::get_username(self)
+    }
+
+    fn is_authenticated(&self) -> bool {
+        JsonBank::is_authenticated(self)
+    }
+
+    fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
+        JsonBank::get_own_document_meta(self, id_or_path)
+    }
+
+    fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
+        JsonBank::get_own_content_as_string(self, id_or_path)
+    }
+
+    fn get_own_content_uncached(&self, id_or_path: &str) -> Result<String, JsbError> {
+        JsonBank::get_own_content_uncached(self, id_or_path)
+    }
+
+    fn get_own_content_value(&self, id_or_path: &str) -> Result<JsonValue, JsbError> {
+        JsonBank::get_own_content(self, id_or_path)
+    }
+
+    fn get_own_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> {
+        JsonBank::get_own_content(self, id_or_path)
+    }
+
+    fn get_own_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> {
+        JsonBank::get_own_content_at(self, id_or_path, pointer)
+    }
+
+    fn document<T: DeserializeOwned + Serialize>(&self, id_or_path: &str) -> Document<'_, T> {
+        JsonBank::document(self, id_or_path)
+    }
+
+    fn has_own_document(&self, id_or_path: &str) -> Result<bool, JsbError> {
+        JsonBank::has_own_document(self, id_or_path)
+    }
+
+    fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        JsonBank::create_document(self, content)
+    }
+
+    fn create_document_if_not_exists(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        JsonBank::create_document_if_not_exists(self, content)
+    }
+
+    fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError> {
+        JsonBank::update_own_document(self, id_or_path, content)
+    }
+
+    fn update_own_document_after_check(&self, id_or_path: &str, content: String, expected: &ExpectedVersion) -> Result<UpdatedDocument, JsbError> {
+        JsonBank::update_own_document_after_check(self, id_or_path, content, expected)
+    }
+
+    fn modify_document<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F) -> Result<UpdatedDocument, JsbError> {
+        JsonBank::modify_document(self, id_or_path, modify)
+    }
+
+    fn patch_own_document(&self, id_or_path: &str, ops: &JsonPatch) -> Result<UpdatedDocument, JsbError> {
+        JsonBank::patch_own_document(self, id_or_path, ops)
+    }
+
+    fn merge_own_document(&self, id_or_path: &str, partial: &JsonValue) -> Result<UpdatedDocument, JsbError> {
+        JsonBank::merge_own_document(self, id_or_path, partial)
+    }
+
+    fn upload_document(&self, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        JsonBank::upload_document(self, doc)
+    }
+
+    fn upload_from_reader<R: Read>(&self, reader: R, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        JsonBank::upload_from_reader(self, reader, doc)
+    }
+
+    fn upload_bytes(&self, bytes: &[u8], doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        JsonBank::upload_bytes(self, bytes, doc)
+    }
+
+    fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError> {
+        JsonBank::delete_document(self, id_or_path)
+    }
+
+    fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError> {
+        JsonBank::create_folder(self, data)
+    }
+
+    fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        JsonBank::get_folder(self, id_or_path)
+    }
+
+    fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        JsonBank::get_folder_with_stats(self, id_or_path)
+    }
+
+    fn create_folder_if_not_exists(&self, data: CreateFolderBody) -> Result<(Folder, bool), JsbError> {
+        JsonBank::create_folder_if_not_exists(self, data)
+    }
+}
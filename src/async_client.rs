@@ -0,0 +1,735 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::{AsyncDocument, Config, ContentCache, InitConfig, JsonPatch, JsonValue, RequestOptions, TlsConfig};
+use crate::modify::{self, CheckFirst, Next, Reply, Request};
+use crate::cache::{Lookup, Scope};
+use crate::error::*;
+use crate::functions::*;
+use crate::operations::{self, FolderPath, IfNotExists, Requests};
+use crate::request::*;
+use crate::structs::*;
+use crate::trace::{self, RequestSpan};
+use crate::metrics::MetricsSink;
+use crate::retry::Attempts;
+use crate::middleware::Chain;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Async JsonBank SDK Instance
+///
+/// Mirrors [JsonBank](struct.JsonBank.html), every method returns a future.
+/// Only available with the `async` feature.
+/// # Example
+/// ```no_run
+/// # async fn run() {
+/// use jsonbank::{AsyncJsonBank, JsonValue};
+///
+/// let jsb = AsyncJsonBank::new_without_config();
+/// let data: JsonValue = jsb.get_content("jsonbank/sdk-test/index").await.unwrap();
+/// assert_eq!(data["author"], "jsonbank");
+/// # }
+/// ```
+pub struct AsyncJsonBank {
+    /// Instance Config
+    pub config: Config,
+    // Endpoints
+    endpoints: Endpoints,
+    // Authenticated data
+    authenticated_data: Option<AuthenticatedData>,
+    // Http client, shared by all requests of this instance. The error it could not be built with otherwise,
+    // every request returns it
+    client: Result<reqwest::Client, JsbError>,
+    // Content cache, if enabled
+    cache: Option<ContentCache>,
+    // Metrics sink, if set
+    metrics: Option<Arc<dyn MetricsSink>>,
+    // Middleware chain, starting with the api keys
+    middleware: Chain,
+}
+
+// Implementing AsyncJsonBank
+impl AsyncJsonBank {
+    /// Initialize Async JsonBank SDK Instance
+    /// # Arguments
+    /// * `conf` - The minimal config needed to initialize
+    ///
+    /// Does not fail: when the http client cannot be built, e.g. the user agent contains invalid characters,
+    /// every request returns the error. Use [JsonBank::builder](struct.JsonBank.html#method.builder) to get it
+    /// right away and to set a proxy or the TLS settings.
+    pub fn new(conf: InitConfig) -> Self {
+        Self::init(conf, None, TlsConfig::default())
+    }
+
+    // try_new - Initialize Async JsonBank SDK Instance, returning an error when the http client cannot be built
+    pub(crate) fn try_new(conf: InitConfig, proxy: Option<String>, tls: TlsConfig) -> Result<Self, JsbError> {
+        let jsb = Self::init(conf, proxy, tls);
+        jsb.client()?;
+
+        Ok(jsb)
+    }
+
+    // init - Initialize Async JsonBank SDK Instance, keeping the error if the http client cannot be built
+    fn init(mut conf: InitConfig, proxy: Option<String>, tls: TlsConfig) -> Self {
+        // the cache is not part of the config
+        let cache = conf.cache.take().map(ContentCache::new);
+        let metrics = conf.metrics.take();
+        let middleware = Chain::new(std::mem::take(&mut conf.middleware));
+
+        // build config
+        let config = Config::from_init(conf, proxy, tls);
+
+        // set endpoints
+        let endpoints = Endpoints::new(&config.host);
+
+        // build http client
+        let client = match configure_client!(reqwest::Client::builder(), &config) {
+            // not a transport error, sending again does not help
+            Ok(builder) => builder.build().map_err(|err| err_invalid_config("The http client cannot be built").with_source(err)),
+            Err(err) => Err(err),
+        };
+
+        AsyncJsonBank { config, endpoints, authenticated_data: None, client, cache, metrics, middleware }
+    }
+
+    /// Initialize Async JsonBank SDK Instance without config
+    pub fn new_without_config() -> Self {
+        Self::new(InitConfig::default())
+    }
+}
+
+// Instance Implementation
+impl AsyncJsonBank {
+    // requests_with - Returns the request builder of this instance using call options
+    fn requests_with(&self, options: RequestOptions) -> Requests<'_> {
+        Requests::new(&self.config, &self.endpoints, options)
+    }
+
+    // make_request - Sends a prepared request, retrying it as allowed by the retry policy
+    async fn make_request(&self, req: ApiRequest) -> Result<ApiResponse, JsbError> {
+        let span = RequestSpan::new(&req);
+
+        span.instrument(async {
+            let mut attempts = Attempts::new(&req, &self.config.retry, &span, self.metrics.as_deref());
+
+            loop {
+                let res = self.send_attempt(req.clone()).await;
+
+                match attempts.next(&res) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return res,
+                }
+            }
+        }).await
+    }
+
+    // send_attempt - Sends one attempt of a request through the middleware chain
+    async fn send_attempt(&self, mut req: ApiRequest) -> Result<ApiResponse, JsbError> {
+        let (passed, answer) = self.middleware.before(&mut req);
+
+        let res = match answer {
+            Some(res) => res,
+            None => self.send_once(&req).await,
+        };
+
+        self.middleware.after(passed, &req, res)
+    }
+
+    // client - Returns the http client, or the error it could not be built with
+    fn client(&self) -> Result<&reqwest::Client, JsbError> {
+        self.client.as_ref().map_err(client_error)
+    }
+
+    // send_once - Sends a prepared request and reads the whole response
+    async fn send_once(&self, req: &ApiRequest) -> Result<ApiResponse, JsbError> {
+        // send request
+        let res = match http_request!(self.client()?, req).send().await {
+            Ok(res) => res,
+            Err(err) => return Err(JsbError::from(err)),
+        };
+
+        let status = res.status();
+        let headers = res.headers().clone();
+        match res.bytes().await {
+            Ok(body) => Ok(ApiResponse { status, headers, body: body.to_vec() }),
+            Err(err) => Err(JsbError::from(err)),
+        }
+    }
+
+    // send_request - Sends request and deserializes the response
+    async fn send_request<T: DeserializeOwned>(&self, req: Result<ApiRequest, JsbError>) -> Result<T, JsbError> {
+        let res = match req {
+            Ok(req) => self.make_request(req).await,
+            Err(err) => Err(err),
+        };
+
+        match res {
+            Ok(res) => process_response(res),
+            Err(err) => Err(err),
+        }
+    }
+
+    // send_request_as_string - Sends request and returns response as text
+    async fn send_request_as_string(&self, req: Result<ApiRequest, JsbError>) -> Result<String, JsbError> {
+        let res = match req {
+            Ok(req) => self.make_request(req).await,
+            Err(err) => Err(err),
+        };
+
+        match res {
+            Ok(res) => process_response_as_string(res),
+            Err(err) => Err(err),
+        }
+    }
+
+    // cached_content - Returns the cached content of a document, sending the request and caching its content on a miss
+    async fn cached_content(&self, scope: Scope, id_or_path: &str, req: Result<ApiRequest, JsbError>) -> Result<String, JsbError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.send_request_as_string(req).await,
+        };
+
+        let (validators, generation) = match cache.get(scope, id_or_path) {
+            Lookup::Fresh(content) => return Ok(content),
+            Lookup::Send(validators, generation) => (validators, generation),
+        };
+
+        let req = req?;
+        match cache.revalidated(scope, id_or_path, self.send_conditional(Ok(req.clone()), &validators).await?, generation) {
+            Some(content) => Ok(content),
+            // evicted while revalidating
+            None => self.send_request_as_string(Ok(req)).await,
+        }
+    }
+
+    // send_conditional - Sends a request with the validators of a previous response, if any
+    async fn send_conditional(&self, req: Result<ApiRequest, JsbError>, validators: &Validators) -> Result<Revalidated, JsbError> {
+        let mut req = req?;
+        validators.apply(&mut req)?;
+
+        match self.make_request(req).await {
+            Ok(res) => process_conditional_response(res),
+            Err(err) => Err(err),
+        }
+    }
+
+    // content_if_changed - Sends a content request with `If-None-Match` and decodes the content if it changed
+    async fn content_if_changed<T: DeserializeOwned>(&self, req: Result<ApiRequest, JsbError>, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        let validators = Validators { etag: previous_etag.map(|etag| etag.to_string()), last_modified: None };
+
+        conditional_content(self.send_conditional(req, &validators).await?)
+    }
+
+    /// The content cache, see [JsonBank::cache](struct.JsonBank.html#method.cache)
+    pub fn cache(&self) -> Option<&ContentCache> {
+        self.cache.as_ref()
+    }
+
+    // invalidate - Removes a document from the cache, if enabled
+    fn invalidate(&self, id_or_path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(id_or_path);
+        }
+    }
+
+    // alias - Tells the cache the id and the path of an own document, so updating one evicts both
+    fn alias(&self, id: &str, project: &str, path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.alias(id, project, path);
+        }
+    }
+
+    /// Sets host, see [JsonBank::set_host](struct.JsonBank.html#method.set_host)
+    pub fn set_host(&mut self, host: &str) {
+        self.config.host = host.to_string();
+        // update endpoints
+        self.endpoints = Endpoints::new(&self.config.host);
+    }
+
+    /// Get public content meta from jsonbank
+    pub async fn get_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
+        self.get_document_meta_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get public content meta with call options.
+    pub async fn get_document_meta_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DocumentMeta, JsbError> {
+        self.send_request(self.requests_with(options).get_document_meta(id_or_path)).await
+    }
+
+    /// Get public content from jsonbank
+    pub async fn get_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> {
+        self.get_content_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get public content with call options.
+    pub async fn get_content_with<T: DeserializeOwned>(&self, id_or_path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.cache {
+            Some(_) => self.get_content_as_string_with(id_or_path, options).await.and_then(|content| decode_content(&content)),
+            None => self.send_request(self.requests_with(options).get_content(id_or_path)).await,
+        }
+    }
+
+    /// Get public content as string from jsonbank
+    pub async fn get_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
+        self.get_content_as_string_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get public content as string with call options.
+    pub async fn get_content_as_string_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.cached_content(Scope::Public, id_or_path, self.requests_with(options).get_content(id_or_path)).await
+    }
+
+    /// Get the node of public content at a JSON Pointer, e.g. `/features/checkout`
+    pub async fn get_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> {
+        self.get_content_at_with(id_or_path, pointer, RequestOptions::default()).await
+    }
+
+    /// Get the node of public content at a JSON Pointer with call options.
+    pub async fn get_content_at_with<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.get_content_as_string_with(id_or_path, options).await {
+            Ok(content) => decode_content_at(&content, pointer),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get public content only if it changed since `previous_etag`
+    pub async fn get_content_if_changed<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        self.get_content_if_changed_with(id_or_path, previous_etag, RequestOptions::default()).await
+    }
+
+    /// Get public content only if it changed since `previous_etag` with call options.
+    pub async fn get_content_if_changed_with<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>, options: RequestOptions) -> Result<ConditionalContent<T>, JsbError> {
+        self.content_if_changed(self.requests_with(options).get_content(id_or_path), previous_etag).await
+    }
+
+    /// Grab a public json file from Github.
+    pub async fn get_github_content<T: DeserializeOwned>(&self, path: &str) -> Result<T, JsbError> {
+        self.get_github_content_with(path, RequestOptions::default()).await
+    }
+
+    /// Grab a public json file from Github with call options.
+    pub async fn get_github_content_with<T: DeserializeOwned>(&self, path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        self.send_request(self.requests_with(options).get_github_content(path)).await
+    }
+
+    /// Grab a public json file from Github as a string.
+    pub async fn get_github_content_as_string(&self, path: &str) -> Result<String, JsbError> {
+        self.get_github_content_as_string_with(path, RequestOptions::default()).await
+    }
+
+    /// Grab a public json file from Github as a string with call options.
+    pub async fn get_github_content_as_string_with(&self, path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.send_request_as_string(self.requests_with(options).get_github_content(path)).await
+    }
+}
+
+// Auth Implementation
+impl AsyncJsonBank {
+    /// Authenticate user using current api key
+    pub async fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError> {
+        self.authenticate_with(RequestOptions::default()).await
+    }
+
+    /// Authenticate user with call options, keys set in the options are used for this request only.
+    pub async fn authenticate_with(&mut self, options: RequestOptions) -> Result<AuthenticatedData, JsbError> {
+        match self.send_request::<AuthenticatedData>(self.requests_with(options).authenticate()).await {
+            Ok(data) => {
+                // set authenticated data
+                self.authenticated_data = Some(data.clone());
+
+                Ok(data)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get username of authenticated user
+    ///
+    /// **Note:** [authenticate](#method.authenticate) must be called before calling this method.
+    pub fn get_username(&self) -> Result<String, JsbError> {
+        match &self.authenticated_data {
+            Some(data) => Ok(data.username.clone()),
+            None => Err(err_not_authenticated()),
+        }
+    }
+
+    /// Check if user is authenticated
+    pub fn is_authenticated(&self) -> bool {
+        match &self.authenticated_data {
+            Some(data) => data.authenticated,
+            None => false,
+        }
+    }
+
+    /// Get content meta of a document owned by authenticated user
+    pub async fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
+        self.get_own_document_meta_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get content meta of a document owned by authenticated user with call options.
+    pub async fn get_own_document_meta_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DocumentMeta, JsbError> {
+        let remember = options.keys.is_none();
+        let res = self.send_request::<DocumentMeta>(self.requests_with(options).get_own_document_meta(id_or_path)).await;
+
+        if let (Ok(meta), true) = (&res, remember) {
+            self.alias(&meta.id, &meta.project, &meta.path);
+        }
+
+        res
+    }
+
+    /// Get json content of a document owned by authenticated user
+    pub async fn get_own_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> {
+        self.get_own_content_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get json content of a document owned by authenticated user with call options.
+    pub async fn get_own_content_with<T: DeserializeOwned>(&self, id_or_path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match (&self.cache, &options.keys) {
+            (Some(_), None) => self.get_own_content_as_string_with(id_or_path, options).await.and_then(|content| decode_content(&content)),
+            _ => self.send_request(self.requests_with(options).get_own_content(id_or_path)).await,
+        }
+    }
+
+    /// Get content of a document owned by authenticated user as json string
+    pub async fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
+        self.get_own_content_as_string_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get content of a document owned by authenticated user as json string with call options.
+    pub async fn get_own_content_as_string_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        // the same path may be another document for other keys
+        if options.keys.is_some() {
+            return self.send_request_as_string(self.requests_with(options).get_own_content(id_or_path)).await;
+        }
+
+        self.cached_content(Scope::Own, id_or_path, self.requests_with(options).get_own_content(id_or_path)).await
+    }
+
+    /// Get content of a document owned by authenticated user as json string from the server, even if it is cached,
+    /// see [JsonBank::get_own_content_uncached](../struct.JsonBank.html#method.get_own_content_uncached).
+    pub async fn get_own_content_uncached(&self, id_or_path: &str) -> Result<String, JsbError> {
+        self.get_own_content_uncached_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get content of a document owned by authenticated user from the server with call options.
+    pub async fn get_own_content_uncached_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.invalidate(id_or_path);
+        self.get_own_content_as_string_with(id_or_path, options).await
+    }
+
+    /// Get the node of a document owned by authenticated user at a JSON Pointer
+    pub async fn get_own_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> {
+        self.get_own_content_at_with(id_or_path, pointer, RequestOptions::default()).await
+    }
+
+    /// Get the node of a document owned by authenticated user at a JSON Pointer with call options.
+    pub async fn get_own_content_at_with<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.get_own_content_as_string_with(id_or_path, options).await {
+            Ok(content) => decode_content_at(&content, pointer),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get json content of a document owned by authenticated user only if it changed since `previous_etag`
+    pub async fn get_own_content_if_changed<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        self.get_own_content_if_changed_with(id_or_path, previous_etag, RequestOptions::default()).await
+    }
+
+    /// Get json content of a document owned by authenticated user only if it changed since `previous_etag` with call options.
+    pub async fn get_own_content_if_changed_with<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>, options: RequestOptions) -> Result<ConditionalContent<T>, JsbError> {
+        self.content_if_changed(self.requests_with(options).get_own_content(id_or_path), previous_etag).await
+    }
+
+    /// A handle to a document owned by authenticated user, bound to the type of its content.
+    /// See [AsyncDocument](struct.AsyncDocument.html).
+    pub fn document<T: DeserializeOwned + Serialize>(&self, id_or_path: &str) -> AsyncDocument<'_, T> {
+        AsyncDocument::new(self, id_or_path)
+    }
+
+    /// Check if user has document.
+    pub async fn has_own_document(&self, id_or_path: &str) -> Result<bool, JsbError> {
+        self.has_own_document_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Check if user has document with call options.
+    pub async fn has_own_document_with(&self, id_or_path: &str, options: RequestOptions) -> Result<bool, JsbError> {
+        operations::exists(self.get_own_document_meta_with(id_or_path, options).await)
+    }
+
+    /// Create a document.
+    pub async fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        self.create_document_with(content, RequestOptions::default()).await
+    }
+
+    /// Create a document with call options.
+    pub async fn create_document_with(&self, content: CreateDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let remember = options.keys.is_none();
+        let res = self.send_request::<NewDocument>(self.requests_with(options).create_document(content)).await;
+
+        if let (Ok(doc), true) = (&res, remember) {
+            self.alias(&doc.id, &doc.project, &doc.path);
+        }
+
+        res
+    }
+
+    /// Create a document if it does not exist
+    pub async fn create_document_if_not_exists(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        self.create_document_if_not_exists_with(content, RequestOptions::default()).await
+    }
+
+    /// Create a document if it does not exist with call options.
+    pub async fn create_document_if_not_exists_with(&self, content: CreateDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        trace::instrument_operation("create_document_if_not_exists", async move {
+            let res = self.create_document_with(content.clone(), options.clone()).await;
+
+            match operations::if_not_exists(res, || make_document_path(&content)) {
+                IfNotExists::Done(res) => res,
+                IfNotExists::Read(doc_path) => {
+                    let res = self.get_own_document_meta_with(doc_path.as_str(), options).await?;
+                    Ok(document_meta_to_new_document(res, content.name))
+                }
+            }
+        }).await
+    }
+
+    /// Update a document that belongs to the authenticated user.
+    pub async fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError> {
+        self.update_own_document_with(id_or_path, content, RequestOptions::default()).await
+    }
+
+    /// Update a document with call options.
+    pub async fn update_own_document_with(&self, id_or_path: &str, content: String, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let res = self.send_request(self.requests_with(options).update_own_document(id_or_path, content)).await;
+        self.invalidate(id_or_path);
+        res
+    }
+
+    /// Update a document if no other client changed it since it was read, best effort,
+    /// see [JsonBank::update_own_document_after_check](../struct.JsonBank.html#method.update_own_document_after_check).
+    pub async fn update_own_document_after_check(&self, id_or_path: &str, content: String, expected: &ExpectedVersion) -> Result<UpdatedDocument, JsbError> {
+        self.update_own_document_after_check_with(id_or_path, content, expected, RequestOptions::default()).await
+    }
+
+    /// Update a document if no other client changed it since it was read with call options,
+    /// they apply to the check and to the update.
+    pub async fn update_own_document_after_check_with(&self, id_or_path: &str, content: String, expected: &ExpectedVersion, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        trace::instrument_operation("update_own_document_after_check", async move {
+            self.run_check_first(id_or_path, modify::AfterCheck::new(content, expected), options).await
+        }).await
+    }
+
+    /// Read a document, change it with `modify` and write it back if no other client changed it in between,
+    /// see [JsonBank::modify_document](../struct.JsonBank.html#method.modify_document).
+    pub async fn modify_document<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F) -> Result<UpdatedDocument, JsbError> {
+        self.modify_document_with(id_or_path, modify, RequestOptions::default()).await
+    }
+
+    /// Read, change and write back a document with call options, they apply to every request.
+    pub async fn modify_document_with<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        trace::instrument_operation("modify_document", async move {
+            self.run_check_first(id_or_path, modify::Modify::new(modify), options).await
+        }).await
+    }
+
+    /// Apply RFC 6902 JSON Patch operations to a document,
+    /// see [JsonBank::patch_own_document](../struct.JsonBank.html#method.patch_own_document).
+    pub async fn patch_own_document(&self, id_or_path: &str, ops: &JsonPatch) -> Result<UpdatedDocument, JsbError> {
+        self.patch_own_document_with(id_or_path, ops, RequestOptions::default()).await
+    }
+
+    /// Apply RFC 6902 JSON Patch operations to a document with call options, they apply to every request.
+    pub async fn patch_own_document_with(&self, id_or_path: &str, ops: &JsonPatch, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        trace::instrument_operation("patch_own_document", async move {
+            let op = modify::Change::new(|value| modify::patch(value, ops));
+            self.run_check_first(id_or_path, op, options).await
+        }).await
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch to a document,
+    /// see [JsonBank::merge_own_document](../struct.JsonBank.html#method.merge_own_document).
+    pub async fn merge_own_document(&self, id_or_path: &str, partial: &JsonValue) -> Result<UpdatedDocument, JsbError> {
+        self.merge_own_document_with(id_or_path, partial, RequestOptions::default()).await
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch to a document with call options, they apply to every request.
+    pub async fn merge_own_document_with(&self, id_or_path: &str, partial: &JsonValue, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        trace::instrument_operation("merge_own_document", async move {
+            let op = modify::Change::new(|value| modify::merge(value, partial));
+            self.run_check_first(id_or_path, op, options).await
+        }).await
+    }
+
+    // run_check_first - `modify::run_check_first` with an `.await` on every request
+    async fn run_check_first<O: modify::Operation>(&self, id_or_path: &str, op: O, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let mut op = CheckFirst::new(id_or_path, op);
+        let mut next = op.start();
+
+        loop {
+            let reply = match next {
+                Next::Send(Request::Meta) => self.get_own_document_meta_with(id_or_path, options.clone()).await.map(Reply::Meta),
+                Next::Send(Request::Content) => self.get_own_content_uncached_with(id_or_path, options.clone()).await.map(Reply::Content),
+                Next::Send(Request::Update(content)) => {
+                    self.update_own_document_with(id_or_path, content, options.clone()).await.map(Reply::Updated)
+                }
+                Next::Done(res) => return res,
+            };
+
+            next = op.next(reply);
+        }
+    }
+
+    /// Upload a json document
+    pub async fn upload_document(&self, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        self.upload_document_with(doc, RequestOptions::default()).await
+    }
+
+    /// Upload a json document with call options.
+    pub async fn upload_document_with(&self, doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        trace::instrument_operation("upload_document", async move {
+            match read_upload_file(doc).await {
+                Ok(content) => self.create_document_with(content, options).await,
+                Err(err) => Err(err),
+            }
+        }).await
+    }
+
+    /// Upload a json document read from an async reader, e.g. a `tokio::fs::File`.
+    /// The reader is read before the request is sent.
+    pub async fn upload_from_reader<R: AsyncRead + Unpin>(&self, reader: R, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        self.upload_from_reader_with(reader, doc, RequestOptions::default()).await
+    }
+
+    /// Upload a json document read from an async reader with call options.
+    pub async fn upload_from_reader_with<R: AsyncRead + Unpin>(&self, reader: R, doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        trace::instrument_operation("upload_from_reader", async move {
+            match read_upload_async_reader(reader, doc).await {
+                Ok(content) => self.create_document_with(content, options).await,
+                Err(err) => Err(err),
+            }
+        }).await
+    }
+
+    /// Upload a json document from a byte buffer
+    pub async fn upload_bytes(&self, bytes: &[u8], doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        self.upload_bytes_with(bytes, doc, RequestOptions::default()).await
+    }
+
+    /// Upload a json document from a byte buffer with call options.
+    pub async fn upload_bytes_with(&self, bytes: &[u8], doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        trace::instrument_operation("upload_bytes", async move {
+            match read_upload_bytes(bytes, doc) {
+                Ok(content) => self.create_document_with(content, options).await,
+                Err(err) => Err(err),
+            }
+        }).await
+    }
+
+    /// Delete a document
+    pub async fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError> {
+        self.delete_document_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Delete a document with call options.
+    pub async fn delete_document_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DeletedDocument, JsbError> {
+        let res = self.send_request::<DeletedDocument>(self.requests_with(options).delete_document(id_or_path)).await;
+        self.invalidate(id_or_path);
+
+        operations::deleted(res)
+    }
+
+    /// Create a folder
+    pub async fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError> {
+        self.create_folder_with(data, RequestOptions::default()).await
+    }
+
+    /// Create a folder with call options.
+    pub async fn create_folder_with(&self, data: CreateFolderBody, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.send_request(self.requests_with(options).create_folder(data)).await
+    }
+
+    /// Get a folder
+    pub async fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        self.get_folder_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get a folder with call options.
+    pub async fn get_folder_with(&self, id_or_path: &str, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.send_request(self.requests_with(options).get_folder(id_or_path, false)).await
+    }
+
+    /// Get a folder with statistics count
+    pub async fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        self.get_folder_with_stats_with(id_or_path, RequestOptions::default()).await
+    }
+
+    /// Get a folder with statistics count with call options.
+    pub async fn get_folder_with_stats_with(&self, id_or_path: &str, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.send_request(self.requests_with(options).get_folder(id_or_path, true)).await
+    }
+
+    /// Create a folder if it does not exist
+    pub async fn create_folder_if_not_exists(&self, data: CreateFolderBody) -> Result<(Folder, bool), JsbError> {
+        self.create_folder_if_not_exists_with(data, RequestOptions::default()).await
+    }
+
+    /// Create a folder if it does not exist with call options.
+    pub async fn create_folder_if_not_exists_with(&self, data: CreateFolderBody, options: RequestOptions) -> Result<(Folder, bool), JsbError> {
+        trace::instrument_operation("create_folder_if_not_exists", async move {
+            let res = self.create_folder_with(data.clone(), options.clone()).await;
+
+            match operations::if_not_exists(res, || make_folder_path(&data)) {
+                IfNotExists::Done(res) => res.map(|folder| (folder, false)),
+                IfNotExists::Read(folder_path) => Ok((self.get_folder_with(folder_path.as_str(), options).await?, true)),
+            }
+        }).await
+    }
+
+    /// Create every missing folder of a `project/a/b/c` path, like `mkdir -p`
+    pub async fn ensure_folder_path(&self, path: &str) -> Result<EnsuredFolder, JsbError> {
+        self.ensure_folder_path_with(path, RequestOptions::default()).await
+    }
+
+    /// Create every missing folder of a `project/a/b/c` path with call options, they apply to every request.
+    pub async fn ensure_folder_path_with(&self, path: &str, options: RequestOptions) -> Result<EnsuredFolder, JsbError> {
+        trace::instrument_operation("ensure_folder_path", async move {
+            let mut folders = FolderPath::new(path)?;
+
+            while let Some(data) = folders.next_folder() {
+                let (folder, exists) = self.create_folder_if_not_exists_with(data, options.clone()).await?;
+                folders.add(folder, exists);
+            }
+
+            folders.finish()
+        }).await
+    }
+}
+
+// read_upload_file - `read_upload_document` with `tokio::fs`, the file is read without blocking the runtime
+async fn read_upload_file(doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // project is required
+    if doc.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    let file_path = std::path::PathBuf::from(&doc.file_path);
+    match tokio::fs::metadata(&file_path).await {
+        Ok(meta) if meta.is_file() => {}
+        _ => return Err(err_file_not_found(&file_path)),
+    }
+
+    let bytes = tokio::fs::read(&file_path).await?;
+    read_upload_bytes(&bytes, doc)
+}
+
+// read_upload_async_reader - `read_upload_reader` for an async reader
+async fn read_upload_async_reader<R: AsyncRead + Unpin>(mut reader: R, doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // project is required
+    if doc.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    read_upload_bytes(&bytes, doc)
+}
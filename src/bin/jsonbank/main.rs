@@ -0,0 +1,400 @@
+//! The `jsonbank` command line tool, built with the `cli` feature.
+//!
+//! Keys are read from `--public-key`/`--private-key`, then from the `JSB_PUBLIC_KEY`/`JSB_PRIVATE_KEY`
+//! environment variables, then from a profile of `~/.jsonbank/profiles.json`.
+mod profile;
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use jsonbank::{JsonBank, JsonBankBuilder, JsonValue, ENV_HOST, ENV_PRIVATE_KEY, ENV_PUBLIC_KEY};
+use jsonbank::error::{err_required, ErrorKind, JsbError};
+use jsonbank::structs::{CreateFolderBody, DocumentMeta, Folder, SyncOptions, UploadDocumentBody};
+use profile::load_profile;
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0   success
+  1   other error
+  2   invalid arguments
+  3   document, folder or json pointer not found
+  4   document or folder already exists
+  5   missing or invalid keys
+  6   invalid json or request
+  7   invalid configuration or profile
+  8   local file error
+  9   connection error
+  10  rate limited
+  11  server error
+  12  invalid response
+  13  document changed by another client
+  14  not supported by the api";
+
+#[derive(Parser)]
+#[command(name = "jsonbank", version, about = "Read and edit JsonBank documents", after_help = EXIT_CODES)]
+struct Cli {
+    /// Api host
+    #[arg(long, global = true, env = ENV_HOST)]
+    host: Option<String>,
+
+    /// Public key, used to read documents
+    #[arg(long, global = true, env = ENV_PUBLIC_KEY, hide_env_values = true)]
+    public_key: Option<String>,
+
+    /// Private key, used to write documents
+    #[arg(long, global = true, env = ENV_PRIVATE_KEY, hide_env_values = true)]
+    private_key: Option<String>,
+
+    /// Profile to read the host and keys from
+    #[arg(long, global = true, env = "JSB_PROFILE")]
+    profile: Option<String>,
+
+    /// Profile file [default: ~/.jsonbank/profiles.json]
+    #[arg(long, global = true, env = "JSB_PROFILE_FILE")]
+    profile_file: Option<PathBuf>,
+
+    /// Print json instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the content of a document
+    Get {
+        /// Id or `project/path` of the document
+        path: String,
+        /// Read a document of a public project, no keys needed
+        #[arg(long)]
+        public: bool,
+        /// Print only the node at this JSON Pointer, e.g. `/features/checkout`
+        #[arg(long)]
+        pointer: Option<String>,
+    },
+    /// Print the meta of a document
+    Meta {
+        /// Id or `project/path` of the document
+        path: String,
+        /// Read a document of a public project, no keys needed
+        #[arg(long)]
+        public: bool,
+    },
+    /// Create a document, the content is read from stdin when --content is not set
+    Create {
+        /// `project/folder/name.json` of the new document
+        path: String,
+        /// Json content
+        #[arg(long)]
+        content: Option<String>,
+    },
+    /// Replace the content of a document, the content is read from stdin when --content and --file are not set
+    Update {
+        /// Id or `project/path` of the document
+        path: String,
+        /// Json content
+        #[arg(long, conflicts_with = "file")]
+        content: Option<String>,
+        /// Json file
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Upload a json file
+    Upload {
+        /// The file to upload
+        file: PathBuf,
+        /// The project to upload to
+        project: String,
+        /// Name of the document [default: the file name]
+        #[arg(long)]
+        name: Option<String>,
+        /// Folder of the document
+        #[arg(long)]
+        folder: Option<String>,
+    },
+    /// Delete a document
+    Delete {
+        /// Id or `project/path` of the document
+        path: String,
+    },
+    /// Create a folder
+    Mkdir {
+        /// `project/path` of the folder
+        path: String,
+        /// Create the missing parent folders, no error if the folder exists
+        #[arg(short, long)]
+        parents: bool,
+    },
+    /// Print a folder
+    Folder {
+        /// Id or `project/path` of the folder
+        path: String,
+        /// Include the number of documents and folders
+        #[arg(long)]
+        stats: bool,
+    },
+    /// Print the user and the projects of the keys
+    Whoami,
+    /// Upload the json files of a directory to a project, remote documents are never deleted
+    Sync {
+        /// Local directory
+        dir: PathBuf,
+        /// The project to sync to
+        project: String,
+        /// Print the plan without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(&cli) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            print_error(cli.json, &err);
+            ExitCode::from(exit_code(err.kind))
+        }
+    }
+}
+
+// run - runs a command and prints its result
+fn run(cli: &Cli) -> Result<(), JsbError> {
+    let mut jsb = client(cli)?;
+
+    match &cli.command {
+        Command::Get { path, public, pointer } => {
+            let content = match (pointer, *public) {
+                (Some(pointer), true) => jsb.get_content_at::<JsonValue>(path, pointer)?.to_string(),
+                (Some(pointer), false) => jsb.get_own_content_at::<JsonValue>(path, pointer)?.to_string(),
+                (None, true) => jsb.get_content_as_string(path)?,
+                (None, false) => jsb.get_own_content_as_string(path)?,
+            };
+
+            if cli.json {
+                println!("{}", content);
+            } else {
+                // pretty print, the content is printed as is if it is not json
+                match serde_json::from_str::<JsonValue>(&content) {
+                    Ok(value) => println!("{}", serde_json::to_string_pretty(&value)?),
+                    Err(_) => println!("{}", content),
+                }
+            }
+        }
+        Command::Meta { path, public } => {
+            let meta = if *public {
+                jsb.get_document_meta(path)?
+            } else {
+                jsb.get_own_document_meta(path)?
+            };
+
+            print(cli.json, &meta, || meta_text(&meta))?;
+        }
+        Command::Create { path, content } => {
+            let (project, folder, name) = split_document_path(path)?;
+            let doc = UploadDocumentBody {
+                file_path: String::new(),
+                project,
+                name: Some(name),
+                folder,
+            };
+
+            let res = match content {
+                Some(content) => jsb.upload_bytes(content.as_bytes(), doc)?,
+                None => jsb.upload_from_reader(io::stdin().lock(), doc)?,
+            };
+
+            print(cli.json, &res, || format!("created {}/{} ({})", res.project, res.path, res.id))?;
+        }
+        Command::Update { path, content, file } => {
+            let content = match (content, file) {
+                (Some(content), _) => content.clone(),
+                (None, Some(file)) => std::fs::read_to_string(file)?,
+                (None, None) => {
+                    let mut content = String::new();
+                    io::stdin().read_to_string(&mut content)?;
+                    content
+                }
+            };
+
+            let res = jsb.update_own_document(path, content)?;
+            print(cli.json, &res, || {
+                if res.changed {
+                    format!("updated {}", path)
+                } else {
+                    format!("unchanged {}", path)
+                }
+            })?;
+        }
+        Command::Upload { file, project, name, folder } => {
+            let res = jsb.upload_document(UploadDocumentBody {
+                file_path: file.to_string_lossy().to_string(),
+                project: project.clone(),
+                name: name.clone(),
+                folder: folder.clone(),
+            })?;
+
+            print(cli.json, &res, || format!("created {}/{} ({})", res.project, res.path, res.id))?;
+        }
+        Command::Delete { path } => {
+            let res = jsb.delete_document(path)?;
+            if !res.deleted {
+                return Err(JsbError::new(ErrorKind::NotFound, "notFound", "Document not found"));
+            }
+
+            print(cli.json, &res, || format!("deleted {}", path))?;
+        }
+        Command::Mkdir { path, parents } => {
+            if *parents {
+                let res = jsb.ensure_folder_path(path)?;
+                print(cli.json, &res, || {
+                    res.created.iter()
+                        .map(|path| format!("created {}/{}", res.folder.project, path))
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                })?;
+            } else {
+                let (project, parent, name) = split_document_path(path)?;
+                let folder = jsb.create_folder(CreateFolderBody { name, project, folder: parent })?;
+                print(cli.json, &folder, || format!("created {}/{}", folder.project, folder.path))?;
+            }
+        }
+        Command::Folder { path, stats } => {
+            let folder = if *stats {
+                jsb.get_folder_with_stats(path)?
+            } else {
+                jsb.get_folder(path)?
+            };
+
+            print(cli.json, &folder, || folder_text(&folder))?;
+        }
+        Command::Whoami => {
+            let data = jsb.authenticate()?;
+            print(cli.json, &data, || {
+                format!("{} (key: {})\nprojects: {}", data.username, data.api_key.title, data.api_key.projects.join(", "))
+            })?;
+        }
+        Command::Sync { dir, project, dry_run } => {
+            let report = jsb.sync_directory(dir, project, SyncOptions { dry_run: *dry_run, ..Default::default() })?;
+            print(cli.json, &report, || report.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+// client - builds the client from the flags, the environment and the profile, in that order
+fn client(cli: &Cli) -> Result<JsonBank, JsbError> {
+    let profile = load_profile(cli.profile_file.clone(), cli.profile.as_deref())?;
+    let mut builder = JsonBankBuilder::new();
+
+    if let Some(host) = first(&cli.host, &profile.host) {
+        builder = builder.host(host);
+    }
+    if let Some(key) = first(&cli.public_key, &profile.public_key) {
+        builder = builder.public_key(key);
+    }
+    if let Some(key) = first(&cli.private_key, &profile.private_key) {
+        builder = builder.private_key(key);
+    }
+
+    builder.build()
+}
+
+// first - the first value that is set and not empty
+fn first<'a>(value: &'a Option<String>, fallback: &'a Option<String>) -> Option<&'a str> {
+    [value, fallback].into_iter()
+        .flatten()
+        .map(|value| value.trim())
+        .find(|value| !value.is_empty())
+}
+
+// split_document_path - splits `project/folder/name` into the project, the folder and the name
+fn split_document_path(path: &str) -> Result<(String, Option<String>, String), JsbError> {
+    let path = path.trim_matches('/');
+
+    let (project, rest) = match path.split_once('/') {
+        Some((project, rest)) if !project.is_empty() && !rest.is_empty() => (project, rest),
+        _ => return Err(err_required("Path in the form project/name")),
+    };
+
+    match rest.rsplit_once('/') {
+        Some((folder, name)) => Ok((project.to_string(), Some(folder.to_string()), name.to_string())),
+        None => Ok((project.to_string(), None, rest.to_string())),
+    }
+}
+
+// print - prints a value as json or as text
+fn print<T: Serialize>(json: bool, value: &T, text: impl FnOnce() -> String) -> Result<(), JsbError> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        let text = text();
+        if !text.is_empty() {
+            println!("{}", text);
+        }
+    }
+
+    Ok(())
+}
+
+fn meta_text(meta: &DocumentMeta) -> String {
+    format!(
+        "id: {}\nproject: {}\npath: {}\nsize: {}\ncreated: {}\nupdated: {}",
+        meta.id, meta.project, meta.path, meta.content_size.string, meta.created_at, meta.updated_at,
+    )
+}
+
+fn folder_text(folder: &Folder) -> String {
+    let mut text = format!(
+        "id: {}\nproject: {}\npath: {}\ncreated: {}\nupdated: {}",
+        folder.id, folder.project, folder.path, folder.created_at, folder.updated_at,
+    );
+
+    if let Some(stats) = &folder.stats {
+        text.push_str(&format!("\ndocuments: {}\nfolders: {}", stats.documents, stats.folders));
+    }
+
+    text
+}
+
+// print_error - prints an error to stderr
+fn print_error(json: bool, err: &JsbError) {
+    if json {
+        let body = serde_json::json!({
+            "error": {
+                "kind": format!("{:?}", err.kind),
+                "code": err.code,
+                "message": err.message,
+                "status": err.status,
+            }
+        });
+        eprintln!("{}", body);
+    } else {
+        eprintln!("error: {}", err);
+    }
+}
+
+// exit_code - the exit code of an error kind, see EXIT_CODES
+fn exit_code(kind: ErrorKind) -> u8 {
+    match kind {
+        ErrorKind::NotFound | ErrorKind::PathNotFound => 3,
+        ErrorKind::AlreadyExists => 4,
+        ErrorKind::Unauthorized | ErrorKind::MissingKey | ErrorKind::NotAuthenticated => 5,
+        ErrorKind::InvalidJson | ErrorKind::Validation => 6,
+        ErrorKind::Config => 7,
+        ErrorKind::Io => 8,
+        ErrorKind::Transport => 9,
+        ErrorKind::RateLimited => 10,
+        ErrorKind::Server => 11,
+        ErrorKind::Decode => 12,
+        ErrorKind::Conflict => 13,
+        ErrorKind::Unsupported => 14,
+        _ => 1,
+    }
+}
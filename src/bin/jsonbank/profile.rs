@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::Deserialize;
+use jsonbank::error::{err_invalid_config, JsbError};
+
+/// The profile used when `--profile` is not set.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The host and keys of a profile.
+///
+/// The profile file is a json object of profiles by name:
+/// ```json
+/// {
+///   "default": { "publicKey": "...", "privateKey": "..." },
+///   "staging": { "host": "https://staging.example.com", "publicKey": "..." }
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub host: Option<String>,
+    pub public_key: Option<String>,
+    pub private_key: Option<String>,
+}
+
+/// The default location of the profile file, `~/.jsonbank/profiles.json`
+pub fn default_profile_file() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".jsonbank").join("profiles.json"))
+}
+
+/// Loads a profile.
+/// A missing file or a missing `default` profile is not an error, a missing named profile is.
+pub fn load_profile(file: Option<PathBuf>, name: Option<&str>) -> Result<Profile, JsbError> {
+    let file = match file.or_else(default_profile_file) {
+        Some(file) => file,
+        None => return Ok(Profile::default()),
+    };
+
+    let content = match fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound && name.is_none() => return Ok(Profile::default()),
+        Err(err) => return Err(JsbError::from(err)),
+    };
+
+    let mut profiles: HashMap<String, Profile> = match serde_json::from_str(&content) {
+        Ok(profiles) => profiles,
+        Err(err) => {
+            return Err(err_invalid_config(&format!("Profile file {} is invalid: {}", file.display(), err)));
+        }
+    };
+
+    match name {
+        Some(name) => match profiles.remove(name) {
+            Some(profile) => Ok(profile),
+            None => Err(err_invalid_config(&format!("Profile {} not found in {}", name, file.display()))),
+        },
+        None => Ok(profiles.remove(DEFAULT_PROFILE).unwrap_or_default()),
+    }
+}
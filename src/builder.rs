@@ -0,0 +1,252 @@
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::Url;
+use crate::{CacheConfig, InitConfig, JsonBank, Keys, PoolConfig, RetryPolicy, TlsBackend, TlsConfig};
+use crate::error::*;
+use crate::metrics::MetricsSink;
+use crate::middleware::Middleware;
+#[cfg(feature = "async")]
+use crate::AsyncJsonBank;
+
+/// Environment variable of the host, optional
+pub const ENV_HOST: &str = "JSB_HOST";
+/// Environment variable of the public key, required by [from_env](struct.JsonBankBuilder.html#method.from_env)
+pub const ENV_PUBLIC_KEY: &str = "JSB_PUBLIC_KEY";
+/// Environment variable of the private key, optional
+pub const ENV_PRIVATE_KEY: &str = "JSB_PRIVATE_KEY";
+
+/// Chainable builder of [JsonBank](struct.JsonBank.html) instances.
+///
+/// Values are only checked in [build](#method.build), which returns an error
+/// instead of panicking when a key, the host, the proxy url or a root certificate is malformed.
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// use jsonbank::{JsonBank, RetryPolicy};
+///
+/// let jsb = JsonBank::builder()
+///     .keys("your_public_key", "your_private_key")
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-app/1.0")
+///     .retry(RetryPolicy::none())
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct JsonBankBuilder {
+    config: InitConfig,
+    // only the builder sets the proxy and the TLS settings, they can make building the http client fail
+    proxy: Option<String>,
+    tls: TlsConfig,
+}
+
+impl JsonBankBuilder {
+    /// Create a builder with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder from the `JSB_HOST`, `JSB_PUBLIC_KEY` and `JSB_PRIVATE_KEY` environment variables.
+    ///
+    /// `JSB_PUBLIC_KEY` is required, `JSB_HOST` and `JSB_PRIVATE_KEY` are optional.
+    /// Empty variables are treated as not set.
+    /// Returns an error with kind [MissingKey](error/enum.ErrorKind.html#variant.MissingKey) when the public key is not set
+    /// and [Config](error/enum.ErrorKind.html#variant.Config) when a value is malformed.
+    pub fn from_env() -> Result<Self, JsbError> {
+        let mut builder = Self::new();
+
+        if let Some(host) = read_env(ENV_HOST) {
+            validate_host(ENV_HOST, &host)?;
+            builder = builder.host(&host);
+        }
+
+        match read_env(ENV_PUBLIC_KEY) {
+            Some(key) => {
+                validate_key(ENV_PUBLIC_KEY, &key)?;
+                builder = builder.public_key(&key);
+            }
+            None => {
+                return Err(JsbError::new(ErrorKind::MissingKey, "bad_request", &format!("{} is not set", ENV_PUBLIC_KEY)));
+            }
+        }
+
+        if let Some(key) = read_env(ENV_PRIVATE_KEY) {
+            validate_key(ENV_PRIVATE_KEY, &key)?;
+            builder = builder.private_key(&key);
+        }
+
+        Ok(builder)
+    }
+
+    /// Set the host, e.g. `https://api.jsonbank.io`
+    pub fn host(mut self, host: &str) -> Self {
+        self.config.host = Some(host.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Set both api keys
+    pub fn keys(self, public: &str, private: &str) -> Self {
+        self.public_key(public).private_key(private)
+    }
+
+    /// Set the public key, used to read documents
+    pub fn public_key(mut self, key: &str) -> Self {
+        let keys = self.config.keys.get_or_insert(Keys { public: None, private: None });
+        keys.public = Some(key.to_string());
+        self
+    }
+
+    /// Set the private key, used to write documents
+    pub fn private_key(mut self, key: &str) -> Self {
+        let keys = self.config.keys.get_or_insert(Keys { public: None, private: None });
+        keys.private = Some(key.to_string());
+        self
+    }
+
+    /// Set the total timeout of a request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of the connect phase
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.config.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Send all requests through a proxy, e.g. `http://127.0.0.1:8080`
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Set the TLS settings
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Set the TLS implementation, see [TlsBackend](enum.TlsBackend.html)
+    pub fn tls_backend(mut self, backend: TlsBackend) -> Self {
+        self.tls.backend = backend;
+        self
+    }
+
+    /// Trust the PEM encoded root certificate(s) besides the built-in ones, e.g. the CA of a TLS-intercepting gateway
+    pub fn add_root_certificate(mut self, pem: &str) -> Self {
+        self.tls.root_certificates.push(pem.to_string());
+        self
+    }
+
+    /// Set the retry policy
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.config.retry = Some(retry);
+        self
+    }
+
+    /// Cache the content of documents in memory
+    pub fn cache(mut self, cache: CacheConfig) -> Self {
+        self.config.cache = Some(cache);
+        self
+    }
+
+    /// Set the connection pool settings
+    pub fn pool(mut self, pool: PoolConfig) -> Self {
+        self.config.pool = Some(pool);
+        self
+    }
+
+    /// Pass the metrics of every request to a sink, see [metrics](metrics/index.html)
+    pub fn metrics<S: MetricsSink + 'static>(mut self, sink: S) -> Self {
+        self.config.metrics = Some(Arc::new(sink));
+        self
+    }
+
+    /// Add a middleware to the end of the chain every request passes, see [middleware](middleware/index.html)
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.config.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    // validate - checks every value that would otherwise fail when sending a request
+    fn validate(&self) -> Result<(), JsbError> {
+        if let Some(host) = &self.config.host {
+            validate_host("Host", host)?;
+        }
+
+        if let Some(keys) = &self.config.keys {
+            if let Some(key) = &keys.public {
+                validate_key("Public key", key)?;
+            }
+
+            if let Some(key) = &keys.private {
+                validate_key("Private key", key)?;
+            }
+        }
+
+        if let Some(user_agent) = &self.config.user_agent {
+            if reqwest::header::HeaderValue::from_str(user_agent).is_err() {
+                return Err(err_invalid_config("User agent contains invalid characters"));
+            }
+        }
+
+        if let Some(proxy) = &self.proxy {
+            if let Err(err) = reqwest::Proxy::all(proxy.as_str()) {
+                // the url is not part of the message, it may contain credentials
+                return Err(err_invalid_config("Proxy url is invalid").with_source(err));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a [JsonBank](struct.JsonBank.html) instance
+    pub fn build(self) -> Result<JsonBank, JsbError> {
+        self.validate()?;
+        JsonBank::try_new(self.config, self.proxy, self.tls)
+    }
+
+    /// Build an [AsyncJsonBank](struct.AsyncJsonBank.html) instance. Only available with the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn build_async(self) -> Result<AsyncJsonBank, JsbError> {
+        self.validate()?;
+        AsyncJsonBank::try_new(self.config, self.proxy, self.tls)
+    }
+}
+
+// read_env - reads an environment variable, empty values are treated as not set
+fn read_env(name: &str) -> Option<String> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => Some(value.trim().to_string()),
+        _ => None,
+    }
+}
+
+// validate_host - the host must be an absolute http(s) url
+fn validate_host(name: &str, host: &str) -> Result<(), JsbError> {
+    match Url::parse(host) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(()),
+        Ok(_) => Err(err_invalid_config(&format!("{} must be an http or https url: {}", name, host))),
+        Err(err) => Err(err_invalid_config(&format!("{} is not a valid url: {}", name, host)).with_source(err)),
+    }
+}
+
+// validate_key - keys are sent as headers, so they must be visible ascii without whitespace
+fn validate_key(name: &str, key: &str) -> Result<(), JsbError> {
+    if key.is_empty() {
+        return Err(err_invalid_config(&format!("{} is empty", name)));
+    }
+
+    if !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(err_invalid_config(&format!("{} is malformed, it must not contain whitespace or non ascii characters", name)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::request::{Revalidated, Validators};
+
+/// Settings of the content cache.
+///
+/// Only the content returned by `get_content` and `get_own_content` (and their `_as_string` variants) is cached.
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// use jsonbank::{CacheConfig, JsonBank};
+///
+/// let jsb = JsonBank::builder()
+///     .cache(CacheConfig {
+///         ttl: Duration::from_secs(30),
+///         max_entries: Some(100),
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// How long content is kept.
+    pub ttl: Duration,
+    /// Per document ttl by the id or path used to read it, overrides `ttl`.
+    /// A zero duration disables the cache for that document.
+    pub document_ttls: HashMap<String, Duration>,
+    /// Maximum number of cached documents, the least recently used one is evicted first.
+    pub max_entries: Option<usize>,
+    /// Maximum total size of the cached content in bytes, the least recently used documents are evicted first.
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            ttl: Duration::from_secs(60),
+            document_ttls: HashMap::new(),
+            max_entries: Some(1000),
+            max_bytes: None,
+        }
+    }
+}
+
+// Who a cached document was read as, public documents are read by `username/project/path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Scope {
+    Public,
+    Own,
+}
+
+// The result of a cache lookup
+pub(crate) enum Lookup {
+    /// The content is cached and not expired
+    Fresh(String),
+    /// The content must be read from the server. If it expired, it can be revalidated
+    /// with the `ETag` or `Last-Modified` of its response, the validators are empty otherwise.
+    /// The generation of the cache is passed back to `revalidated`
+    Send(Validators, u64),
+}
+
+// Aliases are forgotten when there are more, invalidation then falls back to evicting by the other form
+const MAX_ALIASES: usize = 10_000;
+
+struct Entry {
+    content: String,
+    expires_at: Instant,
+    validators: Validators,
+    // value of `Entries::clock` when the entry was last read or written
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<(Scope, String), Entry>,
+    // keys by `last_used`, the first one is the least recently used
+    order: BTreeMap<u64, (Scope, String)>,
+    bytes: usize,
+    clock: u64,
+    // the id of a `project/path` and the `project/path` of an id, learned from metas and created documents
+    aliases: HashMap<String, String>,
+    // bumped by every invalidation, content read before it is not cached
+    generation: u64,
+}
+
+impl Entries {
+    fn remove(&mut self, key: &(Scope, String)) -> bool {
+        match self.map.remove(key) {
+            Some(entry) => {
+                self.order.remove(&entry.last_used);
+                self.bytes -= entry.content.len();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // touch - marks an entry as the most recently used one
+    fn touch(&mut self, key: &(Scope, String)) {
+        self.clock += 1;
+        let clock = self.clock;
+
+        if let Some(entry) = self.map.get_mut(key) {
+            self.order.remove(&entry.last_used);
+            entry.last_used = clock;
+            self.order.insert(clock, key.clone());
+        }
+    }
+}
+
+/// In-memory cache of document content with a ttl and lru eviction, see [CacheConfig](struct.CacheConfig.html).
+///
+/// Entries are keyed by the id or path used to read them.
+/// Expired entries whose response had an `ETag` or `Last-Modified` header are revalidated,
+/// their content is reused when the server answers `304 Not Modified`.
+/// `update_own_document` and `delete_document` evict the entries of the document by its id and by its path,
+/// call [invalidate](#method.invalidate) after changing a document with another client.
+/// Content read while a document is invalidated is returned but not cached.
+pub struct ContentCache {
+    config: CacheConfig,
+    entries: Mutex<Entries>,
+}
+
+impl ContentCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        ContentCache { config, entries: Mutex::new(Entries::default()) }
+    }
+
+    /// The settings of the cache.
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// Remove the cached content of a document.
+    /// Public entries whose path ends with `id_or_path` are removed too,
+    /// so `project/config.json` also removes `username/project/config.json`.
+    ///
+    /// The entries of the document by its id and by its `project/path` are removed if the other one is known
+    /// from a meta read or a created document. Otherwise every entry of the other form whose document is not
+    /// known is removed, e.g. all entries read by id when `id_or_path` is a path.
+    pub fn invalidate(&self, id_or_path: &str) {
+        let key = normalize(id_or_path);
+
+        let mut entries = self.lock();
+        entries.generation += 1;
+
+        // the alias is learned again with the next meta, a new document may take the path
+        let alias = entries.aliases.remove(&key);
+        if let Some(alias) = &alias {
+            entries.aliases.remove(alias);
+        }
+
+        let keys: Vec<(Scope, String)> = entries.map.keys()
+            .filter(|(scope, path)| {
+                same_key(&key, *scope, path) || match &alias {
+                    Some(alias) => same_key(alias, *scope, path),
+                    None => is_path(path) != is_path(&key) && !entries.aliases.contains_key(path),
+                }
+            })
+            .cloned()
+            .collect();
+
+        for key in keys {
+            entries.remove(&key);
+        }
+    }
+
+    /// Remove every cached document.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.map.clear();
+        entries.order.clear();
+        entries.bytes = 0;
+        entries.generation += 1;
+    }
+
+    /// Number of cached documents, including expired ones that were not evicted yet.
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    /// Returns true if no document is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // get - returns the content of a document if it is cached and not expired
+    pub(crate) fn get(&self, scope: Scope, id_or_path: &str) -> Lookup {
+        let key = (scope, normalize(id_or_path));
+        let mut entries = self.lock();
+
+        let expired = match entries.map.get(&key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                let content = entry.content.clone();
+                entries.touch(&key);
+                return Lookup::Fresh(content);
+            }
+            // keep it until the server says if it changed
+            Some(entry) if !entry.validators.is_empty() => return Lookup::Send(entry.validators.clone(), entries.generation),
+            Some(_) => true,
+            None => false,
+        };
+
+        if expired {
+            entries.remove(&key);
+        }
+
+        Lookup::Send(Validators::default(), entries.generation)
+    }
+
+    // alias - records that `id` and `project/path` are the same document
+    pub(crate) fn alias(&self, id: &str, project: &str, path: &str) {
+        let (id, path) = (normalize(id), normalize(&format!("{}/{}", project, path)));
+        if id == path {
+            return;
+        }
+
+        let mut entries = self.lock();
+        if entries.aliases.len() >= MAX_ALIASES {
+            entries.aliases.clear();
+        }
+
+        entries.aliases.insert(id.clone(), path.clone());
+        entries.aliases.insert(path, id);
+    }
+
+    // refresh - restarts the ttl of an expired entry the server reported as not modified,
+    // unless the cache was invalidated since `generation`
+    fn refresh(&self, scope: Scope, id_or_path: &str, generation: u64) -> Option<String> {
+        let ttl = self.ttl(id_or_path);
+        let key = (scope, normalize(id_or_path));
+        let mut entries = self.lock();
+
+        if entries.generation != generation {
+            return None;
+        }
+
+        let content = match entries.map.get_mut(&key) {
+            Some(entry) => {
+                entry.expires_at = Instant::now() + ttl;
+                entry.content.clone()
+            }
+            None => return None,
+        };
+
+        entries.touch(&key);
+        Some(content)
+    }
+
+    // revalidated - caches the content of a response to the request of a lookup made at `generation` and returns it,
+    // or None if the server reported it as not modified but the entry was evicted or invalidated meanwhile
+    pub(crate) fn revalidated(&self, scope: Scope, id_or_path: &str, res: Revalidated, generation: u64) -> Option<String> {
+        match res {
+            Revalidated::Modified(content, validators) => {
+                self.insert(scope, id_or_path, &content, validators, generation);
+                Some(content)
+            }
+            Revalidated::NotModified => self.refresh(scope, id_or_path, generation),
+        }
+    }
+
+    // ttl - the ttl of a document
+    fn ttl(&self, id_or_path: &str) -> Duration {
+        match self.config.document_ttls.get(&normalize(id_or_path)).or_else(|| self.config.document_ttls.get(id_or_path)) {
+            Some(ttl) => *ttl,
+            None => self.config.ttl,
+        }
+    }
+
+    // insert - caches the content of a document, evicting the least recently used ones when a limit is reached.
+    // Nothing is cached if the cache was invalidated since `generation`, the content may be older than the update
+    fn insert(&self, scope: Scope, id_or_path: &str, content: &str, validators: Validators, generation: u64) {
+        let path = normalize(id_or_path);
+        let ttl = self.ttl(id_or_path);
+
+        // content larger than the whole budget is never cached
+        if ttl.is_zero() || self.config.max_entries == Some(0) || self.config.max_bytes.is_some_and(|max| content.len() > max) {
+            return;
+        }
+
+        let mut entries = self.lock();
+        if entries.generation != generation {
+            return;
+        }
+
+        let key = (scope, path);
+        entries.remove(&key);
+
+        entries.clock += 1;
+        let entry = Entry {
+            content: content.to_string(),
+            expires_at: Instant::now() + ttl,
+            validators,
+            last_used: entries.clock,
+        };
+        entries.bytes += entry.content.len();
+        entries.order.insert(entry.last_used, key.clone());
+        entries.map.insert(key, entry);
+
+        // evict the least recently used entries
+        loop {
+            let too_many = self.config.max_entries.is_some_and(|max| entries.map.len() > max);
+            let too_big = self.config.max_bytes.is_some_and(|max| entries.bytes > max);
+            if !too_many && !too_big {
+                break;
+            }
+
+            let oldest = entries.order.values().next().cloned();
+
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        // the entries are always left consistent, a poisoned lock is still usable
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+// same_key - whether the entry `path` of `scope` is the document `key`,
+// public documents are read by `username/project/path`
+fn same_key(key: &str, scope: Scope, path: &str) -> bool {
+    path == key || (scope == Scope::Public && path.strip_suffix(key).is_some_and(|prefix| prefix.ends_with('/')))
+}
+
+// is_path - ids have no slash, paths start with the project
+fn is_path(key: &str) -> bool {
+    key.contains('/')
+}
+
+// normalize - the key of an id or path, leading and trailing slashes are ignored
+fn normalize(id_or_path: &str) -> String {
+    id_or_path.trim_matches('/').to_string()
+}
@@ -0,0 +1,93 @@
+use std::vec::IntoIter;
+use crate::JsonBankApi;
+use crate::error::JsbError;
+use crate::structs::{ContentItem, ListOptions};
+
+// The listing followed by a ContentsIter
+enum Target {
+    Project(String),
+    Folder(String),
+}
+
+/// Iterates over every item of a project or folder, fetching the next page only when needed.
+///
+/// Returned by `iter_project_contents` and `iter_folder_contents`.
+/// A failed request is yielded as an error and ends the iteration.
+/// Items added or removed while iterating may be skipped or returned twice.
+/// # Example
+/// ```
+/// use jsonbank::JsonBankApi;
+/// use jsonbank::stores::MemoryStore;
+/// use jsonbank::structs::ListOptions;
+///
+/// let store = MemoryStore::new();
+/// store.add_project("sdk-test", false);
+/// for i in 0..5 {
+///     store.add_document("sdk-test", &format!("doc-{}.json", i), "{}").unwrap();
+/// }
+///
+/// // two items per request
+/// let options = ListOptions { per_page: 2, ..Default::default() };
+/// let names: Vec<String> = store.iter_project_contents("sdk-test", options)
+///     .map(|item| item.unwrap().name().to_string())
+///     .collect();
+///
+/// assert_eq!(names.len(), 5);
+/// ```
+pub struct ContentsIter<'a> {
+    api: &'a dyn JsonBankApi,
+    target: Target,
+    options: ListOptions,
+    items: IntoIter<ContentItem>,
+    done: bool,
+}
+
+impl<'a> ContentsIter<'a> {
+    /// Iterate over the root of a project, starting at `options.page`
+    pub fn project(api: &'a dyn JsonBankApi, project: &str, options: ListOptions) -> Self {
+        Self::new(api, Target::Project(project.to_string()), options)
+    }
+
+    /// Iterate over a folder, starting at `options.page`
+    pub fn folder(api: &'a dyn JsonBankApi, id_or_path: &str, options: ListOptions) -> Self {
+        Self::new(api, Target::Folder(id_or_path.to_string()), options)
+    }
+
+    fn new(api: &'a dyn JsonBankApi, target: Target, options: ListOptions) -> Self {
+        ContentsIter { api, target, options, items: Vec::new().into_iter(), done: false }
+    }
+}
+
+impl<'a> Iterator for ContentsIter<'a> {
+    type Item = Result<ContentItem, JsbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            // fetch the next page
+            let page = match &self.target {
+                Target::Project(project) => self.api.list_project_contents(project, self.options.clone()),
+                Target::Folder(id_or_path) => self.api.list_folder_contents(id_or_path, self.options.clone()),
+            };
+
+            match page {
+                Ok(page) => {
+                    self.done = !page.has_more();
+                    self.options.page += 1;
+                    self.items = page.items.into_iter();
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
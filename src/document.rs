@@ -0,0 +1,169 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::{trace, JsonBankApi};
+use crate::error::*;
+use crate::functions::document_body_from_path;
+use crate::request::decode_content;
+use crate::structs::*;
+
+/// A document owned by the authenticated user, bound to the type of its content.
+///
+/// Created with [JsonBank::document](struct.JsonBank.html#method.document).
+/// Content that does not match `T` fails with [ErrorKind::Decode](error/enum.ErrorKind.html#variant.Decode),
+/// a `T` that can not be serialized with [ErrorKind::Encode](error/enum.ErrorKind.html#variant.Encode).
+/// # Example
+/// ```no_run
+/// use serde::{Deserialize, Serialize};
+/// # use jsonbank::JsonBank;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct AppConfig {
+///     debug: bool,
+/// }
+///
+/// # let jsb = JsonBank::new_without_config();
+/// let config = jsb.document::<AppConfig>("sdk-test/config.json");
+///
+/// let mut app = config.get().unwrap();
+/// app.debug = false;
+/// config.set(&app).unwrap();
+/// ```
+pub struct Document<'a, T> {
+    api: &'a dyn JsonBankApi,
+    path: String,
+    content: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Debug for Document<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Document").field("path", &self.path).finish()
+    }
+}
+
+impl<'a, T: DeserializeOwned + Serialize> Document<'a, T> {
+    pub(crate) fn new(api: &'a dyn JsonBankApi, path: &str) -> Self {
+        Document { api, path: path.to_string(), content: PhantomData }
+    }
+
+    /// The id or `project/path` of the document.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the content of the document.
+    pub fn get(&self) -> Result<T, JsbError> {
+        match self.api.get_own_content_as_string(&self.path) {
+            Ok(content) => decode_content(&content),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Replace the content of the document, the document is created if it does not exist.
+    /// Only a document bound by its `project/path` can be created.
+    pub fn set(&self, value: &T) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("document_set");
+
+        let content = serde_json::to_string(value).map_err(err_encode)?;
+
+        match self.api.update_own_document(&self.path, content.clone()) {
+            Err(err) if err.kind == ErrorKind::NotFound => {
+                let doc = self.api.create_document_if_not_exists(document_body_from_path(&self.path, content.clone())?)?;
+
+                // created by someone else meanwhile
+                if doc.exists {
+                    self.api.update_own_document(&self.path, content)
+                } else {
+                    Ok(UpdatedDocument { changed: true })
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Get the meta of the document.
+    pub fn meta(&self) -> Result<DocumentMeta, JsbError> {
+        self.api.get_own_document_meta(&self.path)
+    }
+
+    /// Check if the document exists.
+    pub fn exists(&self) -> Result<bool, JsbError> {
+        self.api.has_own_document(&self.path)
+    }
+
+    /// Delete the document.
+    pub fn delete(&self) -> Result<DeletedDocument, JsbError> {
+        self.api.delete_document(&self.path)
+    }
+}
+
+/// A document bound to the type of its content, see [Document](struct.Document.html).
+///
+/// Created with [AsyncJsonBank::document](struct.AsyncJsonBank.html#method.document).
+#[cfg(feature = "async")]
+pub struct AsyncDocument<'a, T> {
+    jsb: &'a crate::AsyncJsonBank,
+    path: String,
+    content: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "async")]
+impl<'a, T> Debug for AsyncDocument<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncDocument").field("path", &self.path).finish()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: DeserializeOwned + Serialize> AsyncDocument<'a, T> {
+    pub(crate) fn new(jsb: &'a crate::AsyncJsonBank, path: &str) -> Self {
+        AsyncDocument { jsb, path: path.to_string(), content: PhantomData }
+    }
+
+    /// The id or `project/path` of the document.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the content of the document.
+    pub async fn get(&self) -> Result<T, JsbError> {
+        self.jsb.get_own_content(&self.path).await
+    }
+
+    /// Replace the content of the document, the document is created if it does not exist.
+    pub async fn set(&self, value: &T) -> Result<UpdatedDocument, JsbError> {
+        trace::instrument_operation("document_set", async move {
+            let content = serde_json::to_string(value).map_err(err_encode)?;
+
+            match self.jsb.update_own_document(&self.path, content.clone()).await {
+                Err(err) if err.kind == ErrorKind::NotFound => {
+                    let doc = self.jsb.create_document_if_not_exists(document_body_from_path(&self.path, content.clone())?).await?;
+
+                    // created by someone else meanwhile
+                    if doc.exists {
+                        self.jsb.update_own_document(&self.path, content).await
+                    } else {
+                        Ok(UpdatedDocument { changed: true })
+                    }
+                }
+                res => res,
+            }
+        }).await
+    }
+
+    /// Get the meta of the document.
+    pub async fn meta(&self) -> Result<DocumentMeta, JsbError> {
+        self.jsb.get_own_document_meta(&self.path).await
+    }
+
+    /// Check if the document exists.
+    pub async fn exists(&self) -> Result<bool, JsbError> {
+        self.jsb.has_own_document(&self.path).await
+    }
+
+    /// Delete the document.
+    pub async fn delete(&self) -> Result<DeletedDocument, JsbError> {
+        self.jsb.delete_document(&self.path).await
+    }
+}
@@ -1,12 +1,63 @@
-use std::{fmt::Display, fmt::Formatter, error::Error, any::Any};
+use std::{fmt::Display, fmt::Formatter, error::Error};
+
+/// The kind of a [JsbError](struct.JsbError.html).
+///
+/// Match on the kind instead of comparing the raw `code` of an error.
+/// New kinds may be added in future releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The document or folder does not exist.
+    NotFound,
+    /// The JSON Pointer does not match a node of the document, see `get_own_content_at`.
+    PathNotFound,
+    /// A document or folder with the same name already exists.
+    AlreadyExists,
+    /// The document changed since it was read, see `update_own_document_after_check`.
+    Conflict,
+    /// The api keys are invalid or have no access to the resource.
+    Unauthorized,
+    /// A public or private key required by the request is not set.
+    MissingKey,
+    /// The content is not a valid json string.
+    InvalidJson,
+    /// The request is invalid, e.g. a required field is empty.
+    Validation,
+    /// [authenticate](../struct.JsonBank.html#method.authenticate) has not been called.
+    NotAuthenticated,
+    /// The client configuration is invalid, e.g. a malformed key, host or proxy url.
+    Config,
+    /// A local file could not be found or read.
+    Io,
+    /// The request could not be sent or the response could not be read.
+    Transport,
+    /// The response could not be decoded.
+    Decode,
+    /// A value could not be serialized to json.
+    Encode,
+    /// Too many requests were sent.
+    RateLimited,
+    /// The server failed to handle the request.
+    Server,
+    /// The backend does not support the operation, e.g. listing contents over http.
+    Unsupported,
+    /// Any other error.
+    Other,
+}
 
 /// JsonBank Error Struct
 #[derive(Debug)]
 pub struct JsbError {
-    /// The code of the error
+    /// The kind of the error
+    pub kind: ErrorKind,
+    /// The code of the error, as returned by the server when the error came from the api
     pub code: String,
     /// The message of the error
     pub message: String,
+    /// The http status of the response, if the error came from the api
+    pub status: Option<u16>,
+    // The underlying error
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
 }
 
 impl Display for JsbError {
@@ -15,40 +66,96 @@ impl Display for JsbError {
     }
 }
 
-impl Error for JsbError {}
+impl Error for JsbError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.source {
+            Some(err) => Some(err.as_ref()),
+            None => None,
+        }
+    }
+}
 
 impl JsbError {
-    /// convert any error to jsb error.
-    /// If the error is already a jsb error, it will be returned as is.
-    pub fn from_any(err: &dyn Any, _code: Option<&str>) -> JsbError {
-        let mut code = _code.unwrap_or("500");
-
-        if let Some(err) = err.downcast_ref::<JsbError>() {
-            // if _code is not provided, use the code from the error
-            if _code.is_none() {
-                code = &err.code;
-            }
-
-            JsbError {
-                code: code.to_string(),
-                message: err.message.to_string(),
-            }
-        } else if let Some(err) = err.downcast_ref::<reqwest::Error>() {
-            JsbError {
-                code: code.to_string(),
-                message: err.to_string(),
-            }
-        } else if let Some(err) = err.downcast_ref::<serde_json::Error>() {
-            JsbError {
-                code: code.to_string(),
-                message: err.to_string(),
-            }
+    /// Create a new error
+    pub fn new(kind: ErrorKind, code: &str, message: &str) -> JsbError {
+        JsbError {
+            kind,
+            code: code.to_string(),
+            message: message.to_string(),
+            status: None,
+            source: None,
+        }
+    }
+
+    /// Create an error from an api error response.
+    /// The kind is derived from the server code and the http status.
+    pub fn from_api(status: u16, code: &str, message: &str) -> JsbError {
+        let kind = match (code, status) {
+            ("notFound", _) | (_, 404) => ErrorKind::NotFound,
+            ("name.exists", _) | (_, 409) => ErrorKind::AlreadyExists,
+            (_, 412) => ErrorKind::Conflict,
+            ("invalid_json_content", _) => ErrorKind::InvalidJson,
+            (_, 401) | (_, 403) => ErrorKind::Unauthorized,
+            (_, 429) => ErrorKind::RateLimited,
+            (_, 400) | (_, 422) => ErrorKind::Validation,
+            (_, 500..=599) => ErrorKind::Server,
+            _ => ErrorKind::Other,
+        };
+
+        JsbError::new(kind, code, message).with_status(status)
+    }
+
+    /// Set the http status of the error
+    pub fn with_status(mut self, status: u16) -> JsbError {
+        self.status = Some(status);
+        self
+    }
+
+    /// Set the underlying error, returned by [source](#method.source)
+    pub fn with_source<E: Error + Send + Sync + 'static>(mut self, source: E) -> JsbError {
+        self.source = Some(Box::new(source));
+        self
+    }
+}
+
+impl From<reqwest::Error> for JsbError {
+    fn from(err: reqwest::Error) -> Self {
+        let kind = if err.is_decode() {
+            ErrorKind::Decode
         } else {
-            JsbError {
-                code: code.to_string(),
-                message: "Unknown error".to_string(),
-            }
+            ErrorKind::Transport
+        };
+
+        let mut jsb_err = JsbError::new(kind, "transport_error", &err.to_string());
+        if let Some(status) = err.status() {
+            jsb_err = jsb_err.with_status(status.as_u16());
         }
+
+        jsb_err.with_source(err)
+    }
+}
+
+impl From<serde_json::Error> for JsbError {
+    fn from(err: serde_json::Error) -> Self {
+        JsbError::new(ErrorKind::Decode, "decode_error", &err.to_string()).with_source(err)
+    }
+}
+
+impl From<json_patch::PatchError> for JsbError {
+    fn from(err: json_patch::PatchError) -> Self {
+        JsbError::new(ErrorKind::Validation, "invalid_patch", &err.to_string()).with_source(err)
+    }
+}
+
+impl From<std::io::Error> for JsbError {
+    fn from(err: std::io::Error) -> Self {
+        let code = if err.kind() == std::io::ErrorKind::NotFound {
+            "file_not_found"
+        } else {
+            "invalid_file"
+        };
+
+        JsbError::new(ErrorKind::Io, code, &err.to_string()).with_source(err)
     }
 }
 
@@ -64,8 +171,55 @@ pub struct ApiErrorResponse {
 
 /// Returns a JsbError with code "invalid_json_content"
 pub fn err_invalid_json() -> JsbError {
-    JsbError {
-        code: String::from("invalid_json_content"),
-        message: "Content is not a valid JSON string".to_string(),
-    }
-}
\ No newline at end of file
+    JsbError::new(ErrorKind::InvalidJson, "invalid_json_content", "Content is not a valid JSON string")
+}
+
+/// Returns a JsbError with code "bad_request" for a required field
+pub fn err_required(field: &str) -> JsbError {
+    JsbError::new(ErrorKind::Validation, "bad_request", &format!("{} required", field))
+}
+
+/// Returns a JsbError with code "bad_request" for a missing public or private key
+pub fn err_missing_key(key: &str) -> JsbError {
+    JsbError::new(ErrorKind::MissingKey, "bad_request", &format!("{} key is not set", key))
+}
+
+/// Returns a JsbError with code "invalid_config"
+pub fn err_invalid_config(message: &str) -> JsbError {
+    JsbError::new(ErrorKind::Config, "invalid_config", message)
+}
+
+/// Returns a JsbError with code "not_authenticated"
+pub fn err_not_authenticated() -> JsbError {
+    JsbError::new(ErrorKind::NotAuthenticated, "not_authenticated", "User is not authenticated")
+}
+
+/// Returns a JsbError with code "notFound", e.g. `err_not_found("Document")`
+pub fn err_not_found(what: &str) -> JsbError {
+    JsbError::new(ErrorKind::NotFound, "notFound", &format!("{} not found", what))
+}
+
+/// Returns a JsbError with code "path_not_found" for a JSON Pointer that does not match a node
+pub fn err_path_not_found(pointer: &str) -> JsbError {
+    JsbError::new(ErrorKind::PathNotFound, "path_not_found", &format!("Path {} not found in document", pointer))
+}
+
+/// Returns a JsbError with code "conflict" for a document that changed since it was read
+pub fn err_conflict(id_or_path: &str) -> JsbError {
+    JsbError::new(ErrorKind::Conflict, "conflict", &format!("Document {} was changed by another client", id_or_path))
+}
+
+/// Returns a JsbError with code "encode_error" for a value that could not be serialized
+pub fn err_encode(err: serde_json::Error) -> JsbError {
+    JsbError::new(ErrorKind::Encode, "encode_error", &err.to_string()).with_source(err)
+}
+
+/// Returns a JsbError with code "unsupported" for an operation the backend does not support
+pub fn err_unsupported(operation: &str) -> JsbError {
+    JsbError::new(ErrorKind::Unsupported, "unsupported", &format!("{} is not supported by this backend", operation))
+}
+
+/// Returns a JsbError with code "name.exists"
+pub fn err_name_exists() -> JsbError {
+    JsbError::new(ErrorKind::AlreadyExists, "name.exists", "A document or folder with this name already exists")
+}
@@ -1,53 +1,127 @@
-use crate::{ContentSize, DocumentMeta, JsonObject, JsonValue};
-use crate::structs::{CreateDocumentBody, CreateFolderBody, Folder, FolderStats};
-
-/// Converts a HashMap to a DocumentMeta struct
-pub fn json_object_to_document_meta(map: &JsonObject) -> DocumentMeta {
-    let size = map["contentSize"].as_object().unwrap();
-    // get optional folderId
-    let folder_id = if map.contains_key("folderId") {
-        Some(map["folderId"].as_str().unwrap().to_string())
-    } else {
-        None
-    };
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::JsonValue;
+use crate::error::*;
+use crate::structs::*;
+
+/// Converts the meta of an existing document to a NewDocument struct
+/// Used when `create_document_if_not_exists` finds the document already exists
+pub fn document_meta_to_new_document(meta: DocumentMeta, name: String) -> NewDocument {
+    NewDocument {
+        id: meta.id,
+        name,
+        path: meta.path,
+        project: meta.project,
+        created_at: meta.created_at,
+        exists: true,
+    }
+}
+
+/// Reads the file of an upload and converts it to a CreateDocumentBody.
+/// Relative paths are resolved from the current working directory.
+pub fn read_upload_document(doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // project is required
+    if doc.project.is_empty() {
+        return Err(err_required("Project"));
+    }
 
-    DocumentMeta {
-        id: map["id"].as_str().unwrap().to_string(),
-        project: map["project"].as_str().unwrap().to_string(),
-        name: map["name"].as_str().unwrap().to_string(),
-        path: map["path"].as_str().unwrap().to_string(),
-        content_size: ContentSize {
-            number: size["number"].as_u64().unwrap(),
-            string: size["string"].as_str().unwrap().to_string(),
+    let file_path = PathBuf::from(&doc.file_path);
+
+    // check if file exists using os
+    if !file_path.is_file() {
+        return Err(err_file_not_found(&file_path));
+    }
+
+    // open file
+    match fs::File::open(file_path) {
+        Ok(file) => read_upload_reader(file, doc),
+        Err(err) => Err(JsbError::from(err)),
+    }
+}
+
+/// The error of an upload whose file does not exist.
+pub(crate) fn err_file_not_found(path: &Path) -> JsbError {
+    JsbError::new(ErrorKind::Io, "file_not_found", &format!("File does not exist: {}", path.display()))
+}
+
+/// Reads the content of an upload from a reader and converts it to a CreateDocumentBody
+pub fn read_upload_reader<R: Read>(mut reader: R, doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // project is required
+    if doc.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    let mut content = String::new();
+    if let Err(err) = reader.read_to_string(&mut content) {
+        // content that is not utf-8 cannot be json
+        return if err.kind() == std::io::ErrorKind::InvalidData {
+            Err(err_invalid_json())
+        } else {
+            Err(JsbError::from(err))
+        };
+    }
+
+    upload_to_create_document(content, doc)
+}
+
+/// Converts the bytes of an upload to a CreateDocumentBody
+pub fn read_upload_bytes(bytes: &[u8], doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // project is required
+    if doc.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(content) => upload_to_create_document(content.to_string(), doc),
+        Err(_) => Err(err_invalid_json()),
+    }
+}
+
+// upload_to_create_document - validates the content of an upload and names it after the file if no name is set
+fn upload_to_create_document(content: String, doc: UploadDocumentBody) -> Result<CreateDocumentBody, JsbError> {
+    // check if content is valid json
+    if !is_valid_json(&content) {
+        return Err(err_invalid_json());
+    }
+
+    // set name if not set
+    let name = match doc.name {
+        Some(name) => name,
+        None => match Path::new(&doc.file_path).file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => return Err(err_required("Name")),
         },
-        folder_id,
-        updated_at: map["updatedAt"].as_str().unwrap().to_string(),
-        created_at: map["createdAt"].as_str().unwrap().to_string(),
-    }
-}
-
-/// Converts a HashMap to a Folder
-pub fn json_object_to_folder(map: &JsonObject) -> Folder {
-    // if stats exists, convert it to a FolderStats
-    let stats = if map.contains_key("stats") {
-        let stats_map = map["stats"].as_object().unwrap();
-        Some(FolderStats {
-            documents: stats_map["documents"].as_i64().unwrap() as i32,
-            folders: stats_map["folders"].as_i64().unwrap() as i32,
-        })
-    } else {
-        None
     };
 
-    Folder {
-        id: map["id"].as_str().unwrap().to_string(),
-        name: map["name"].as_str().unwrap().to_string(),
-        path: map["path"].as_str().unwrap().to_string(),
-        project: map["project"].as_str().unwrap().to_string(),
-        created_at: map["createdAt"].as_str().unwrap().to_string(),
-        updated_at: map["updatedAt"].as_str().unwrap().to_string(),
-        stats
-    }
+    Ok(CreateDocumentBody {
+        name,
+        project: doc.project,
+        content,
+        folder: doc.folder,
+    })
+}
+
+/// Converts a `project/folder/name` path to the body that creates the document.
+/// Fails for an id, which has no project.
+pub fn document_body_from_path(path: &str, content: String) -> Result<CreateDocumentBody, JsbError> {
+    let (project, rest) = match path.trim_matches('/').split_once('/') {
+        Some((project, rest)) if !project.is_empty() && !rest.is_empty() => (project, rest),
+        _ => return Err(JsbError::new(ErrorKind::Validation, "bad_request", "A document can only be created by its project/path")),
+    };
+
+    let (folder, name) = match rest.rsplit_once('/') {
+        Some((folder, name)) => (Some(folder.to_string()), name),
+        None => (None, rest),
+    };
+
+    Ok(CreateDocumentBody {
+        name: name.to_string(),
+        project: project.to_string(),
+        content,
+        folder,
+    })
 }
 
 /// checks if a string is valid JSON
@@ -55,14 +129,34 @@ pub fn is_valid_json(json: &str) -> bool {
     serde_json::from_str::<JsonValue>(json).is_ok()
 }
 
+/// Hash of a document content, compared by `update_own_document_after_check`.
+/// The hash is stable across runs and platforms, so it can be stored.
+/// # Example
+/// ```
+/// use jsonbank::content_hash;
+///
+/// assert_eq!(content_hash("{}"), content_hash("{}"));
+/// assert_ne!(content_hash("{}"), content_hash("[]"));
+/// ```
+pub fn content_hash(content: &str) -> String {
+    // 64 bit FNV-1a
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    format!("{:016x}", hash)
+}
+
 /// Generate a document full path.
 /// If the document has a folder, the folder will be prepended to the document name
 pub fn make_document_path(document: &CreateDocumentBody) -> String {
     let mut folder = String::new();
 
     // if the document has a folder, prepend it to the document name
-    if document.folder.is_some() {
-        folder = format!("{}/", document.folder.as_ref().unwrap());
+    if let Some(f) = &document.folder {
+        folder = format!("{}/", f);
     }
 
     format!("{}/{}{}", document.project, folder, document.name)
@@ -74,9 +168,136 @@ pub fn make_folder_path(folder: &CreateFolderBody) -> String {
     let mut parent_folder = String::new();
 
     // if the folder has a parent folder, prepend it to the folder name
-    if folder.folder.is_some() {
-        parent_folder = format!("{}/", folder.folder.as_ref().unwrap());
+    if let Some(parent) = &folder.folder {
+        parent_folder = format!("{}/", parent);
     }
 
     format!("{}/{}{}", folder.project, parent_folder, folder.name)
-}
\ No newline at end of file
+}
+
+/// Splits a `project/a/b/c` folder path into the project and the folder names.
+/// Empty segments are ignored, at least one folder name is required.
+pub fn split_folder_path(path: &str) -> Result<(String, Vec<String>), JsbError> {
+    let mut segments = path.split('/').filter(|s| !s.is_empty()).map(|s| s.to_string());
+
+    let project = match segments.next() {
+        Some(project) => project,
+        None => return Err(err_required("Project")),
+    };
+
+    let names: Vec<String> = segments.collect();
+    if names.is_empty() {
+        return Err(err_required("Folder"));
+    }
+
+    Ok((project, names))
+}
+
+/// Checks the required fields and the content of a CreateDocumentBody
+pub fn validate_create_document(content: &CreateDocumentBody) -> Result<(), JsbError> {
+    // check if content.project is set
+    if content.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    // check if content.name is set
+    if content.name.is_empty() {
+        return Err(err_required("Name"));
+    }
+
+    // check if content.content is set
+    if content.content.is_empty() {
+        return Err(err_required("Content"));
+    }
+
+    // check if content.content is a valid json
+    if !is_valid_json(&content.content) {
+        return Err(err_invalid_json());
+    }
+
+    Ok(())
+}
+
+/// Checks the required fields of a CreateFolderBody
+pub fn validate_create_folder(data: &CreateFolderBody) -> Result<(), JsbError> {
+    // project is required
+    if data.project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    // name is required
+    if data.name.is_empty() {
+        return Err(err_required("Name"));
+    }
+
+    Ok(())
+}
+
+/// Maximum number of items per page of a listing
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Checks the page and page size of a listing
+pub fn validate_list_options(options: &ListOptions) -> Result<(), JsbError> {
+    if options.page == 0 {
+        return Err(JsbError::new(ErrorKind::Validation, "bad_request", "Page must be 1 or more"));
+    }
+
+    if options.per_page == 0 || options.per_page > MAX_PER_PAGE {
+        return Err(JsbError::new(ErrorKind::Validation, "bad_request", &format!("Per page must be between 1 and {}", MAX_PER_PAGE)));
+    }
+
+    Ok(())
+}
+
+/// Sorts the items of a listing and returns the requested page.
+/// Used by the local stores, the api does the same on the server.
+pub fn paginate(mut items: Vec<ContentItem>, options: &ListOptions) -> Result<ContentsPage, JsbError> {
+    validate_list_options(options)?;
+
+    // items with the same sort value keep a stable order by name
+    items.sort_by(|a, b| {
+        let ordering = match options.sort {
+            SortBy::Name => a.name().cmp(b.name()),
+            SortBy::UpdatedAt => a.updated_at().cmp(b.updated_at()).then_with(|| a.name().cmp(b.name())),
+        };
+
+        match options.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+
+    let total = items.len() as u64;
+    let start = (options.page as usize - 1).saturating_mul(options.per_page as usize);
+    let items = items.into_iter().skip(start).take(options.per_page as usize).collect();
+
+    Ok(ContentsPage { items, page: options.page, per_page: options.per_page, total })
+}
+
+/// Current time as an ISO 8601 string, e.g. `2023-05-01T10:20:30.000Z`
+pub fn now() -> String {
+    format_timestamp(SystemTime::now())
+}
+
+/// Formats a time as an ISO 8601 string in UTC, the format used by the api
+pub fn format_timestamp(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = duration.as_secs();
+    let (hour, minute, second) = ((secs / 3600) % 24, (secs / 60) % 60, secs % 60);
+
+    // convert days since epoch to a civil date
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, duration.subsec_millis()
+    )
+}
@@ -8,16 +8,14 @@
 //! ### Without Api Keys
 //!
 //! Using this [json file from jsonbank](https://api.jsonbank.io/f/jsonbank/sdk-test/index.json)
-//! ```
+//! ```no_run
 //! use jsonbank::{JsonBank, JsonValue};
 //!
-//! fn main() {
-//!     let jsb = JsonBank::new_without_config();
+//! let jsb = JsonBank::new_without_config();
 //!
-//!     // get public content
-//!     let data: JsonValue = jsb.get_content("jsonbank/sdk-test/index").unwrap();
-//!     assert_eq!(data["author"], "jsonbank")
-//! }
+//! // get public content
+//! let data: JsonValue = jsb.get_content("jsonbank/sdk-test/index").unwrap();
+//! assert_eq!(data["author"], "jsonbank")
 //! ```
 //!
 //! ### With Api Keys
@@ -25,19 +23,18 @@
 //! ```no_run
 //! use jsonbank::{JsonBank, InitConfig, Keys};
 //!
-//! fn main() {
-//!     let mut  jsb = JsonBank::new(InitConfig {
-//!         host: None, // use default host
-//!         keys: Some(Keys {
-//!             public: Some("Your public key".to_string()),
-//!             private: Some("Your private key".to_string()),
-//!         }),
-//!     });
+//! let mut  jsb = JsonBank::new(InitConfig {
+//!     host: None, // use default host
+//!     keys: Some(Keys {
+//!         public: Some("Your public key".to_string()),
+//!         private: Some("Your private key".to_string()),
+//!     }),
+//!     ..Default::default()
+//! });
 //!
-//!     // authenticate the api keys (optional)
-//!     if !jsb.authenticate().is_ok() {
-//!         panic!("Authentication failed");
-//!      }
+//! // authenticate the api keys (optional)
+//! if jsb.authenticate().is_err() {
+//!     panic!("Authentication failed");
 //! }
 //! ```
 //!
@@ -48,32 +45,56 @@
 //! - [Object Json File](https://jsonbank.io/f/jsonbank/sdk-test/index.json)
 //! - [Array Json File](https://jsonbank.io/gh/jsonbankio/documentation/github-test-array.json) (From github)
 //!
-//! ```
+//! ```no_run
 //! use jsonbank::{JsonBank, JsonValue, JsonObject, JsonArray};
 //!
-//! fn main() {
-//!     /// initialize jsonbank
-//!     let jsb = JsonBank::new_without_config();
+//! // initialize jsonbank
+//! let jsb = JsonBank::new_without_config();
 //!
-//!     /// Get json object
-//!     let data: JsonObject = jsb.get_content("jsonbank/sdk-test/index.json").unwrap();
-//!     assert_eq!(data["author"], "jsonbank");
+//! // Get json object
+//! let data: JsonObject = jsb.get_content("jsonbank/sdk-test/index.json").unwrap();
+//! assert_eq!(data["author"], "jsonbank");
 //!
-//!     /// Get json array
-//!     let data: JsonArray = jsb.get_github_content("jsonbankio/documentation/github-test-array.json").unwrap();
-//!     assert_eq!(data[0], 1);
-//!     assert_eq!(data[1], "MultiType Array");
-//!     assert_eq!(data[2].as_object().unwrap()["name"], "github-test-array.json");
+//! // Get json array
+//! let data: JsonArray = jsb.get_github_content("jsonbankio/documentation/github-test-array.json").unwrap();
+//! assert_eq!(data[0], 1);
+//! assert_eq!(data[1], "MultiType Array");
+//! assert_eq!(data[2].as_object().unwrap()["name"], "github-test-array.json");
 //!
-//!     /// Get json value (when you don't know the exact type)
-//!     let data: JsonValue = jsb.get_content("jsonbank/sdk-test/index.json").unwrap();
-//!     if data.is_object() {
-//!        assert_eq!(data["author"], "jsonbank");
-//!     } else {
-//!        panic!("Expected json object");
-//!     }
+//! // Get json value (when you don't know the exact type)
+//! let data: JsonValue = jsb.get_content("jsonbank/sdk-test/index.json").unwrap();
+//! if data.is_object() {
+//!    assert_eq!(data["author"], "jsonbank");
+//! } else {
+//!    panic!("Expected json object");
 //! }
 //!```
+//! ## Async
+//! Enable the `async` feature to use [AsyncJsonBank](struct.AsyncJsonBank.html).
+//! It has the same methods as [JsonBank](struct.JsonBank.html) but they return futures.
+//! ```toml
+//! [dependencies]
+//! jsonbank = { version = "0.1", features = ["async"] }
+//! ```
+//!
+//! ## Swapping Backends
+//! The document, folder and auth operations are also available through the [JsonBankApi](trait.JsonBankApi.html) trait.
+//! Besides [JsonBank](struct.JsonBank.html), it is implemented by [MemoryStore](stores/struct.MemoryStore.html)
+//! and [FileSystemStore](stores/struct.FileSystemStore.html), so code that depends on the trait can run
+//! against memory or a local directory.
+//! ```
+//! use jsonbank::{JsonBank, JsonBankApi};
+//! use jsonbank::stores::{FileSystemStore, MemoryStore};
+//!
+//! fn backend(name: &str) -> Box<dyn JsonBankApi> {
+//!     match name {
+//!         "memory" => Box::new(MemoryStore::new()),
+//!         "local" => Box::new(FileSystemStore::new("./data")),
+//!         _ => Box::new(JsonBank::new_without_config()),
+//!     }
+//! }
+//! ```
+//!
 //! ## Extra Info
 //! The struct [JsonBank](struct.JsonBank.html) is well documented, so you can check the docs for more info.
 //!
@@ -85,21 +106,66 @@ extern crate serde_json;
 
 
 mod functions;
+mod request;
+mod operations;
+mod retry;
+mod api;
+mod builder;
+mod cache;
+mod contents;
+mod sync;
+mod modify;
+mod document;
+mod trace;
 /// Package structs
 pub mod structs;
 /// Package error module
 pub mod error;
-
-
-use serde::{de::DeserializeOwned};
+/// Local implementations of [JsonBankApi](../trait.JsonBankApi.html).
+///
+pub mod stores;
+/// Request metrics.
+///
+pub mod metrics;
+/// Request/response middleware.
+///
+pub mod middleware;
+#[cfg(feature = "async")]
+mod async_client;
+#[cfg(feature = "test-util")]
+pub mod testing;
+
+
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{Value};
 use std::collections::HashMap;
-use std::fs;
-use std::path::{PathBuf};
-use reqwest::blocking::Response;
+use std::time::Duration;
+use std::fmt::{Debug, Formatter};
 use error::*;
 use functions::*;
+use operations::{FolderPath, IfNotExists, Requests};
+use modify::{Reply, Request};
+use request::*;
 use structs::*;
+use cache::{Lookup, Scope};
+use trace::RequestSpan;
+use metrics::MetricsSink;
+use retry::Attempts;
+use middleware::{Chain, Middleware};
+use std::sync::Arc;
+
+pub use retry::RetryPolicy;
+pub use api::JsonBankApi;
+pub use cache::{CacheConfig, ContentCache};
+pub use contents::ContentsIter;
+pub use sync::MIRROR_MANIFEST;
+pub use functions::content_hash;
+pub use builder::{JsonBankBuilder, ENV_HOST, ENV_PUBLIC_KEY, ENV_PRIVATE_KEY};
+pub use document::Document;
+#[cfg(feature = "async")]
+pub use async_client::AsyncJsonBank;
+#[cfg(feature = "async")]
+pub use document::AsyncDocument;
 
 
 /// The keyword `jsonbank`
@@ -119,28 +185,198 @@ pub type JsonObject = HashMap<String, JsonValue>;
 /// An alias for `Vec<JsonValue>`
 pub type JsonArray = Vec<JsonValue>;
 
+/// An RFC 6902 JSON Patch, a list of operations deserialized from e.g. `[{"op": "remove", "path": "/a"}]`
+pub use json_patch::Patch as JsonPatch;
+
 /// Holds the public and private keys
+#[derive(Clone, Default)]
 pub struct Keys {
     pub public: Option<String>,
     pub private: Option<String>,
 }
 
+// The keys are never printed
+impl Debug for Keys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let redact = |key: &Option<String>| key.as_ref().map(|_| "***");
+
+        f.debug_struct("Keys")
+            .field("public", &redact(&self.public))
+            .field("private", &redact(&self.private))
+            .finish()
+    }
+}
+
+impl Keys {
+    // get - Returns a public or private key depending on the key parameter
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "public" => self.public.as_deref(),
+            "private" => self.private.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 /// The configuration struct
 pub struct Config {
     pub host: String,
     keys: Option<Keys>, // Keys
+    pool: PoolConfig, // Connection pool settings
+    retry: RetryPolicy, // Retry policy
+    timeout: Option<Duration>, // Total timeout of a request
+    connect_timeout: Option<Duration>, // Timeout of the connect phase
+    user_agent: Option<String>, // User-Agent header
+    proxy: Option<String>, // Proxy url used for all requests
+    tls: TlsConfig, // TLS settings
 }
 
 /// Minimal Config struct needed to initialize.
+///
+/// See [JsonBank::builder](struct.JsonBank.html#method.builder) for a chainable way to set it up.
+#[derive(Default)]
 pub struct InitConfig {
     pub host: Option<String>,
     pub keys: Option<Keys>,
+    /// Connection pool settings, uses reqwest defaults when not set
+    pub pool: Option<PoolConfig>,
+    /// Retry policy, uses [RetryPolicy::default] when not set
+    pub retry: Option<RetryPolicy>,
+    /// Total timeout of a request, from connecting until the whole body is read. No timeout when not set
+    pub timeout: Option<Duration>,
+    /// Timeout of the connect phase. No timeout when not set
+    pub connect_timeout: Option<Duration>,
+    /// Value of the `User-Agent` header
+    pub user_agent: Option<String>,
+    /// Cache the content of documents in memory. Nothing is cached when not set
+    pub cache: Option<CacheConfig>,
+    /// Receives the metrics of every request, see [metrics](metrics/index.html)
+    pub metrics: Option<Arc<dyn MetricsSink>>,
+    /// Middleware every request passes in order, after the api keys are added, see [middleware](middleware/index.html)
+    pub middleware: Vec<Arc<dyn Middleware>>,
+}
+
+/// Options for a single call, used by the `_with` variants of methods.
+///
+/// Every method that sends one request has a `_with` variant, e.g. `get_own_content_with`.
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use jsonbank::{JsonBank, Keys, RequestOptions};
+/// # let jsb = JsonBank::new_without_config();
+/// // retry the update if it fails, sending the same content twice is safe
+/// let res = jsb.update_own_document_with("id_or_path", "{}".to_string(), RequestOptions {
+///     retry_writes: true,
+///     timeout: Some(Duration::from_secs(5)),
+///     ..Default::default()
+/// }).unwrap();
+///
+/// // read with the keys of another tenant
+/// let tenant = RequestOptions {
+///     keys: Some(Keys { public: Some("tenant_public_key".to_string()), private: None }),
+///     ..Default::default()
+/// };
+/// let content = jsb.get_own_content_as_string_with("id_or_path", tenant).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Retry this call even though it writes, e.g. `create_document`.
+    /// Only enable it when sending the request more than once is safe.
+    pub retry_writes: bool,
+    /// Total timeout of this call, overrides the timeout of the instance.
+    pub timeout: Option<Duration>,
+    /// Extra headers sent with the request. The api key headers can not be set, use `keys` instead.
+    pub headers: HashMap<String, String>,
+    /// Sent as the `Idempotency-Key` header, so the server can recognize a retried write.
+    pub idempotency_key: Option<String>,
+    /// Retry policy of this call, overrides the policy of the instance.
+    pub retry: Option<RetryPolicy>,
+    /// Keys of this call, override the keys of the instance.
+    /// Content read with other keys is never cached.
+    pub keys: Option<Keys>,
+}
+
+/// Connection pool settings.
+///
+/// Every instance owns one http client, so connections (and their TLS sessions)
+/// are reused by all requests made with that instance.
+#[derive(Debug, Clone, Default)]
+pub struct PoolConfig {
+    /// How long an idle connection is kept open. (reqwest default: 90 seconds)
+    pub idle_timeout: Option<Duration>,
+    /// Maximum number of idle connections kept per host. (reqwest default: no limit)
+    pub max_idle_per_host: Option<usize>,
+    /// The http version to use.
+    pub http_version: HttpVersion,
+}
+
+/// Http version preference of the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// Use http/2 when the server supports it, negotiated during the TLS handshake.
+    #[default]
+    Auto,
+    /// Only use http/1.1
+    Http1Only,
+    /// Use http/2 without negotiation, the server must support it.
+    Http2PriorKnowledge,
+}
+
+/// TLS settings.
+///
+/// TLS needs the `native-tls` (default) or the `rustls` feature, without them only `http` hosts can be used.
+/// # Example
+/// ```no_run
+/// use jsonbank::{JsonBank, TlsConfig};
+///
+/// // trust the CA of a TLS-intercepting gateway
+/// let jsb = JsonBank::builder()
+///     .proxy("http://proxy.internal:3128")
+///     .tls(TlsConfig {
+///         root_certificates: vec![std::fs::read_to_string("gateway-ca.pem").unwrap()],
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// The TLS implementation to use.
+    pub backend: TlsBackend,
+    /// PEM encoded root certificates trusted besides the built-in ones, an entry may contain several certificates.
+    pub root_certificates: Vec<String>,
+    /// Only trust `root_certificates`, not the built-in root certificates.
+    pub only_root_certificates: bool,
+}
+
+/// TLS implementation of the client, each one needs the cargo feature of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TlsBackend {
+    /// `native-tls` when enabled, otherwise `rustls`.
+    #[default]
+    Auto,
+    /// The platform library, e.g. OpenSSL on linux. Needs the `native-tls` feature.
+    NativeTls,
+    /// rustls with the Mozilla root certificates. Needs the `rustls` feature.
+    Rustls,
 }
 
-// Endpoints struct - Endpoints
-struct Endpoints {
-    v1: String,
-    public: String,
+impl Config {
+    // from_init - Builds the config of an instance, using defaults for unset values
+    fn from_init(conf: InitConfig, proxy: Option<String>, tls: TlsConfig) -> Config {
+        Config {
+            host: conf.host.unwrap_or(DEFAULT_HOST.to_string()),
+            keys: conf.keys,
+            pool: conf.pool.unwrap_or_default(),
+            retry: conf.retry.unwrap_or_default(),
+            timeout: conf.timeout,
+            connect_timeout: conf.connect_timeout,
+            user_agent: conf.user_agent,
+            proxy,
+            tls,
+        }
+    }
+
 }
 
 /// JsonBank SDK Instance
@@ -151,59 +387,20 @@ pub struct JsonBank {
     endpoints: Endpoints,
     // Authenticated data
     authenticated_data: Option<AuthenticatedData>,
+    // Http client, shared by all requests of this instance. The error it could not be built with otherwise,
+    // every request returns it
+    client: Result<reqwest::blocking::Client, JsbError>,
+    // Content cache, if enabled
+    cache: Option<ContentCache>,
+    // Metrics sink, if set
+    metrics: Option<Arc<dyn MetricsSink>>,
+    // Middleware chain, starting with the api keys
+    middleware: Chain,
 }
 
 
 // Implementing JsonBank
 impl JsonBank {
-    // Checks if a key is provided either public or private
-    fn has_key(&self, key: &str) -> bool {
-        // check if keys are provided
-        if self.config.keys.is_none() {
-            return false;
-        }
-
-        // get keys
-        let keys = self.config.keys.as_ref().unwrap();
-
-        // check if key is provided
-        if key == "public" {
-            return keys.public.is_some();
-        } else if key == "private" {
-            return keys.private.is_some();
-        }
-
-        false
-    }
-
-    // Returns a public or private key depending on the key parameter
-    fn get_key(&self, key: &str) -> String {
-        // check if keys are provided
-        if self.config.keys.is_none() {
-            return "".to_string();
-        }
-
-        // get keys
-        let keys = self.config.keys.as_ref().unwrap();
-
-        // check if key is provided
-        if key == "public" {
-            return keys.public.as_ref().unwrap().to_string();
-        } else if key == "private" {
-            return keys.private.as_ref().unwrap().to_string();
-        }
-
-        "".to_string()
-    }
-
-    // Make Endpoints
-    fn make_endpoints(host: &String) -> Endpoints {
-        Endpoints {
-            v1: format!("{}/v1", host),
-            public: host.to_string(),
-        }
-    }
-
     /// Initialize JsonBank SDK Instance
     /// # Arguments
     /// * `conf` - The minimal config needed to initialize
@@ -216,22 +413,61 @@ impl JsonBank {
     ///     public: Some("Your public key".to_string()),
     ///     private: Some("Your private key".to_string()),
     ///     }),
+    ///     ..Default::default()
+    /// });
+    /// ```
+    ///
+    /// With connection pool settings:
+    /// ```
+    /// # use std::time::Duration;
+    /// # use jsonbank::{JsonBank, InitConfig, PoolConfig, HttpVersion};
+    /// let jsb = JsonBank::new(InitConfig {
+    ///     pool: Some(PoolConfig {
+    ///         idle_timeout: Some(Duration::from_secs(30)),
+    ///         max_idle_per_host: Some(8),
+    ///         http_version: HttpVersion::Auto,
+    ///     }),
+    ///     ..Default::default()
     /// });
     /// ```
+    ///
+    /// Does not fail: when the http client cannot be built, e.g. the user agent contains invalid characters,
+    /// every request returns the error. Use [builder](#method.builder) to get it right away
+    /// and to set a proxy or the TLS settings.
     pub fn new(conf: InitConfig) -> Self {
-        let host = conf.host.unwrap_or(DEFAULT_HOST.to_string());
+        Self::init(conf, None, TlsConfig::default())
+    }
+
+    // try_new - Initialize JsonBank SDK Instance, returning an error when the http client cannot be built
+    pub(crate) fn try_new(conf: InitConfig, proxy: Option<String>, tls: TlsConfig) -> Result<Self, JsbError> {
+        let jsb = Self::init(conf, proxy, tls);
+        jsb.client()?;
+
+        Ok(jsb)
+    }
+
+    // init - Initialize JsonBank SDK Instance, keeping the error if the http client cannot be built
+    fn init(mut conf: InitConfig, proxy: Option<String>, tls: TlsConfig) -> Self {
+        // the cache is not part of the config
+        let cache = conf.cache.take().map(ContentCache::new);
+        let metrics = conf.metrics.take();
+        let middleware = Chain::new(std::mem::take(&mut conf.middleware));
 
         // build config
-        let config = Config {
-            host: host.to_string(),
-            keys: conf.keys,
-        };
+        let config = Config::from_init(conf, proxy, tls);
 
         // set endpoints
-        let endpoints = Self::make_endpoints(&host);
+        let endpoints = Endpoints::new(&config.host);
+
+        // build http client
+        let client = match configure_client!(reqwest::blocking::Client::builder(), &config) {
+            // not a transport error, sending again does not help
+            Ok(builder) => builder.build().map_err(|err| err_invalid_config("The http client cannot be built").with_source(err)),
+            Err(err) => Err(err),
+        };
 
         // return JsonBank struct
-        JsonBank { config, endpoints, authenticated_data: None }
+        JsonBank { config, endpoints, authenticated_data: None, client, cache, metrics, middleware }
     }
 
     /// Initialize JsonBank SDK Instance without config
@@ -241,141 +477,102 @@ impl JsonBank {
     /// let jsb = JsonBank::new_without_config();
     /// ```
     pub fn new_without_config() -> Self {
-        Self::new(InitConfig {
-            host: None,
-            keys: None,
-        })
+        Self::new(InitConfig::default())
+    }
+
+    /// Returns a [JsonBankBuilder](struct.JsonBankBuilder.html) to configure an instance step by step
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use jsonbank::JsonBank;
+    /// let jsb = JsonBank::builder()
+    ///     .host("https://api.jsonbank.io")
+    ///     .keys("your_public_key", "your_private_key")
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> JsonBankBuilder {
+        JsonBankBuilder::new()
+    }
+
+    /// Initialize JsonBank SDK Instance from the `JSB_HOST`, `JSB_PUBLIC_KEY` and `JSB_PRIVATE_KEY` environment variables.
+    ///
+    /// See [JsonBankBuilder::from_env](struct.JsonBankBuilder.html#method.from_env) for the rules,
+    /// use it instead to change other settings.
+    /// # Example
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// let jsb = match JsonBank::from_env() {
+    ///     Ok(jsb) => jsb,
+    ///     Err(err) => panic!("Invalid jsonbank config: {}", err),
+    /// };
+    /// ```
+    pub fn from_env() -> Result<Self, JsbError> {
+        JsonBankBuilder::from_env().and_then(|builder| builder.build())
     }
 }
 
 // Instance Implementation
 impl JsonBank {
-    // Format public url
-    fn public_url(&self, paths: Vec<&str>) -> String {
-        // add paths to public endpoint
-        format!("{}/{}", self.endpoints.public, paths.join("/"))
+    // requests_with - Returns the request builder of this instance using call options
+    fn requests_with(&self, options: RequestOptions) -> Requests<'_> {
+        Requests::new(&self.config, &self.endpoints, options)
     }
 
-    // format v1 url
-    fn v1_url(&self, paths: Vec<&str>) -> String {
-        // add paths to v1 endpoint
-        format!("{}/{}", self.endpoints.v1, paths.join("/"))
-    }
+    // make_request - Sends a prepared request, retrying it as allowed by the retry policy
+    fn make_request(&self, req: ApiRequest) -> Result<ApiResponse, JsbError> {
+        let span = RequestSpan::new(&req);
+        let _entered = span.enter();
+        let mut attempts = Attempts::new(&req, &self.config.retry, &span, self.metrics.as_deref());
 
-    // process_response_error - Processes response error
-    fn process_response_error<T: DeserializeOwned>(&self, res: Response) -> Result<T, JsbError> {
-        let code = res.status().to_string();
-        let data: JsonObject = match res.json() {
-            Ok(text) => text,
-            Err(err) => {
-                return Err(JsbError::from_any(&err, None));
-            }
-        };
+        loop {
+            let res = self.send_attempt(req.clone());
 
-        // get error object from data
-        let error = match data["error"].as_object() {
-            Some(err) => err,
-            None => {
-                return Err(JsbError {
-                    code,
-                    message: "Unknown error".to_string(),
-                });
+            match attempts.next(&res) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return res,
             }
-        };
+        }
+    }
 
+    // send_attempt - Sends one attempt of a request through the middleware chain
+    fn send_attempt(&self, mut req: ApiRequest) -> Result<ApiResponse, JsbError> {
+        let (passed, answer) = self.middleware.before(&mut req);
 
-        Err(JsbError {
-            code: error["code"].as_str().unwrap().to_string(),
-            message: error["message"].as_str().unwrap().to_string(),
-        })
-    }
+        let res = match answer {
+            Some(res) => res,
+            None => self.send_once(&req),
+        };
 
-    // process_response - Processes response
-    fn process_response<T: DeserializeOwned>(&self, res: Response) -> Result<T, JsbError> {
-        // Check if the response is successful
-        if res.status().is_success() {
-            let response_text: T = match res.json() {
-                Ok(text) => text,
-                Err(err) => {
-                    return Err(JsbError::from_any(&err, None));
-                }
-            };
-
-            Ok(response_text)
-        } else {
-            self.process_response_error(res)
-        }
+        self.middleware.after(passed, &req, res)
     }
 
-    // process_response_as_string - Processes response as text
-    fn process_response_as_string(&self, res: Response) -> Result<String, JsbError> {
-        // Check if the response is successful
-        if res.status().is_success() {
-            let response_text: String = match res.text() {
-                Ok(text) => text,
-                Err(err) => {
-                    return Err(JsbError::from_any(&err, None));
-                }
-            };
-
-            Ok(response_text)
-        } else {
-            self.process_response_error(res)
-        }
+    // client - Returns the http client, or the error it could not be built with
+    fn client(&self) -> Result<&reqwest::blocking::Client, JsbError> {
+        self.client.as_ref().map_err(client_error)
     }
 
-    // make_request - Makes request
-    fn make_request(&self, method: &str, url: String, body: Option<JsonObject>, require_pub_key: bool, require_prv_key: bool) -> Result<Response, JsbError> {
-        // build request
-        let client = reqwest::blocking::Client::new();
-        // add json header
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("Content-Type", "application/json".parse().unwrap());
-
-        // check if public key is required and not provided
-        if require_pub_key {
-            if self.has_key("public") {
-                // add public key to headers as `jsb-pub-key`
-                headers.insert("jsb-pub-key", self.get_key("public").parse().unwrap());
-            } else {
-                return Err(JsbError {
-                    code: "bad_request".to_string(),
-                    message: "Public key is not set".to_string(),
-                });
-            }
-        }
-
-        // check if private key is required and not provided
-        if require_prv_key {
-            if self.has_key("private") {
-                // add private key to headers as `jsb-private-key`
-                headers.insert("jsb-prv-key", self.get_key("private").parse().unwrap());
-            } else {
-                return Err(JsbError {
-                    code: "bad_request".to_string(),
-                    message: "Private key is not set".to_string(),
-                });
-            }
-        }
-
+    // send_once - Sends a prepared request and reads the whole response
+    fn send_once(&self, req: &ApiRequest) -> Result<ApiResponse, JsbError> {
         // send request
-        match {
-            match method {
-                "POST" => client.post(&url).json(&body.unwrap_or(HashMap::new())),
-                "DELETE" => client.delete(&url),
-                _ => client.get(&url).query(&body.unwrap_or(HashMap::new())),
-            }.headers(headers).send()
-        } {
-            Ok(res) => Ok(res),
-            Err(err) => Err(JsbError::from_any(&err, None))
+        let res = match http_request!(self.client()?, req).send() {
+            Ok(res) => res,
+            Err(err) => return Err(JsbError::from(err)),
+        };
+
+        let status = res.status();
+        let headers = res.headers().clone();
+        match res.bytes() {
+            Ok(body) => Ok(ApiResponse { status, headers, body: body.to_vec() }),
+            Err(err) => Err(JsbError::from(err)),
         }
     }
 
-    // send_get_request - Sends get request
-    // This function sends the http request using reqwest
-    fn send_request<T: DeserializeOwned>(&self, method: &str, url: String, body: Option<JsonObject>, require_pub_key: bool, require_prv_key: bool) -> Result<T, JsbError> {
+    // send_request - Sends request and deserializes the response
+    fn send_request<T: DeserializeOwned>(&self, req: Result<ApiRequest, JsbError>) -> Result<T, JsbError> {
         // make request
-        let res = match self.make_request(method, url, body, require_pub_key, require_prv_key) {
+        let res = match req.and_then(|req| self.make_request(req)) {
             Ok(res) => res,
             Err(err) => {
                 return Err(err);
@@ -383,13 +580,13 @@ impl JsonBank {
         };
 
         // process response
-        self.process_response(res)
+        process_response(res)
     }
 
     // send_request_as_string - Sends request and returns response as text
-    fn send_request_as_string(&self, method: &str, url: String, body: Option<JsonObject>, require_pub_key: bool, require_prv_key: bool) -> Result<String, JsbError> {
+    fn send_request_as_string(&self, req: Result<ApiRequest, JsbError>) -> Result<String, JsbError> {
         // make request
-        let res = match self.make_request(method, url, body, require_pub_key, require_prv_key) {
+        let res = match req.and_then(|req| self.make_request(req)) {
             Ok(res) => res,
             Err(err) => {
                 return Err(err);
@@ -397,42 +594,74 @@ impl JsonBank {
         };
 
         // process response
-        self.process_response_as_string(res)
+        process_response_as_string(res)
     }
 
-    // public_request - Sends get request to public endpoint
-    fn public_request<T: DeserializeOwned>(&self, url: Vec<&str>) -> Result<T, JsbError> {
-        self.send_request("GET", self.public_url(url), None, false, false)
-    }
+    // cached_content - Returns the cached content of a document, sending the request and caching its content on a miss
+    fn cached_content(&self, scope: Scope, id_or_path: &str, req: Result<ApiRequest, JsbError>) -> Result<String, JsbError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.send_request_as_string(req),
+        };
+
+        let (validators, generation) = match cache.get(scope, id_or_path) {
+            Lookup::Fresh(content) => return Ok(content),
+            Lookup::Send(validators, generation) => (validators, generation),
+        };
 
-    // public_request_as_string - Sends get request to public endpoint and returns response as text
-    fn public_request_as_string(&self, url: Vec<&str>) -> Result<String, JsbError> {
-        self.send_request_as_string("GET", self.public_url(url), None, false, false)
+        let req = req?;
+        match cache.revalidated(scope, id_or_path, self.send_conditional(Ok(req.clone()), &validators)?, generation) {
+            Some(content) => Ok(content),
+            // evicted while revalidating
+            None => self.send_request_as_string(Ok(req)),
+        }
     }
 
-    // read_request - Sends get request to auth required endpoints using public key
-    fn read_request<T: DeserializeOwned>(&self, url: Vec<&str>, query: Option<JsonObject>) -> Result<T, JsbError> {
-        self.send_request("GET", self.v1_url(url), query, true, false)
+    // send_conditional - Sends a request with the validators of a previous response, if any
+    fn send_conditional(&self, req: Result<ApiRequest, JsbError>, validators: &Validators) -> Result<Revalidated, JsbError> {
+        let mut req = req?;
+        validators.apply(&mut req)?;
+
+        match self.make_request(req) {
+            Ok(res) => process_conditional_response(res),
+            Err(err) => Err(err),
+        }
     }
 
-    // read_request_as_string - Sends get request to auth required endpoints using public key and returns response as text
-    fn read_request_as_string(&self, url: Vec<&str>, query: Option<JsonObject>) -> Result<String, JsbError> {
-        self.send_request_as_string("GET", self.v1_url(url), query, true, false)
+    // content_if_changed - Sends a content request with `If-None-Match` and decodes the content if it changed
+    fn content_if_changed<T: DeserializeOwned>(&self, req: Result<ApiRequest, JsbError>, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        let validators = Validators { etag: previous_etag.map(|etag| etag.to_string()), last_modified: None };
+
+        conditional_content(self.send_conditional(req, &validators)?)
     }
 
-    // read_post_request - Sends post request to auth required endpoints using public key
-    fn read_post_request<T: DeserializeOwned>(&self, url: Vec<&str>, body: Option<JsonObject>) -> Result<T, JsbError> {
-        self.send_request("POST", self.v1_url(url), body, true, false)
+    /// The content cache, `None` when [InitConfig::cache](struct.InitConfig.html#structfield.cache) is not set.
+    /// # Example:
+    /// ```
+    /// # use jsonbank::{CacheConfig, JsonBank};
+    /// let jsb = JsonBank::builder().cache(CacheConfig::default()).build().unwrap();
+    ///
+    /// // after changing a document outside of this instance
+    /// if let Some(cache) = jsb.cache() {
+    ///     cache.invalidate("project/config.json");
+    /// }
+    /// ```
+    pub fn cache(&self) -> Option<&ContentCache> {
+        self.cache.as_ref()
     }
 
-    // write_request - Sends post request to auth required endpoints using private key
-    fn write_request<T: DeserializeOwned>(&self, url: Vec<&str>, body: Option<JsonObject>) -> Result<T, JsbError> {
-        self.send_request("POST", self.v1_url(url), body, false, true)
+    // invalidate - Removes a document from the cache, if enabled
+    fn invalidate(&self, id_or_path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(id_or_path);
+        }
     }
 
-    // delete_request - Sends delete request to auth required endpoints using private key
-    fn delete_request<T: DeserializeOwned>(&self, url: Vec<&str>) -> Result<T, JsbError> {
-        self.send_request("DELETE", self.v1_url(url), None, false, true)
+    // alias - Tells the cache the id and the path of an own document, so updating one evicts both
+    fn alias(&self, id: &str, project: &str, path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.alias(id, project, path);
+        }
     }
 
     /// Sets host, this is useful when you want to use your own jsonbank server (Not currently supported)
@@ -446,13 +675,13 @@ impl JsonBank {
     pub fn set_host(&mut self, host: &str) {
         self.config.host = host.to_string();
         // update endpoints
-        self.endpoints = Self::make_endpoints(&self.config.host);
+        self.endpoints = Endpoints::new(&self.config.host);
     }
 
     /// Get public content meta from jsonbank
     /// # Example:
     /// Using this [json object file from jsonbank](https://api.jsonbank.io/f/jsonbank/sdk-test/index.json)
-    /// ```
+    /// ```no_run
     /// # use jsonbank::JsonBank;
     /// let jsb = JsonBank::new_without_config();
     /// let meta = jsb.get_document_meta("jsonbank/sdk-test/index").unwrap();
@@ -460,13 +689,12 @@ impl JsonBank {
     /// println!("{}", meta.id);
     /// ```
     pub fn get_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
-        match self.public_request::<JsonObject>(vec!["meta/f", id_or_path]) {
-            Ok(res) => {
-                // convert to DocumentMeta
-                Ok(json_object_to_document_meta(&res))
-            }
-            Err(err) => Err(err),
-        }
+        self.get_document_meta_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_document_meta](#method.get_document_meta) with call options.
+    pub fn get_document_meta_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DocumentMeta, JsbError> {
+        self.send_request(self.requests_with(options).get_document_meta(id_or_path))
     }
 
     /// Get public content from jsonbank
@@ -486,7 +714,15 @@ impl JsonBank {
     /// let data: JsonValue =  jsb.get_content("id_or_path").unwrap();
     /// ```
     pub fn get_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> {
-        self.public_request::<T>(vec!["f", id_or_path])
+        self.get_content_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_content](#method.get_content) with call options.
+    pub fn get_content_with<T: DeserializeOwned>(&self, id_or_path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.cache {
+            Some(_) => self.get_content_as_string_with(id_or_path, options).and_then(|content| decode_content(&content)),
+            None => self.send_request(self.requests_with(options).get_content(id_or_path)),
+        }
     }
 
     /// Get public content as string from jsonbank
@@ -498,7 +734,63 @@ impl JsonBank {
     /// println!("{}", data);
     /// ```
     pub fn get_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
-        self.public_request_as_string(vec!["f", id_or_path])
+        self.get_content_as_string_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_content_as_string](#method.get_content_as_string) with call options.
+    pub fn get_content_as_string_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.cached_content(Scope::Public, id_or_path, self.requests_with(options).get_content(id_or_path))
+    }
+
+    /// Get the node of public content at a JSON Pointer, e.g. `/features/checkout`.
+    /// Fails with [ErrorKind::PathNotFound](error/enum.ErrorKind.html#variant.PathNotFound) if there is no such node.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let enabled: bool = jsb.get_content_at("id_or_path", "/features/checkout").unwrap();
+    /// ```
+    pub fn get_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> {
+        self.get_content_at_with(id_or_path, pointer, RequestOptions::default())
+    }
+
+    /// Same as [get_content_at](#method.get_content_at) with call options.
+    pub fn get_content_at_with<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.get_content_as_string_with(id_or_path, options) {
+            Ok(content) => decode_content_at(&content, pointer),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get public content only if it changed since `previous_etag`, the `ETag` of an earlier response.
+    /// Does not use the content cache, keep the content and its `ETag` yourself.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::{JsonBank, JsonValue};
+    /// use jsonbank::structs::ConditionalContent;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let mut etag = None;
+    /// let mut data = JsonValue::Null;
+    ///
+    /// // first call downloads the content
+    /// if let ConditionalContent::Changed(content, new_etag) = jsb.get_content_if_changed("id_or_path", etag.as_deref()).unwrap() {
+    ///     data = content;
+    ///     etag = new_etag;
+    /// }
+    ///
+    /// // later calls only download it again if it changed
+    /// match jsb.get_content_if_changed::<JsonValue>("id_or_path", etag.as_deref()).unwrap() {
+    ///     ConditionalContent::Changed(content, _) => println!("changed: {}", content),
+    ///     ConditionalContent::NotModified => println!("unchanged: {}", data),
+    /// }
+    /// ```
+    pub fn get_content_if_changed<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        self.get_content_if_changed_with(id_or_path, previous_etag, RequestOptions::default())
+    }
+
+    /// Same as [get_content_if_changed](#method.get_content_if_changed) with call options.
+    pub fn get_content_if_changed_with<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>, options: RequestOptions) -> Result<ConditionalContent<T>, JsbError> {
+        self.content_if_changed(self.requests_with(options).get_content(id_or_path), previous_etag)
     }
 
     /// Grab a public json file from Github.
@@ -506,7 +798,7 @@ impl JsonBank {
     ///
     /// # Example:
     /// Using this [json object file from github](https://jsonbank.io/gh/jsonbankio/jsonbank-js/package.json)
-    /// ```
+    /// ```no_run
     /// # use jsonbank::{JsonBank, JsonValue};
     /// # let jsb = JsonBank::new_without_config();
     /// let content: JsonValue = jsb.get_github_content("jsonbankio/jsonbank-js/package.json").unwrap();
@@ -514,7 +806,12 @@ impl JsonBank {
     /// assert_eq!(content["author"], "jsonbankio");
     /// ```
     pub fn get_github_content<T: DeserializeOwned>(&self, path: &str) -> Result<T, JsbError> {
-        self.public_request(vec!["gh", path])
+        self.get_github_content_with(path, RequestOptions::default())
+    }
+
+    /// Same as [get_github_content](#method.get_github_content) with call options.
+    pub fn get_github_content_with<T: DeserializeOwned>(&self, path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        self.send_request(self.requests_with(options).get_github_content(path))
     }
 
     /// Grab a public json file from Github as a string.
@@ -522,7 +819,7 @@ impl JsonBank {
     ///
     /// # Example:
     /// Using this [json object file from github](https://jsonbank.io/gh/jsonbankio/jsonbank-js/package.json)
-    /// ```
+    /// ```no_run
     /// # use jsonbank::JsonBank;
     /// # let jsb = JsonBank::new_without_config();
     /// let content: String = jsb.get_github_content_as_string("jsonbankio/jsonbank-js/package.json").unwrap();
@@ -530,7 +827,12 @@ impl JsonBank {
     /// assert!(content.contains("prepublishOnly"));
     /// ```
     pub fn get_github_content_as_string(&self, path: &str) -> Result<String, JsbError> {
-        self.public_request_as_string(vec!["gh", path])
+        self.get_github_content_as_string_with(path, RequestOptions::default())
+    }
+
+    /// Same as [get_github_content_as_string](#method.get_github_content_as_string) with call options.
+    pub fn get_github_content_as_string_with(&self, path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.send_request_as_string(self.requests_with(options).get_github_content(path))
     }
 }
 
@@ -539,18 +841,14 @@ impl JsonBank {
 impl JsonBank {
     /// Authenticate user using current api key
     pub fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError> {
-        match self.read_post_request::<JsonObject>(vec!["authenticate"], None) {
-            Ok(res) => {
-                // convert to AuthenticatedData
-                let data = AuthenticatedData {
-                    authenticated: res["authenticated"].as_bool().unwrap(),
-                    username: res["username"].as_str().unwrap().to_string(),
-                    api_key: AuthenticatedKey {
-                        title: res["apiKey"]["title"].as_str().unwrap().to_string(),
-                        projects: res["apiKey"]["projects"].as_array().unwrap().iter().map(|x| x.as_str().unwrap().to_string()).collect(),
-                    },
-                };
+        self.authenticate_with(RequestOptions::default())
+    }
 
+    /// Same as [authenticate](#method.authenticate) with call options.
+    /// Keys set in the options are used for this request only, the instance keeps its own.
+    pub fn authenticate_with(&mut self, options: RequestOptions) -> Result<AuthenticatedData, JsbError> {
+        match self.send_request::<AuthenticatedData>(self.requests_with(options).authenticate()) {
+            Ok(data) => {
                 // set authenticated data
                 self.authenticated_data = Some(data.clone());
 
@@ -567,10 +865,7 @@ impl JsonBank {
     pub fn get_username(&self) -> Result<String, JsbError> {
         match &self.authenticated_data {
             Some(data) => Ok(data.username.clone()),
-            None => Err(JsbError {
-                code: "not_authenticated".to_string(),
-                message: "User is not authenticated".to_string(),
-            }),
+            None => Err(err_not_authenticated()),
         }
     }
 
@@ -586,13 +881,19 @@ impl JsonBank {
     ///
     /// **Note:** This does not return the content of the document.
     pub fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
-        match self.read_request::<JsonObject>(vec!["meta/file", id_or_path], None) {
-            Ok(res) => {
-                // convert to DocumentMeta
-                Ok(json_object_to_document_meta(&res))
-            }
-            Err(err) => Err(err),
+        self.get_own_document_meta_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_own_document_meta](#method.get_own_document_meta) with call options.
+    pub fn get_own_document_meta_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DocumentMeta, JsbError> {
+        let remember = options.keys.is_none();
+        let res = self.send_request::<DocumentMeta>(self.requests_with(options).get_own_document_meta(id_or_path));
+
+        if let (Ok(meta), true) = (&res, remember) {
+            self.alias(&meta.id, &meta.project, &meta.path);
         }
+
+        res
     }
 
 
@@ -615,7 +916,15 @@ impl JsonBank {
     /// println!("{:?}", data);
     /// ```
     pub fn get_own_content<T: DeserializeOwned>(&self, id_or_path: &str) -> Result<T, JsbError> {
-        self.read_request(vec!["file", id_or_path], None)
+        self.get_own_content_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_own_content](#method.get_own_content) with call options.
+    pub fn get_own_content_with<T: DeserializeOwned>(&self, id_or_path: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match (&self.cache, &options.keys) {
+            (Some(_), None) => self.get_own_content_as_string_with(id_or_path, options).and_then(|content| decode_content(&content)),
+            _ => self.send_request(self.requests_with(options).get_own_content(id_or_path)),
+        }
     }
 
     /// Get content of a document owned by authenticated user as json string
@@ -627,22 +936,78 @@ impl JsonBank {
     /// println!("{}", data);
     /// ```
     pub fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
-        self.read_request_as_string(vec!["file", id_or_path], None)
+        self.get_own_content_as_string_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_own_content_as_string](#method.get_own_content_as_string) with call options.
+    pub fn get_own_content_as_string_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        // the same path may be another document for other keys
+        if options.keys.is_some() {
+            return self.send_request_as_string(self.requests_with(options).get_own_content(id_or_path));
+        }
+
+        self.cached_content(Scope::Own, id_or_path, self.requests_with(options).get_own_content(id_or_path))
+    }
+
+    /// Get content of a document owned by authenticated user as json string from the server,
+    /// even if it is cached. The cached content is replaced with the content read.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let data: String = jsb.get_own_content_uncached("id_or_path").unwrap();
+    /// println!("{}", data);
+    /// ```
+    pub fn get_own_content_uncached(&self, id_or_path: &str) -> Result<String, JsbError> {
+        self.get_own_content_uncached_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_own_content_uncached](#method.get_own_content_uncached) with call options.
+    pub fn get_own_content_uncached_with(&self, id_or_path: &str, options: RequestOptions) -> Result<String, JsbError> {
+        self.invalidate(id_or_path);
+        self.get_own_content_as_string_with(id_or_path, options)
+    }
+
+    /// Get the node of a document owned by authenticated user at a JSON Pointer.
+    /// See [get_content_at](#method.get_content_at).
+    pub fn get_own_content_at<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str) -> Result<T, JsbError> {
+        self.get_own_content_at_with(id_or_path, pointer, RequestOptions::default())
+    }
+
+    /// Same as [get_own_content_at](#method.get_own_content_at) with call options.
+    pub fn get_own_content_at_with<T: DeserializeOwned>(&self, id_or_path: &str, pointer: &str, options: RequestOptions) -> Result<T, JsbError> {
+        match self.get_own_content_as_string_with(id_or_path, options) {
+            Ok(content) => decode_content_at(&content, pointer),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get json content of a document owned by authenticated user only if it changed since `previous_etag`.
+    /// See [get_content_if_changed](#method.get_content_if_changed).
+    pub fn get_own_content_if_changed<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>) -> Result<ConditionalContent<T>, JsbError> {
+        self.get_own_content_if_changed_with(id_or_path, previous_etag, RequestOptions::default())
+    }
+
+    /// Same as [get_own_content_if_changed](#method.get_own_content_if_changed) with call options.
+    pub fn get_own_content_if_changed_with<T: DeserializeOwned>(&self, id_or_path: &str, previous_etag: Option<&str>, options: RequestOptions) -> Result<ConditionalContent<T>, JsbError> {
+        self.content_if_changed(self.requests_with(options).get_own_content(id_or_path), previous_etag)
+    }
+
+    /// A handle to a document owned by authenticated user, bound to the type of its content.
+    /// See [Document](struct.Document.html).
+    pub fn document<T: DeserializeOwned + Serialize>(&self, id_or_path: &str) -> Document<'_, T> {
+        Document::new(self, id_or_path)
     }
 
     /// Check if user has document.
     /// This method will try to get document meta and if it throws the `notFound` error it will return false.
     pub fn has_own_document(&self, id_or_path: &str) -> Result<bool, JsbError> {
-        match self.get_own_document_meta(id_or_path) {
-            Ok(_) => Ok(true),
-            Err(err) => {
-                if err.code == "notFound" {
-                    Ok(false)
-                } else {
-                    Err(JsbError::from_any(&err, None))
-                }
-            }
-        }
+        self.has_own_document_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [has_own_document](#method.has_own_document) with call options.
+    pub fn has_own_document_with(&self, id_or_path: &str, options: RequestOptions) -> Result<bool, JsbError> {
+        operations::exists(self.get_own_document_meta_with(id_or_path, options))
     }
 
     /// Create a document.
@@ -662,65 +1027,19 @@ impl JsonBank {
     /// assert_eq!(new_doc.project, "test");
     /// ```
     pub fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        self.create_document_with(content, RequestOptions::default())
+    }
 
-        // check if content.project is set
-        if content.project.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Project required".to_string(),
-            });
-        }
-
-        // check if content.name is set
-        if content.name.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Name required".to_string(),
-            });
-        }
-
-        // check if content.content is set
-        if content.content.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Content required".to_string(),
-            });
-        }
-
-        // check if content.content is a valid json
-        if !is_valid_json(&content.content) {
-            return Err(err_invalid_json());
-        }
-
-        // convert content to hashmap
-        let mut body: JsonObject = HashMap::from([
-            ("name".to_string(), JsonValue::String(content.name)),
-            ("project".to_string(), JsonValue::String(content.project.clone())),
-            ("content".to_string(), JsonValue::String(content.content)),
-        ]);
+    /// Same as [create_document](#method.create_document) with call options.
+    pub fn create_document_with(&self, content: CreateDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let remember = options.keys.is_none();
+        let res = self.send_request::<NewDocument>(self.requests_with(options).create_document(content));
 
-        // add folder if set
-        if content.folder.is_some() {
-            body.insert("folder".to_string(), JsonValue::String(content.folder.unwrap()));
+        if let (Ok(doc), true) = (&res, remember) {
+            self.alias(&doc.id, &doc.project, &doc.path);
         }
 
-
-        // send request
-        let url = vec!["project", &content.project, "document"];
-        match self.write_request::<JsonObject>(url, Some(body)) {
-            Ok(res) => {
-                // convert to NewDocument
-                Ok(NewDocument {
-                    id: res["id"].as_str().unwrap().to_string(),
-                    name: res["name"].as_str().unwrap().to_string(),
-                    path: res["path"].as_str().unwrap().to_string(),
-                    project: res["project"].as_str().unwrap().to_string(),
-                    created_at: res["createdAt"].as_str().unwrap().to_string(),
-                    exists: false,
-                })
-            }
-            Err(err) => Err(err),
-        }
+        res
     }
 
     /// Create a document if it does not exist
@@ -749,27 +1068,20 @@ impl JsonBank {
     /// }
     /// ```
     pub fn create_document_if_not_exists(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
-        match self.create_document(content.clone()) {
-            Ok(res) => Ok(res),
-            Err(err) => {
-                // check if error code is name.exists
-                if err.code == "name.exists" {
-                    let doc_path = make_document_path(&content);
-                    // get document
-                    match self.get_own_document_meta(doc_path.as_str()) {
-                        Ok(res) => Ok(NewDocument {
-                            id: res.id,
-                            name: content.name,
-                            path: res.path,
-                            project: res.project,
-                            created_at: res.created_at,
-                            exists: true,
-                        }),
-                        Err(err) => Err(err),
-                    }
-                } else {
-                    Err(err)
-                }
+        self.create_document_if_not_exists_with(content, RequestOptions::default())
+    }
+
+    /// Same as [create_document_if_not_exists](#method.create_document_if_not_exists) with call options.
+    pub fn create_document_if_not_exists_with(&self, content: CreateDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("create_document_if_not_exists");
+
+        let res = self.create_document_with(content.clone(), options.clone());
+
+        match operations::if_not_exists(res, || make_document_path(&content)) {
+            IfNotExists::Done(res) => res,
+            IfNotExists::Read(doc_path) => {
+                let res = self.get_own_document_meta_with(doc_path.as_str(), options)?;
+                Ok(document_meta_to_new_document(res, content.name))
             }
         }
     }
@@ -785,28 +1097,126 @@ impl JsonBank {
     /// assert_eq!(res.changed, true);
     /// ```
     pub fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError> {
-        // check if content is a valid json
-        if !is_valid_json(&content) {
-            return Err(err_invalid_json());
-        }
+        self.update_own_document_with(id_or_path, content, RequestOptions::default())
+    }
 
-        // create body
-        let body = JsonObject::from([
-            ("content".to_string(), JsonValue::String(content)),
-        ]);
+    /// Same as [update_own_document](#method.update_own_document) with call options.
+    pub fn update_own_document_with(&self, id_or_path: &str, content: String, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let res = self.send_request(self.requests_with(options).update_own_document(id_or_path, content));
+        self.invalidate(id_or_path);
+        res
+    }
 
-        // send request
-        let url = vec!["file", id_or_path];
-
-        match self.write_request::<JsonObject>(url, Some(body)) {
-            Ok(res) => {
-                // convert to UpdatedDocument
-                Ok(UpdatedDocument {
-                    changed: res["changed"].as_bool().unwrap_or(false),
-                })
-            }
-            Err(err) => Err(err),
-        }
+    /// Update a document if no other client changed it since it was read.
+    /// Fails with [ErrorKind::Conflict](error/enum.ErrorKind.html#variant.Conflict) if it changed.
+    ///
+    /// # Best effort
+    /// The api has no conditional update. The version is checked by a request before the update,
+    /// so a change made between the check and the update is **not** detected and is overwritten.
+    /// It narrows the window in which a concurrent update is lost, it does not close it.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use jsonbank::error::ErrorKind;
+    /// use jsonbank::structs::ExpectedVersion;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let meta = jsb.get_own_document_meta("project/config.json").unwrap();
+    ///
+    /// match jsb.update_own_document_after_check("project/config.json", r#"{"v": 2}"#.to_string(), &ExpectedVersion::from(&meta)) {
+    ///     Ok(res) => println!("updated: {}", res.changed),
+    ///     Err(err) if err.kind == ErrorKind::Conflict => println!("changed by someone else"),
+    ///     Err(err) => panic!("{}", err),
+    /// }
+    /// ```
+    pub fn update_own_document_after_check(&self, id_or_path: &str, content: String, expected: &ExpectedVersion) -> Result<UpdatedDocument, JsbError> {
+        self.update_own_document_after_check_with(id_or_path, content, expected, RequestOptions::default())
+    }
+
+    /// Same as [update_own_document_after_check](#method.update_own_document_after_check) with call options,
+    /// they apply to the check and to the update.
+    pub fn update_own_document_after_check_with(&self, id_or_path: &str, content: String, expected: &ExpectedVersion, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("update_own_document_after_check");
+
+        self.run_check_first(id_or_path, modify::AfterCheck::new(content, expected), options)
+    }
+
+    /// Read a document, change it with `modify` and write it back if no other client changed it in between.
+    /// On a conflict the document is read and `modify` is called again, up to 5 times.
+    /// Each attempt reads the meta and the content, then writes with
+    /// [update_own_document_after_check](#method.update_own_document_after_check), so it is best effort too.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// # let jsb = JsonBank::new_without_config();
+    /// jsb.modify_document("project/config.json", |config| {
+    ///     config["deploys"] = (config["deploys"].as_u64().unwrap_or(0) + 1).into();
+    /// }).unwrap();
+    /// ```
+    pub fn modify_document<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F) -> Result<UpdatedDocument, JsbError> {
+        self.modify_document_with(id_or_path, modify, RequestOptions::default())
+    }
+
+    /// Same as [modify_document](#method.modify_document) with call options, they apply to every request.
+    pub fn modify_document_with<F: FnMut(&mut JsonValue)>(&self, id_or_path: &str, modify: F, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("modify_document");
+
+        self.run_check_first(id_or_path, modify::Modify::new(modify), options)
+    }
+
+    /// Apply RFC 6902 JSON Patch operations to a document.
+    /// The document is only updated if the patch changed it, and fails with
+    /// [ErrorKind::Conflict](error/enum.ErrorKind.html#variant.Conflict) if another client changed it meanwhile.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use jsonbank::JsonPatch;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let ops: JsonPatch = serde_json::from_str(r#"[
+    ///     {"op": "replace", "path": "/version", "value": 2},
+    ///     {"op": "remove", "path": "/legacy"}
+    /// ]"#).unwrap();
+    ///
+    /// let res = jsb.patch_own_document("project/config.json", &ops).unwrap();
+    /// println!("changed: {}", res.changed);
+    /// ```
+    pub fn patch_own_document(&self, id_or_path: &str, ops: &JsonPatch) -> Result<UpdatedDocument, JsbError> {
+        self.patch_own_document_with(id_or_path, ops, RequestOptions::default())
+    }
+
+    /// Same as [patch_own_document](#method.patch_own_document) with call options, they apply to every request.
+    pub fn patch_own_document_with(&self, id_or_path: &str, ops: &JsonPatch, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("patch_own_document");
+
+        self.run_check_first(id_or_path, modify::Change::new(|value| modify::patch(value, ops)), options)
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch to a document, `null` values remove fields.
+    /// The document is only updated if the patch changed it.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use serde_json::json;
+    /// # let jsb = JsonBank::new_without_config();
+    /// jsb.merge_own_document("project/config.json", &json!({"version": 2, "legacy": null})).unwrap();
+    /// ```
+    pub fn merge_own_document(&self, id_or_path: &str, partial: &JsonValue) -> Result<UpdatedDocument, JsbError> {
+        self.merge_own_document_with(id_or_path, partial, RequestOptions::default())
+    }
+
+    /// Same as [merge_own_document](#method.merge_own_document) with call options, they apply to every request.
+    pub fn merge_own_document_with(&self, id_or_path: &str, partial: &JsonValue, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        let _span = trace::operation("merge_own_document");
+
+        self.run_check_first(id_or_path, modify::Change::new(|value| modify::merge(value, partial)), options)
+    }
+
+    // run_check_first - sends the requests of an operation that reads and writes a document, see `modify::CheckFirst`
+    fn run_check_first<O: modify::Operation>(&self, id_or_path: &str, op: O, options: RequestOptions) -> Result<UpdatedDocument, JsbError> {
+        modify::run_check_first(id_or_path, op, |request| match request {
+            Request::Meta => self.get_own_document_meta_with(id_or_path, options.clone()).map(Reply::Meta),
+            Request::Content => self.get_own_content_uncached_with(id_or_path, options.clone()).map(Reply::Content),
+            Request::Update(content) => self.update_own_document_with(id_or_path, content, options.clone()).map(Reply::Updated),
+        })
     }
 
 
@@ -829,57 +1239,79 @@ impl JsonBank {
     /// assert_eq!(res.name, "upload.json");
     /// ```
     pub fn upload_document(&self, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
-        // project is required
-        if doc.project.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Project required".to_string(),
-            });
-        }
+        self.upload_document_with(doc, RequestOptions::default())
+    }
 
-        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(doc.file_path);
+    /// Same as [upload_document](#method.upload_document) with call options.
+    pub fn upload_document_with(&self, doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("upload_document");
 
-        // check if file exists using os
-        if !file_path.exists() {
-            return Err(JsbError {
-                code: "file_not_found".to_string(),
-                message: "File does not exist".to_string(),
-            });
+        match read_upload_document(doc) {
+            Ok(content) => self.create_document_with(content, options),
+            Err(err) => Err(err),
         }
+    }
 
-        // read file
-        let file_content = match fs::read_to_string(file_path.clone()) {
-            Ok(res) => res,
-            Err(err) => {
-                return Err(JsbError {
-                    code: "invalid_file".to_string(),
-                    message: err.to_string(),
-                });
-            }
-        };
+    /// Upload a json document read from any [Read](std::io::Read), e.g. a file, a socket or stdin.
+    /// The name of the document is `name`, or the file name of `file_path` when `name` is not set.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use std::fs::File;
+    /// use jsonbank::structs::UploadDocumentBody;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let file = File::open("/etc/app/settings.json").unwrap();
+    /// let res = jsb.upload_from_reader(file, UploadDocumentBody {
+    ///     file_path: String::new(),
+    ///     project: "test".to_string(),
+    ///     name: Some("settings.json".to_string()),
+    ///     folder: None,
+    /// }).unwrap();
+    ///
+    /// assert_eq!(res.name, "settings.json");
+    /// ```
+    pub fn upload_from_reader<R: std::io::Read>(&self, reader: R, doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        self.upload_from_reader_with(reader, doc, RequestOptions::default())
+    }
 
-        // check if file is valid json
-        if !is_valid_json(&file_content) {
-            return Err(err_invalid_json());
+    /// Same as [upload_from_reader](#method.upload_from_reader) with call options.
+    pub fn upload_from_reader_with<R: std::io::Read>(&self, reader: R, doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("upload_from_reader");
+
+        match read_upload_reader(reader, doc) {
+            Ok(content) => self.create_document_with(content, options),
+            Err(err) => Err(err),
         }
+    }
 
-        // set name if not set
-        let name = if doc.name.is_none() {
-            file_path.file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string()
-        } else {
-            doc.name.unwrap()
-        };
+    /// Upload a json document from a byte buffer.
+    /// The name of the document is `name`, or the file name of `file_path` when `name` is not set.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use jsonbank::structs::UploadDocumentBody;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let res = jsb.upload_bytes(br#"{"debug": true}"#, UploadDocumentBody {
+    ///     file_path: "config/debug.json".to_string(),
+    ///     project: "test".to_string(),
+    ///     name: None,
+    ///     folder: None,
+    /// }).unwrap();
+    ///
+    /// assert_eq!(res.name, "debug.json");
+    /// ```
+    pub fn upload_bytes(&self, bytes: &[u8], doc: UploadDocumentBody) -> Result<NewDocument, JsbError> {
+        self.upload_bytes_with(bytes, doc, RequestOptions::default())
+    }
 
-        self.create_document(CreateDocumentBody {
-            name,
-            project: doc.project,
-            content: file_content,
-            folder: doc.folder,
-        })
+    /// Same as [upload_bytes](#method.upload_bytes) with call options.
+    pub fn upload_bytes_with(&self, bytes: &[u8], doc: UploadDocumentBody, options: RequestOptions) -> Result<NewDocument, JsbError> {
+        let _span = trace::operation("upload_bytes");
+
+        match read_upload_bytes(bytes, doc) {
+            Ok(content) => self.create_document_with(content, options),
+            Err(err) => Err(err),
+        }
     }
 
     /// Delete a document
@@ -892,22 +1324,15 @@ impl JsonBank {
     /// assert_eq!(res.deleted, true);
     /// ```
     pub fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError> {
-        match self.delete_request::<JsonObject>(vec!["file", id_or_path]) {
-            Ok(res) => {
-                // convert to DeletedDocument
-                Ok(DeletedDocument {
-                    deleted: res["deleted"].as_bool().unwrap_or(false),
-                })
-            }
-            Err(err) => {
-                // if error code is `notFound` return DeletedDocument with deleted = false
-                if err.code == "notFound" {
-                    Ok(DeletedDocument { deleted: false })
-                } else {
-                    Err(err)
-                }
-            }
-        }
+        self.delete_document_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [delete_document](#method.delete_document) with call options.
+    pub fn delete_document_with(&self, id_or_path: &str, options: RequestOptions) -> Result<DeletedDocument, JsbError> {
+        let res = self.send_request::<DeletedDocument>(self.requests_with(options).delete_document(id_or_path));
+        self.invalidate(id_or_path);
+
+        operations::deleted(res)
     }
 
     /// Create a folder
@@ -928,57 +1353,17 @@ impl JsonBank {
     /// assert_eq!(res.project, "project");
     /// ```
     pub fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError> {
-        // project is required
-        if data.project.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Project required".to_string(),
-            });
-        }
-
-        // name is required
-        if data.name.is_empty() {
-            return Err(JsbError {
-                code: "bad_request".to_string(),
-                message: "Name required".to_string(),
-            });
-        }
-
-        // create body
-        let body = JsonObject::from([
-            ("name".to_string(), JsonValue::String(data.name)),
-            ("project".to_string(), JsonValue::String(data.project.clone())),
-        ]);
+        self.create_folder_with(data, RequestOptions::default())
+    }
 
-        // send request
-        let url = vec!["project", &data.project, "folder"];
-        match self.write_request::<JsonObject>(url, Some(body)) {
-            Ok(res) => {
-                // convert to NewFolder
-                Ok(json_object_to_folder(&res))
-            }
-            Err(err) => Err(err),
-        }
+    /// Same as [create_folder](#method.create_folder) with call options.
+    pub fn create_folder_with(&self, data: CreateFolderBody, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.send_request(self.requests_with(options).create_folder(data))
     }
 
     //  private _get_folder - get a folder
-    fn ___get_folder(&self, id_or_path: &str, include_stats: bool) -> Result<Folder, JsbError> {
-        // create query
-        let query = if include_stats {
-            Some(JsonObject::from([
-                ("stats".to_string(), JsonValue::Bool(true)),
-            ]))
-        } else {
-            None
-        };
-
-        match self.read_request::<JsonObject>(vec!["folder", id_or_path], query) {
-            Ok(res) => {
-                // convert to Folder
-                Ok(json_object_to_folder(&res))
-            }
-            Err(err) => Err(err),
-        }
+    fn ___get_folder(&self, id_or_path: &str, include_stats: bool, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.send_request(self.requests_with(options).get_folder(id_or_path, include_stats))
     }
 
     /// Get a folder
@@ -990,7 +1375,12 @@ impl JsonBank {
     /// println!("Folder name: {}", res.name);
     /// ```
     pub fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError> {
-        self.___get_folder(id_or_path, false)
+        self.get_folder_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_folder](#method.get_folder) with call options.
+    pub fn get_folder_with(&self, id_or_path: &str, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.___get_folder(id_or_path, false, options)
     }
 
     /// Get a folder with statistics count
@@ -1005,7 +1395,12 @@ impl JsonBank {
     /// println!("Folder folders count: {}", stats.folders);
     /// ```
     pub fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError> {
-        self.___get_folder(id_or_path, true)
+        self.get_folder_with_stats_with(id_or_path, RequestOptions::default())
+    }
+
+    /// Same as [get_folder_with_stats](#method.get_folder_with_stats) with call options.
+    pub fn get_folder_with_stats_with(&self, id_or_path: &str, options: RequestOptions) -> Result<Folder, JsbError> {
+        self.___get_folder(id_or_path, true, options)
     }
 
     /// Create a folder if it does not exist
@@ -1035,21 +1430,73 @@ impl JsonBank {
     /// assert_eq!(res.project, "project");
     /// ```
     pub fn create_folder_if_not_exists(&self, data: CreateFolderBody) -> Result<(Folder, bool), JsbError> {
-        match self.create_folder(data.clone()) {
-            Ok(res) => Ok((res, false)),
-            Err(err) => {
-                // check if error code is name.exists
-                if err.code == "name.exists" {
-                    let folder_path = make_folder_path(&data);
-                    // get folder
-                    match self.get_folder(folder_path.as_str()) {
-                        Ok(res) => Ok((res, true)),
-                        Err(err) => Err(err),
-                    }
-                } else {
-                    Err(err)
-                }
-            }
+        self.create_folder_if_not_exists_with(data, RequestOptions::default())
+    }
+
+    /// Same as [create_folder_if_not_exists](#method.create_folder_if_not_exists) with call options.
+    pub fn create_folder_if_not_exists_with(&self, data: CreateFolderBody, options: RequestOptions) -> Result<(Folder, bool), JsbError> {
+        let _span = trace::operation("create_folder_if_not_exists");
+
+        let res = self.create_folder_with(data.clone(), options.clone());
+
+        match operations::if_not_exists(res, || make_folder_path(&data)) {
+            IfNotExists::Done(res) => res.map(|folder| (folder, false)),
+            IfNotExists::Read(folder_path) => Ok((self.get_folder_with(folder_path.as_str(), options)?, true)),
         }
     }
-}
\ No newline at end of file
+
+    /// Create every missing folder of a `project/a/b/c` path, like `mkdir -p`.
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// # let jsb = JsonBank::new_without_config();
+    /// let res = jsb.ensure_folder_path("project/configs/prod/eu").unwrap();
+    ///
+    /// assert_eq!(res.folder.path, "configs/prod/eu");
+    /// // the folders that did not exist yet, e.g. ["configs/prod", "configs/prod/eu"]
+    /// println!("{:?}", res.created);
+    /// ```
+    pub fn ensure_folder_path(&self, path: &str) -> Result<EnsuredFolder, JsbError> {
+        self.ensure_folder_path_with(path, RequestOptions::default())
+    }
+
+    /// Same as [ensure_folder_path](#method.ensure_folder_path) with call options, they apply to every request.
+    pub fn ensure_folder_path_with(&self, path: &str, options: RequestOptions) -> Result<EnsuredFolder, JsbError> {
+        let _span = trace::operation("ensure_folder_path");
+
+        let mut folders = FolderPath::new(path)?;
+
+        while let Some(data) = folders.next_folder() {
+            let (folder, exists) = self.create_folder_if_not_exists_with(data, options.clone())?;
+            folders.add(folder, exists);
+        }
+
+        folders.finish()
+    }
+
+    /// Mirror the json files of a local directory to a project.
+    /// Sub directories become folders, documents are created or updated when their content changed.
+    /// Hidden files and files without the `.json` extension are skipped.
+    ///
+    /// `delete` needs a listing of the project, which the api does not provide,
+    /// so it fails with [ErrorKind::Unsupported](error/enum.ErrorKind.html#variant.Unsupported).
+    /// # Example:
+    /// ```no_run
+    /// # use jsonbank::JsonBank;
+    /// use std::path::Path;
+    /// use jsonbank::structs::SyncOptions;
+    /// # let jsb = JsonBank::new_without_config();
+    /// // show what would change
+    /// let plan = jsb.sync_directory(Path::new("./configs"), "project", SyncOptions {
+    ///     dry_run: true,
+    ///     ..Default::default()
+    /// }).unwrap();
+    /// println!("{}", plan);
+    ///
+    /// // apply it
+    /// let report = jsb.sync_directory(Path::new("./configs"), "project", SyncOptions::default()).unwrap();
+    /// ```
+    pub fn sync_directory(&self, local_dir: &std::path::Path, project: &str, options: SyncOptions) -> Result<SyncReport, JsbError> {
+        <Self as JsonBankApi>::sync_directory(self, local_dir, project, options)
+    }
+}
@@ -0,0 +1,196 @@
+//! Set a [MetricsSink](trait.MetricsSink.html) with [JsonBankBuilder::metrics](../struct.JsonBankBuilder.html#method.metrics)
+//! to receive a [RequestMetrics](struct.RequestMetrics.html) after every request, e.g. to export counters and
+//! latency histograms. With the `metrics` feature, [MetricsCrateSink](struct.MetricsCrateSink.html) forwards them
+//! to the [metrics](https://docs.rs/metrics) crate facade.
+//! # Example
+//! ```
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use jsonbank::JsonBank;
+//! use jsonbank::metrics::{MetricsSink, RequestMetrics};
+//!
+//! #[derive(Default)]
+//! struct Errors(AtomicU64);
+//!
+//! impl MetricsSink for Errors {
+//!     fn record(&self, metrics: &RequestMetrics) {
+//!         if metrics.error.is_some() {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! let jsb = JsonBank::builder().metrics(Errors::default()).build().unwrap();
+//! ```
+
+use std::time::{Duration, Instant};
+use crate::error::{ErrorKind, JsbError};
+use crate::request::{process_response_error, ApiRequest, ApiResponse};
+
+/// Receives the metrics of every request sent by an instance.
+///
+/// `record` is called on the thread that sent the request, after the last retry, so it should not block.
+pub trait MetricsSink: Send + Sync {
+    /// Record the outcome of a request
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+/// The outcome of a single request, including its retries.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RequestMetrics {
+    /// Name of the api operation, e.g. `get_own_content`
+    pub operation: &'static str,
+    /// The http method
+    pub method: String,
+    /// The http status of the last response, `None` when no response was received
+    pub status: Option<u16>,
+    /// The class of `status`
+    pub status_class: StatusClass,
+    /// The kind of the error, `None` when the request succeeded
+    pub error: Option<ErrorKind>,
+    /// Size of the json body sent, 0 for requests without a body
+    pub bytes_sent: u64,
+    /// Size of the last response body
+    pub bytes_received: u64,
+    /// Time from sending the first attempt until the last response was read
+    pub duration: Duration,
+    /// How many times the request was sent again
+    pub retries: u32,
+}
+
+/// Class of an http status, a label with few values for metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusClass {
+    /// 1xx
+    Informational,
+    /// 2xx
+    Success,
+    /// 3xx, e.g. `304 Not Modified` of a conditional request
+    Redirection,
+    /// 4xx
+    ClientError,
+    /// 5xx
+    ServerError,
+    /// No response was received, e.g. a connection error or a timeout
+    NoResponse,
+}
+
+impl StatusClass {
+    /// The class of an http status
+    pub fn from_status(status: u16) -> StatusClass {
+        match status {
+            100..=199 => StatusClass::Informational,
+            200..=299 => StatusClass::Success,
+            300..=399 => StatusClass::Redirection,
+            400..=499 => StatusClass::ClientError,
+            _ => StatusClass::ServerError,
+        }
+    }
+
+    /// The class as a label, e.g. `2xx`, or `none` when no response was received
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusClass::Informational => "1xx",
+            StatusClass::Success => "2xx",
+            StatusClass::Redirection => "3xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::NoResponse => "none",
+        }
+    }
+}
+
+// Measures one request for a sink
+pub(crate) struct RequestTimer {
+    start: Instant,
+}
+
+impl RequestTimer {
+    pub fn start() -> Self {
+        RequestTimer { start: Instant::now() }
+    }
+
+    // finish - passes the outcome of the last attempt to the sink
+    pub fn finish(&self, sink: &dyn MetricsSink, req: &ApiRequest, attempts: u32, res: &Result<ApiResponse, JsbError>) {
+        let (status, error, bytes_received) = match res {
+            // a 304 answers a conditional request, it is not an error
+            Ok(res) if res.status.is_success() || res.status.is_redirection() => (Some(res.status.as_u16()), None, res.body.len()),
+            Ok(res) => {
+                let kind = process_response_error::<()>(res).err().map(|err| err.kind);
+                (Some(res.status.as_u16()), kind, res.body.len())
+            }
+            Err(err) => (err.status, Some(err.kind), 0),
+        };
+
+        sink.record(&RequestMetrics {
+            operation: req.operation,
+            method: req.method.to_string(),
+            status,
+            status_class: status.map_or(StatusClass::NoResponse, StatusClass::from_status),
+            error,
+            bytes_sent: body_size(req),
+            bytes_received: bytes_received as u64,
+            duration: self.start.elapsed(),
+            retries: attempts - 1,
+        });
+    }
+}
+
+// body_size - size of the json body of a post request, other requests send it as query
+fn body_size(req: &ApiRequest) -> u64 {
+    if req.method != reqwest::Method::POST {
+        return 0;
+    }
+
+    match &req.body {
+        Some(body) => serde_json::to_vec(body).map_or(0, |body| body.len() as u64),
+        None => 2, // `{}`
+    }
+}
+
+/// Forwards request metrics to the [metrics](https://docs.rs/metrics) crate facade.
+///
+/// Emits, labelled with `operation`, `status_class` and `error` (the error kind or `none`):
+/// - counter `jsonbank_requests_total`
+/// - histogram `jsonbank_request_duration_seconds`
+/// - counters `jsonbank_request_bytes_sent_total` and `jsonbank_request_bytes_received_total`, labelled with `operation`
+/// - counter `jsonbank_request_retries_total`, labelled with `operation`
+///
+/// Only available with the `metrics` feature.
+/// # Example
+/// ```
+/// use jsonbank::JsonBank;
+/// use jsonbank::metrics::MetricsCrateSink;
+///
+/// // install a recorder, e.g. `metrics-exporter-prometheus`, before
+/// let jsb = JsonBank::builder().metrics(MetricsCrateSink::new()).build().unwrap();
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsCrateSink;
+
+#[cfg(feature = "metrics")]
+impl MetricsCrateSink {
+    /// Create a sink that uses the globally installed recorder
+    pub fn new() -> Self {
+        MetricsCrateSink
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsSink for MetricsCrateSink {
+    fn record(&self, metrics: &RequestMetrics) {
+        let labels = [
+            ("operation", metrics.operation.to_string()),
+            ("status_class", metrics.status_class.as_str().to_string()),
+            ("error", metrics.error.map_or("none".to_string(), |kind| format!("{:?}", kind))),
+        ];
+        let operation = [("operation", metrics.operation.to_string())];
+
+        ::metrics::counter!("jsonbank_requests_total", &labels).increment(1);
+        ::metrics::histogram!("jsonbank_request_duration_seconds", &labels).record(metrics.duration.as_secs_f64());
+        ::metrics::counter!("jsonbank_request_bytes_sent_total", &operation).increment(metrics.bytes_sent);
+        ::metrics::counter!("jsonbank_request_bytes_received_total", &operation).increment(metrics.bytes_received);
+        ::metrics::counter!("jsonbank_request_retries_total", &operation).increment(metrics.retries as u64);
+    }
+}
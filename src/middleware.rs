@@ -0,0 +1,113 @@
+//! Every attempt of a request passes an ordered chain of [Middleware](trait.Middleware.html), added with
+//! [JsonBankBuilder::middleware](../struct.JsonBankBuilder.html#method.middleware).
+//! `on_request` is called in the order the middleware were added and may change the request or answer it
+//! without sending it. `on_response` is then called in reverse order, only on the middleware whose
+//! `on_request` let the request pass, and may change or replace the response or the error.
+//!
+//! The api keys are added by a built-in middleware that always comes first,
+//! so every added middleware sees the headers that are sent.
+//! # Example
+//! ```
+//! use jsonbank::JsonBank;
+//! use jsonbank::error::JsbError;
+//! use jsonbank::middleware::{header::HeaderValue, ApiRequest, ApiResponse, Middleware};
+//!
+//! // sends a request id with every request
+//! struct RequestId;
+//!
+//! impl Middleware for RequestId {
+//!     fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+//!         req.headers.insert("X-Request-Id", HeaderValue::from_static("f3b2c1"));
+//!         Ok(None)
+//!     }
+//! }
+//!
+//! let jsb = JsonBank::builder().middleware(RequestId).build().unwrap();
+//! ```
+
+use std::sync::Arc;
+use reqwest::header::HeaderValue;
+use crate::error::*;
+use crate::request::{PRIVATE_KEY_HEADER, PUBLIC_KEY_HEADER};
+
+pub use reqwest::header;
+pub use reqwest::{Method, StatusCode};
+pub use crate::request::{ApiRequest, ApiResponse};
+
+/// A step of the request pipeline, see the [module](index.html) docs.
+///
+/// Both methods are called for every attempt of a request, retries included,
+/// and are used by the blocking and the async client, so they should not block.
+pub trait Middleware: Send + Sync {
+    /// Inspect or change the outgoing request.
+    /// Return a response or an error to answer the request without sending it.
+    fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+        let _ = req;
+        Ok(None)
+    }
+
+    /// Inspect, change or replace the response or the error of the request.
+    fn on_response(&self, req: &ApiRequest, res: Result<ApiResponse, JsbError>) -> Result<ApiResponse, JsbError> {
+        let _ = req;
+        res
+    }
+}
+
+// ApiKeys - adds the keys a request requires as `jsb-pub-key` and `jsb-prv-key` headers
+struct ApiKeys;
+
+impl Middleware for ApiKeys {
+    fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+        if let Some(key) = &req.keys.public {
+            let value = key_header(key, "Public")?;
+            req.headers.insert(PUBLIC_KEY_HEADER, value);
+        }
+
+        if let Some(key) = &req.keys.private {
+            let value = key_header(key, "Private")?;
+            req.headers.insert(PRIVATE_KEY_HEADER, value);
+        }
+
+        Ok(None)
+    }
+}
+
+// key_header - the header value of a key, keys set per call are not validated by the builder
+fn key_header(key: &str, name: &str) -> Result<HeaderValue, JsbError> {
+    match HeaderValue::from_str(key) {
+        Ok(value) => Ok(value),
+        Err(_) => Err(err_invalid_config(&format!("{} key contains invalid characters", name))),
+    }
+}
+
+/// The middleware chain of an instance, starting with the api keys.
+pub(crate) struct Chain {
+    middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl Chain {
+    pub fn new(added: Vec<Arc<dyn Middleware>>) -> Chain {
+        let mut middleware: Vec<Arc<dyn Middleware>> = vec![Arc::new(ApiKeys)];
+        middleware.extend(added);
+        Chain { middleware }
+    }
+
+    // before - calls `on_request` in order until a middleware answers the request,
+    // returns how many middleware the request passed and the answer, if any
+    pub fn before(&self, req: &mut ApiRequest) -> (usize, Option<Result<ApiResponse, JsbError>>) {
+        for (passed, middleware) in self.middleware.iter().enumerate() {
+            match middleware.on_request(req) {
+                Ok(None) => {}
+                Ok(Some(res)) => return (passed, Some(Ok(res))),
+                Err(err) => return (passed, Some(Err(err))),
+            }
+        }
+
+        (self.middleware.len(), None)
+    }
+
+    // after - calls `on_response` of the middleware the request passed, in reverse order
+    pub fn after(&self, passed: usize, req: &ApiRequest, res: Result<ApiResponse, JsbError>) -> Result<ApiResponse, JsbError> {
+        self.middleware[..passed].iter().rev().fold(res, |res, middleware| middleware.on_response(req, res))
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{content_hash, JsonBankApi, JsonPatch, JsonValue};
+use crate::error::*;
+use crate::structs::*;
+
+/// How many times `modify_document` reads and writes a document before it returns the conflict.
+pub(crate) const MODIFY_ATTEMPTS: usize = 5;
+
+// The operations below read and write a document in several steps. They do not send anything:
+// the client that runs one sends each step and passes the reply back, so the blocking client,
+// the async client and the stores share the same sequence.
+
+/// A step of an [Operation].
+pub(crate) enum Step {
+    /// Read the meta of the document.
+    Meta,
+    /// Read the content of the document from the backend, not from a cache.
+    Content,
+    /// Update the document if it is still at the expected version.
+    UpdateAfterCheck(String, ExpectedVersion),
+}
+
+/// A step of an operation run with [CheckFirst], the update after check is split into a read and an update.
+pub(crate) enum Request {
+    Meta,
+    Content,
+    Update(String),
+}
+
+/// The reply to a step or a request.
+pub(crate) enum Reply {
+    Meta(DocumentMeta),
+    Content(String),
+    Updated(UpdatedDocument),
+}
+
+/// What an operation does next.
+pub(crate) enum Next<S> {
+    Send(S),
+    Done(Result<UpdatedDocument, JsbError>),
+}
+
+/// An operation made of steps, see [run] and [run_check_first].
+pub(crate) trait Operation {
+    /// The first step.
+    fn start(&mut self) -> Next<Step>;
+
+    /// The step after the reply to the previous one.
+    fn next(&mut self, reply: Result<Reply, JsbError>) -> Next<Step>;
+}
+
+/// `update_own_document_after_check`, a single update.
+pub(crate) struct AfterCheck {
+    content: String,
+    expected: ExpectedVersion,
+}
+
+impl AfterCheck {
+    pub fn new(content: String, expected: &ExpectedVersion) -> Self {
+        AfterCheck { content, expected: expected.clone() }
+    }
+}
+
+impl Operation for AfterCheck {
+    fn start(&mut self) -> Next<Step> {
+        Next::Send(Step::UpdateAfterCheck(std::mem::take(&mut self.content), self.expected.clone()))
+    }
+
+    fn next(&mut self, reply: Result<Reply, JsbError>) -> Next<Step> {
+        match reply {
+            Ok(Reply::Updated(res)) => Next::Done(Ok(res)),
+            Ok(_) => unexpected(),
+            Err(err) => Next::Done(Err(err)),
+        }
+    }
+}
+
+/// `modify_document`, reads the meta and the content, then writes the modified content if the meta did not change.
+/// A conflict starts over, up to `MODIFY_ATTEMPTS` times.
+pub(crate) struct Modify<F> {
+    modify: F,
+    attempt: usize,
+    expected: Option<ExpectedVersion>,
+}
+
+impl<F: FnMut(&mut JsonValue)> Modify<F> {
+    pub fn new(modify: F) -> Self {
+        Modify { modify, attempt: 1, expected: None }
+    }
+}
+
+impl<F: FnMut(&mut JsonValue)> Operation for Modify<F> {
+    fn start(&mut self) -> Next<Step> {
+        Next::Send(Step::Meta)
+    }
+
+    fn next(&mut self, reply: Result<Reply, JsbError>) -> Next<Step> {
+        match reply {
+            // the meta is read first, so the cheap check of its `updated_at` covers the content read after it
+            Ok(Reply::Meta(meta)) => {
+                self.expected = Some(ExpectedVersion::from(&meta));
+                Next::Send(Step::Content)
+            }
+            Ok(Reply::Content(content)) => match (modified_content(&content, &mut self.modify), self.expected.take()) {
+                (Ok(content), Some(expected)) => Next::Send(Step::UpdateAfterCheck(content, expected)),
+                (Err(err), _) => Next::Done(Err(err)),
+                (Ok(_), None) => unexpected(),
+            },
+            Ok(Reply::Updated(res)) => Next::Done(Ok(res)),
+            // another client changed the document since it was read
+            Err(err) if err.kind == ErrorKind::Conflict && self.attempt < MODIFY_ATTEMPTS => {
+                self.attempt += 1;
+                Next::Send(Step::Meta)
+            }
+            Err(err) => Next::Done(Err(err)),
+        }
+    }
+}
+
+/// `patch_own_document` and `merge_own_document`, like [Modify] but the change can fail,
+/// is applied once and nothing is written if it did not change the document.
+pub(crate) struct Change<F> {
+    change: Option<F>,
+    expected: Option<ExpectedVersion>,
+}
+
+impl<F: FnOnce(&mut JsonValue) -> Result<(), JsbError>> Change<F> {
+    pub fn new(change: F) -> Self {
+        Change { change: Some(change), expected: None }
+    }
+}
+
+impl<F: FnOnce(&mut JsonValue) -> Result<(), JsbError>> Operation for Change<F> {
+    fn start(&mut self) -> Next<Step> {
+        Next::Send(Step::Meta)
+    }
+
+    fn next(&mut self, reply: Result<Reply, JsbError>) -> Next<Step> {
+        match reply {
+            Ok(Reply::Meta(meta)) => {
+                self.expected = Some(ExpectedVersion::from(&meta));
+                Next::Send(Step::Content)
+            }
+            Ok(Reply::Content(content)) => match (self.change.take(), self.expected.take()) {
+                (Some(change), Some(expected)) => match changed_content(&content, change) {
+                    Ok(Some(content)) => Next::Send(Step::UpdateAfterCheck(content, expected)),
+                    Ok(None) => Next::Done(Ok(UpdatedDocument { changed: false })),
+                    Err(err) => Next::Done(Err(err)),
+                },
+                _ => unexpected(),
+            },
+            Ok(Reply::Updated(res)) => Next::Done(Ok(res)),
+            Err(err) => Next::Done(Err(err)),
+        }
+    }
+}
+
+/// Runs an operation on a backend without a conditional update, the version is checked by a request right
+/// before the update. Best effort: a change made between the check and the update is not detected.
+pub(crate) struct CheckFirst<'a, O> {
+    op: O,
+    id_or_path: &'a str,
+    // the update waiting for the check
+    update: Option<(String, ExpectedVersion)>,
+}
+
+impl<'a, O: Operation> CheckFirst<'a, O> {
+    pub fn new(id_or_path: &'a str, op: O) -> Self {
+        CheckFirst { op, id_or_path, update: None }
+    }
+
+    /// The first request.
+    pub fn start(&mut self) -> Next<Request> {
+        let next = self.op.start();
+        self.request(next)
+    }
+
+    /// The request after the reply to the previous one.
+    pub fn next(&mut self, reply: Result<Reply, JsbError>) -> Next<Request> {
+        let reply = match self.update.take() {
+            Some((content, expected)) => match reply.and_then(|reply| check_unchanged(self.id_or_path, &expected, &reply)) {
+                Ok(()) => return Next::Send(Request::Update(content)),
+                Err(err) => Err(err),
+            },
+            None => reply,
+        };
+
+        let next = self.op.next(reply);
+        self.request(next)
+    }
+
+    // request - the request of a step, an update after check reads the version first
+    fn request(&mut self, next: Next<Step>) -> Next<Request> {
+        match next {
+            Next::Send(Step::Meta) => Next::Send(Request::Meta),
+            Next::Send(Step::Content) => Next::Send(Request::Content),
+            Next::Send(Step::UpdateAfterCheck(content, expected)) => {
+                let read = match expected {
+                    ExpectedVersion::UpdatedAt(_) => Request::Meta,
+                    ExpectedVersion::ContentHash(_) => Request::Content,
+                };
+                self.update = Some((content, expected));
+                Next::Send(read)
+            }
+            Next::Done(res) => Next::Done(res),
+        }
+    }
+}
+
+/// Runs an operation with the methods of `api`. An update after check is sent to
+/// `update_own_document_after_check`, so a store that checks the version atomically does so.
+pub(crate) fn run<A: JsonBankApi + ?Sized, O: Operation>(api: &A, id_or_path: &str, mut op: O) -> Result<UpdatedDocument, JsbError> {
+    let mut next = op.start();
+
+    loop {
+        let reply = match next {
+            Next::Send(Step::Meta) => api.get_own_document_meta(id_or_path).map(Reply::Meta),
+            Next::Send(Step::Content) => api.get_own_content_uncached(id_or_path).map(Reply::Content),
+            Next::Send(Step::UpdateAfterCheck(content, expected)) => {
+                api.update_own_document_after_check(id_or_path, content, &expected).map(Reply::Updated)
+            }
+            Next::Done(res) => return res,
+        };
+
+        next = op.next(reply);
+    }
+}
+
+/// Runs an operation with [CheckFirst], `send` sends each request.
+/// The async client has the same loop with an `.await` on every request.
+pub(crate) fn run_check_first<O, S>(id_or_path: &str, op: O, mut send: S) -> Result<UpdatedDocument, JsbError>
+where
+    O: Operation,
+    S: FnMut(Request) -> Result<Reply, JsbError>,
+{
+    let mut op = CheckFirst::new(id_or_path, op);
+    let mut next = op.start();
+
+    loop {
+        let reply = match next {
+            Next::Send(request) => send(request),
+            Next::Done(res) => return res,
+        };
+
+        next = op.next(reply);
+    }
+}
+
+// check_unchanged - fails with a conflict if the version read before an update is not the `expected` one
+fn check_unchanged(id_or_path: &str, expected: &ExpectedVersion, current: &Reply) -> Result<(), JsbError> {
+    let unchanged = match (expected, current) {
+        (ExpectedVersion::UpdatedAt(updated_at), Reply::Meta(meta)) => *updated_at == meta.updated_at,
+        (ExpectedVersion::ContentHash(hash), Reply::Content(content)) => *hash == content_hash(content),
+        _ => return Err(err_unexpected_reply()),
+    };
+
+    if !unchanged {
+        return Err(err_conflict(id_or_path));
+    }
+
+    Ok(())
+}
+
+// unexpected - the reply does not answer the step that was sent, a bug of the client that runs the operation
+fn unexpected() -> Next<Step> {
+    Next::Done(Err(err_unexpected_reply()))
+}
+
+fn err_unexpected_reply() -> JsbError {
+    JsbError::new(ErrorKind::Other, "unexpected_reply", "The reply does not match the step of the operation")
+}
+
+// modified_content - applies `modify` to the content `modify_document` read, returns the content to write.
+fn modified_content<F: FnMut(&mut JsonValue)>(content: &str, modify: &mut F) -> Result<String, JsbError> {
+    let mut value: JsonValue = serde_json::from_str(content)?;
+    modify(&mut value);
+
+    Ok(value.to_string())
+}
+
+// changed_content - applies `change` to `content`, returns the new content or `None` if nothing changed.
+fn changed_content<F>(content: &str, change: F) -> Result<Option<String>, JsbError>
+where
+    F: FnOnce(&mut JsonValue) -> Result<(), JsbError>,
+{
+    let original: JsonValue = serde_json::from_str(content)?;
+    let mut value = original.clone();
+    change(&mut value)?;
+
+    if value == original {
+        return Ok(None);
+    }
+
+    Ok(Some(value.to_string()))
+}
+
+// patch - the change of `patch_own_document`
+pub(crate) fn patch(value: &mut JsonValue, ops: &JsonPatch) -> Result<(), JsbError> {
+    json_patch::patch(value, ops)?;
+    Ok(())
+}
+
+// merge - the change of `merge_own_document`
+pub(crate) fn merge(value: &mut JsonValue, partial: &JsonValue) -> Result<(), JsbError> {
+    json_patch::merge(value, partial);
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Method;
+use crate::{Config, JsonObject, JsonValue, RequestOptions};
+use crate::error::*;
+use crate::functions::{is_valid_json, split_folder_path, validate_create_document, validate_create_folder};
+use crate::request::{build_request, ApiRequest, Endpoints, PRIVATE_KEY_HEADER, PUBLIC_KEY_HEADER};
+use crate::structs::{CreateDocumentBody, CreateFolderBody, DeletedDocument, EnsuredFolder, Folder};
+
+/// Header of `RequestOptions::idempotency_key`
+const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// Builds the requests of every api operation.
+/// Validation, urls and bodies live here so the blocking and async clients cannot drift apart.
+pub(crate) struct Requests<'a> {
+    config: &'a Config,
+    endpoints: &'a Endpoints,
+    options: RequestOptions,
+}
+
+impl<'a> Requests<'a> {
+    pub fn new(config: &'a Config, endpoints: &'a Endpoints, options: RequestOptions) -> Self {
+        Requests { config, endpoints, options }
+    }
+
+    // build - builds a request of an operation with the keys and the call options
+    fn build(&self, operation: &'static str, method: Method, url: String, body: Option<JsonObject>, require_pub_key: bool, require_prv_key: bool) -> Result<ApiRequest, JsbError> {
+        // keys of the call replace the keys of the instance
+        let keys = self.options.keys.as_ref().or(self.config.keys.as_ref());
+        let mut req = build_request(keys, method, url, body, require_pub_key, require_prv_key)?;
+
+        for (name, value) in &self.options.headers {
+            let name = match HeaderName::from_bytes(name.as_bytes()) {
+                Ok(name) if name != PUBLIC_KEY_HEADER && name != PRIVATE_KEY_HEADER => name,
+                Ok(_) => return Err(JsbError::new(ErrorKind::Validation, "bad_request", "Api keys can not be set as headers, use keys")),
+                Err(_) => return Err(JsbError::new(ErrorKind::Validation, "bad_request", &format!("Invalid header name: {}", name))),
+            };
+
+            match HeaderValue::from_str(value) {
+                Ok(value) => req.headers.insert(name, value),
+                Err(_) => return Err(JsbError::new(ErrorKind::Validation, "bad_request", &format!("Invalid value of header {}", name))),
+            };
+        }
+
+        if let Some(key) = &self.options.idempotency_key {
+            match HeaderValue::from_str(key) {
+                Ok(value) => req.headers.insert(IDEMPOTENCY_KEY, value),
+                Err(_) => return Err(JsbError::new(ErrorKind::Validation, "bad_request", "Invalid idempotency key")),
+            };
+        }
+
+        req.operation = operation;
+        req.timeout = self.options.timeout;
+        req.retry = self.options.retry.clone();
+        Ok(req)
+    }
+
+    // public - get request to public endpoint
+    fn public(&self, operation: &'static str, url: Vec<&str>) -> Result<ApiRequest, JsbError> {
+        self.build(operation, Method::GET, self.endpoints.public_url(url), None, false, false)
+    }
+
+    // read - get request to auth required endpoints using public key
+    fn read(&self, operation: &'static str, url: Vec<&str>, query: Option<JsonObject>) -> Result<ApiRequest, JsbError> {
+        self.build(operation, Method::GET, self.endpoints.v1_url(url), query, true, false)
+    }
+
+    // read_post - post request to auth required endpoints using public key
+    fn read_post(&self, operation: &'static str, url: Vec<&str>, body: Option<JsonObject>) -> Result<ApiRequest, JsbError> {
+        let mut req = self.build(operation, Method::POST, self.endpoints.v1_url(url), body, true, false)?;
+        // reads do not change anything, so they are safe to retry
+        req.retryable = true;
+        Ok(req)
+    }
+
+    // write - post request to auth required endpoints using private key
+    fn write(&self, operation: &'static str, url: Vec<&str>, body: Option<JsonObject>) -> Result<ApiRequest, JsbError> {
+        let mut req = self.build(operation, Method::POST, self.endpoints.v1_url(url), body, false, true)?;
+        // writes are only retried when the caller opts in
+        req.retryable = self.options.retry_writes;
+        Ok(req)
+    }
+
+    // delete - delete request to auth required endpoints using private key
+    fn delete(&self, operation: &'static str, url: Vec<&str>) -> Result<ApiRequest, JsbError> {
+        self.build(operation, Method::DELETE, self.endpoints.v1_url(url), None, false, true)
+    }
+
+    pub fn get_document_meta(&self, id_or_path: &str) -> Result<ApiRequest, JsbError> {
+        self.public("get_document_meta", vec!["meta/f", id_or_path])
+    }
+
+    pub fn get_content(&self, id_or_path: &str) -> Result<ApiRequest, JsbError> {
+        self.public("get_content", vec!["f", id_or_path])
+    }
+
+    pub fn get_github_content(&self, path: &str) -> Result<ApiRequest, JsbError> {
+        self.public("get_github_content", vec!["gh", path])
+    }
+
+    pub fn authenticate(&self) -> Result<ApiRequest, JsbError> {
+        self.read_post("authenticate", vec!["authenticate"], None)
+    }
+
+    pub fn get_own_document_meta(&self, id_or_path: &str) -> Result<ApiRequest, JsbError> {
+        self.read("get_own_document_meta", vec!["meta/file", id_or_path], None)
+    }
+
+    pub fn get_own_content(&self, id_or_path: &str) -> Result<ApiRequest, JsbError> {
+        self.read("get_own_content", vec!["file", id_or_path], None)
+    }
+
+    pub fn create_document(&self, content: CreateDocumentBody) -> Result<ApiRequest, JsbError> {
+        validate_create_document(&content)?;
+
+        // convert content to hashmap
+        let mut body: JsonObject = HashMap::from([
+            ("name".to_string(), JsonValue::String(content.name)),
+            ("project".to_string(), JsonValue::String(content.project.clone())),
+            ("content".to_string(), JsonValue::String(content.content)),
+        ]);
+
+        // add folder if set
+        if let Some(folder) = content.folder {
+            body.insert("folder".to_string(), JsonValue::String(folder));
+        }
+
+        self.write("create_document", vec!["project", &content.project, "document"], Some(body))
+    }
+
+    pub fn update_own_document(&self, id_or_path: &str, content: String) -> Result<ApiRequest, JsbError> {
+        // check if content is a valid json
+        if !is_valid_json(&content) {
+            return Err(err_invalid_json());
+        }
+
+        // create body
+        let body = JsonObject::from([
+            ("content".to_string(), JsonValue::String(content)),
+        ]);
+
+        self.write("update_own_document", vec!["file", id_or_path], Some(body))
+    }
+
+    pub fn delete_document(&self, id_or_path: &str) -> Result<ApiRequest, JsbError> {
+        self.delete("delete_document", vec!["file", id_or_path])
+    }
+
+    pub fn create_folder(&self, data: CreateFolderBody) -> Result<ApiRequest, JsbError> {
+        validate_create_folder(&data)?;
+
+        // create body
+        let mut body = JsonObject::from([
+            ("name".to_string(), JsonValue::String(data.name)),
+            ("project".to_string(), JsonValue::String(data.project.clone())),
+        ]);
+
+        // add parent folder if set
+        if let Some(folder) = data.folder {
+            body.insert("folder".to_string(), JsonValue::String(folder));
+        }
+
+        self.write("create_folder", vec!["project", &data.project, "folder"], Some(body))
+    }
+
+    pub fn get_folder(&self, id_or_path: &str, include_stats: bool) -> Result<ApiRequest, JsbError> {
+        // create query
+        let query = if include_stats {
+            Some(JsonObject::from([
+                ("stats".to_string(), JsonValue::Bool(true)),
+            ]))
+        } else {
+            None
+        };
+
+        self.read("get_folder", vec!["folder", id_or_path], query)
+    }
+}
+
+// The steps of operations made of several requests, without sending anything.
+// Both clients and the default methods of `JsonBankApi` send the requests and pass the results back here.
+
+/// The next step of `create_document_if_not_exists` and `create_folder_if_not_exists` after the create request.
+pub(crate) enum IfNotExists<T> {
+    /// The create request succeeded, or failed for another reason than an existing name
+    Done(Result<T, JsbError>),
+    /// The name already exists, the document or folder at this path is read instead
+    Read(String),
+}
+
+// if_not_exists - decides if the result of a create request is final, `path` is the path of the created item
+pub(crate) fn if_not_exists<T, P: FnOnce() -> String>(res: Result<T, JsbError>, path: P) -> IfNotExists<T> {
+    match res {
+        Err(err) if err.kind == ErrorKind::AlreadyExists => IfNotExists::Read(path()),
+        res => IfNotExists::Done(res),
+    }
+}
+
+// exists - the result of `has_own_document`, a document that is not found does not exist
+pub(crate) fn exists<T>(res: Result<T, JsbError>) -> Result<bool, JsbError> {
+    match res {
+        Ok(_) => Ok(true),
+        Err(err) if err.kind == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+// deleted - the result of `delete_document`, deleting a document that is not found is not an error
+pub(crate) fn deleted(res: Result<DeletedDocument, JsbError>) -> Result<DeletedDocument, JsbError> {
+    match res {
+        Err(err) if err.kind == ErrorKind::NotFound => Ok(DeletedDocument { deleted: false }),
+        res => res,
+    }
+}
+
+/// The folders of a `project/a/b/c` path, created one after another by `ensure_folder_path`.
+pub(crate) struct FolderPath {
+    project: String,
+    names: std::vec::IntoIter<String>,
+    parent: Option<String>,
+    created: Vec<String>,
+    last: Option<Folder>,
+}
+
+impl FolderPath {
+    pub fn new(path: &str) -> Result<FolderPath, JsbError> {
+        let (project, names) = split_folder_path(path)?;
+        Ok(FolderPath { project, names: names.into_iter(), parent: None, created: Vec::new(), last: None })
+    }
+
+    // next_folder - the folder to create next, inside the previous one, None when the whole path exists
+    pub fn next_folder(&mut self) -> Option<CreateFolderBody> {
+        let name = self.names.next()?;
+        Some(CreateFolderBody { name, project: self.project.clone(), folder: self.parent.clone() })
+    }
+
+    // add - records the result of `create_folder_if_not_exists` for the folder returned by `next_folder`
+    pub fn add(&mut self, folder: Folder, exists: bool) {
+        if !exists {
+            self.created.push(folder.path.clone());
+        }
+
+        self.parent = Some(folder.path.clone());
+        self.last = Some(folder);
+    }
+
+    pub fn finish(self) -> Result<EnsuredFolder, JsbError> {
+        match self.last {
+            Some(folder) => Ok(EnsuredFolder { folder, created: self.created }),
+            None => Err(err_required("Folder")),
+        }
+    }
+}
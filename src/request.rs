@@ -0,0 +1,380 @@
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use crate::{JsonObject, JsonValue, Keys, RetryPolicy};
+use crate::error::*;
+use crate::structs::ConditionalContent;
+
+// configure_client - Applies the instance config to a reqwest `ClientBuilder`.
+// A macro because the blocking and async builders are different types with the same methods.
+// Evaluates to `Result<ClientBuilder, JsbError>`, the proxy url and the TLS config may be invalid.
+macro_rules! configure_client {
+    ($builder:expr, $config:expr) => {{
+        let config = $config;
+        let pool = &config.pool;
+        let mut builder = $builder;
+
+        if let Some(timeout) = pool.idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        if let Some(max) = pool.max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+
+        builder = match pool.http_version {
+            $crate::HttpVersion::Auto => builder,
+            $crate::HttpVersion::Http1Only => builder.http1_only(),
+            $crate::HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent.as_str());
+        }
+
+        let builder = match &config.proxy {
+            Some(proxy) => match reqwest::Proxy::all(proxy.as_str()) {
+                Ok(proxy) => Ok(builder.proxy(proxy)),
+                Err(err) => Err($crate::error::JsbError::from(err)),
+            },
+            None => Ok(builder),
+        };
+
+        let tls = &config.tls;
+
+        // root certificates are only available with a TLS feature
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        let builder = match (builder, $crate::request::root_certificates(tls)) {
+            (Ok(mut builder), Ok(certificates)) => {
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+
+                if tls.only_root_certificates {
+                    builder = builder.tls_built_in_root_certs(false);
+                }
+
+                Ok(builder)
+            }
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        };
+
+        #[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+        let builder = match builder {
+            Ok(_) if !tls.root_certificates.is_empty() || tls.only_root_certificates => {
+                Err($crate::error::err_invalid_config("Root certificates need the `native-tls` or `rustls` feature"))
+            }
+            builder => builder,
+        };
+
+        match builder {
+            Ok(builder) => match tls.backend {
+                $crate::TlsBackend::Auto => Ok(builder),
+                #[cfg(feature = "native-tls")]
+                $crate::TlsBackend::NativeTls => Ok(builder.use_native_tls()),
+                #[cfg(feature = "rustls")]
+                $crate::TlsBackend::Rustls => Ok(builder.use_rustls_tls()),
+                #[allow(unreachable_patterns)]
+                backend => Err($crate::error::err_invalid_config(&format!("The {:?} TLS backend is not enabled, see the cargo features", backend))),
+            },
+            Err(err) => Err(err),
+        }
+    }};
+}
+
+pub(crate) use configure_client;
+
+// http_request - Builds the reqwest request of an `ApiRequest` on a blocking or async client,
+// a macro for the same reason as `configure_client`. Evaluates to the `RequestBuilder`.
+macro_rules! http_request {
+    ($client:expr, $req:expr) => {{
+        let req: &$crate::request::ApiRequest = $req;
+        let mut builder = $client.request(req.method.clone(), &req.url).headers(req.headers.clone());
+
+        if let Some(timeout) = req.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        // post requests send the body as json, get requests send it as query
+        if req.method == reqwest::Method::POST {
+            builder = builder.json(req.body.as_ref().unwrap_or(&$crate::JsonObject::new()));
+        } else if let Some(query) = &req.body {
+            builder = builder.query(query);
+        }
+
+        builder
+    }};
+}
+
+pub(crate) use http_request;
+
+// client_error - The error a request returns when the http client of the instance could not be built
+pub(crate) fn client_error(err: &JsbError) -> JsbError {
+    JsbError::new(err.kind, &err.code, &err.message)
+}
+
+// root_certificates - Parses the PEM root certificates of the TLS config
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub(crate) fn root_certificates(tls: &crate::TlsConfig) -> Result<Vec<reqwest::Certificate>, JsbError> {
+    let mut certificates = Vec::new();
+
+    for pem in &tls.root_certificates {
+        match reqwest::Certificate::from_pem_bundle(pem.as_bytes()) {
+            Ok(bundle) if !bundle.is_empty() => certificates.extend(bundle),
+            Ok(_) => return Err(err_invalid_config("Root certificate does not contain a PEM certificate")),
+            Err(err) => return Err(err_invalid_config("Root certificate is not a valid PEM certificate").with_source(err)),
+        }
+    }
+
+    Ok(certificates)
+}
+
+/// Header of the public key
+pub(crate) const PUBLIC_KEY_HEADER: &str = "jsb-pub-key";
+/// Header of the private key
+pub(crate) const PRIVATE_KEY_HEADER: &str = "jsb-prv-key";
+
+// Endpoints struct - Endpoints
+pub(crate) struct Endpoints {
+    v1: String,
+    public: String,
+}
+
+impl Endpoints {
+    // Make Endpoints
+    pub fn new(host: &str) -> Endpoints {
+        Endpoints {
+            v1: format!("{}/v1", host),
+            public: host.to_string(),
+        }
+    }
+
+    // Format public url
+    pub fn public_url(&self, paths: Vec<&str>) -> String {
+        // add paths to public endpoint
+        format!("{}/{}", self.public, paths.join("/"))
+    }
+
+    // format v1 url
+    pub fn v1_url(&self, paths: Vec<&str>) -> String {
+        // add paths to v1 endpoint
+        format!("{}/{}", self.v1, paths.join("/"))
+    }
+}
+
+/// A prepared api request, passed through the [middleware](crate::middleware) chain.
+///
+/// It does not depend on any http client, so the same request can be sent
+/// by both the blocking and the async client.
+#[derive(Clone)]
+pub struct ApiRequest {
+    /// Name of the api operation, e.g. `get_own_content`
+    pub operation: &'static str,
+    /// The http method
+    pub method: Method,
+    /// The full url of the request
+    pub url: String,
+    /// Headers to send, the api keys are added by the first middleware
+    pub headers: HeaderMap,
+    /// Json body for `POST` requests or query for `GET` requests
+    pub body: Option<JsonObject>,
+    /// If the request can be sent again when it fails
+    pub(crate) retryable: bool,
+    /// Timeout of this request, the instance timeout is used when not set
+    pub(crate) timeout: Option<Duration>,
+    /// Retry policy of this request, the instance policy is used when not set
+    pub(crate) retry: Option<RetryPolicy>,
+    /// The keys the request requires, sent as headers by the `ApiKeys` middleware
+    pub(crate) keys: Keys,
+}
+
+/// A fully read api response, returned through the [middleware](crate::middleware) chain.
+pub struct ApiResponse {
+    /// The http status of the response
+    pub status: StatusCode,
+    /// The headers of the response
+    pub headers: HeaderMap,
+    /// The raw body of the response
+    pub body: Vec<u8>,
+}
+
+/// The `ETag` and `Last-Modified` of a response, sent back to only download content that changed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    // from_headers - reads the validators of a response
+    pub fn from_headers(headers: &HeaderMap) -> Validators {
+        let read = |name| headers.get(name).and_then(|value: &HeaderValue| value.to_str().ok()).map(|value| value.to_string());
+
+        Validators {
+            etag: read(ETAG),
+            last_modified: read(LAST_MODIFIED),
+        }
+    }
+
+    // is_empty - returns true if the response had no validator
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    // apply - makes a request conditional, `If-None-Match` wins over `If-Modified-Since` on the server
+    pub fn apply(&self, req: &mut ApiRequest) -> Result<(), JsbError> {
+        if let Some(etag) = &self.etag {
+            match HeaderValue::from_str(etag) {
+                Ok(value) => req.headers.insert(IF_NONE_MATCH, value),
+                Err(_) => return Err(JsbError::new(ErrorKind::Validation, "bad_request", "ETag contains invalid characters")),
+            };
+        }
+
+        if let Some(last_modified) = &self.last_modified {
+            if let Ok(value) = HeaderValue::from_str(last_modified) {
+                req.headers.insert(IF_MODIFIED_SINCE, value);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The content of a conditional request.
+pub(crate) enum Revalidated {
+    /// The content changed, or the request was not conditional
+    Modified(String, Validators),
+    /// `304`, the content did not change
+    NotModified,
+}
+
+// build_request - Builds a request with the required api keys, they are added to its headers by the `ApiKeys` middleware
+pub(crate) fn build_request(keys: Option<&Keys>, method: Method, url: String, body: Option<JsonObject>, require_pub_key: bool, require_prv_key: bool) -> Result<ApiRequest, JsbError> {
+    // add json header
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", "application/json".parse().unwrap());
+
+    let mut required = Keys::default();
+
+    // check if public key is required and not provided
+    if require_pub_key {
+        match keys.and_then(|keys| keys.get("public")) {
+            Some(key) => required.public = Some(key.to_string()),
+            None => return Err(err_missing_key("Public")),
+        };
+    }
+
+    // check if private key is required and not provided
+    if require_prv_key {
+        match keys.and_then(|keys| keys.get("private")) {
+            Some(key) => required.private = Some(key.to_string()),
+            None => return Err(err_missing_key("Private")),
+        };
+    }
+
+    // only idempotent requests are retried by default
+    let retryable = method == Method::GET || method == Method::DELETE;
+
+    Ok(ApiRequest { operation: "", method, url, headers, body, retryable, timeout: None, retry: None, keys: required })
+}
+
+// process_response_error - Processes response error
+pub(crate) fn process_response_error<T>(res: &ApiResponse) -> Result<T, JsbError> {
+    let status = res.status.as_u16();
+    let code = res.status.to_string();
+    let data: JsonObject = match serde_json::from_slice(&res.body) {
+        Ok(text) => text,
+        Err(err) => {
+            // not a json error body, keep the status so the kind is still known
+            return Err(JsbError::from_api(status, &code, &code).with_source(err));
+        }
+    };
+
+    // get error object from data
+    let error = match data.get("error").and_then(|err| err.as_object()) {
+        Some(err) => err,
+        None => {
+            return Err(JsbError::from_api(status, &code, "Unknown error"));
+        }
+    };
+
+    Err(JsbError::from_api(
+        status,
+        error.get("code").and_then(|c| c.as_str()).unwrap_or(&code),
+        error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error"),
+    ))
+}
+
+// process_response - Processes response
+pub(crate) fn process_response<T: DeserializeOwned>(res: ApiResponse) -> Result<T, JsbError> {
+    // Check if the response is successful
+    if res.status.is_success() {
+        match serde_json::from_slice(&res.body) {
+            Ok(data) => Ok(data),
+            Err(err) => Err(JsbError::from(err)),
+        }
+    } else {
+        process_response_error(&res)
+    }
+}
+
+// decode_content - Deserializes content read as text, e.g. from the cache
+pub(crate) fn decode_content<T: DeserializeOwned>(content: &str) -> Result<T, JsbError> {
+    match serde_json::from_str(content) {
+        Ok(data) => Ok(data),
+        Err(err) => Err(JsbError::from(err)),
+    }
+}
+
+// decode_content_at - Deserializes the node of the content at a JSON Pointer, e.g. `/features/checkout`
+pub(crate) fn decode_content_at<T: DeserializeOwned>(content: &str, pointer: &str) -> Result<T, JsbError> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(JsbError::new(ErrorKind::Validation, "bad_request", "JSON Pointer must be empty or start with /"));
+    }
+
+    let value: JsonValue = decode_content(content)?;
+    match value.pointer(pointer) {
+        Some(node) => T::deserialize(node).map_err(JsbError::from),
+        None => Err(err_path_not_found(pointer)),
+    }
+}
+
+// process_conditional_response - Processes the response of a conditional request
+pub(crate) fn process_conditional_response(res: ApiResponse) -> Result<Revalidated, JsbError> {
+    if res.status == StatusCode::NOT_MODIFIED {
+        return Ok(Revalidated::NotModified);
+    }
+
+    let validators = Validators::from_headers(&res.headers);
+    match process_response_as_string(res) {
+        Ok(content) => Ok(Revalidated::Modified(content, validators)),
+        Err(err) => Err(err),
+    }
+}
+
+// conditional_content - Decodes the content of a conditional request if it changed
+pub(crate) fn conditional_content<T: DeserializeOwned>(res: Revalidated) -> Result<ConditionalContent<T>, JsbError> {
+    match res {
+        Revalidated::Modified(content, validators) => Ok(ConditionalContent::Changed(decode_content(&content)?, validators.etag)),
+        Revalidated::NotModified => Ok(ConditionalContent::NotModified),
+    }
+}
+
+// process_response_as_string - Processes response as text
+pub(crate) fn process_response_as_string(res: ApiResponse) -> Result<String, JsbError> {
+    // Check if the response is successful
+    if res.status.is_success() {
+        Ok(String::from_utf8_lossy(&res.body).to_string())
+    } else {
+        process_response_error(&res)
+    }
+}
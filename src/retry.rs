@@ -0,0 +1,158 @@
+use std::time::Duration;
+use reqwest::header::RETRY_AFTER;
+use crate::error::{ErrorKind, JsbError};
+use crate::metrics::{MetricsSink, RequestTimer};
+use crate::request::{ApiRequest, ApiResponse};
+use crate::trace::RequestSpan;
+
+/// Controls how failed requests are retried.
+///
+/// By default only idempotent requests (`GET`, `DELETE` and reads like `authenticate`) are retried.
+/// Writes such as `create_document` are only retried when `retry_writes` is enabled
+/// on the [RequestOptions](struct.RequestOptions.html) of that call.
+///
+/// A request is retried when the error kind is in `retry_kinds` or the response status is in `retry_statuses`.
+/// For `429` and `503` responses a `Retry-After` header in seconds is honored, the request is not retried
+/// when it asks to wait longer than `max_delay`. The HTTP-date form is not supported, the exponential delay is used instead.
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// # use jsonbank::{JsonBank, InitConfig, RetryPolicy};
+/// let jsb = JsonBank::new(InitConfig {
+///     retry: Some(RetryPolicy {
+///         max_attempts: 5,
+///         base_delay: Duration::from_millis(100),
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after every attempt.
+    pub base_delay: Duration,
+    /// Maximum delay between two attempts.
+    pub max_delay: Duration,
+    /// If `true`, a random delay between half and the full computed delay is used.
+    pub jitter: bool,
+    /// Error kinds that are retried.
+    pub retry_kinds: Vec<ErrorKind>,
+    /// Response statuses that are retried.
+    pub retry_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+            retry_kinds: vec![ErrorKind::Transport],
+            retry_statuses: vec![429, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    // backoff - Exponential delay of an attempt, capped at max_delay
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+
+        if self.jitter {
+            let half = delay / 2;
+            half + delay.mul_f64(fastrand::f64()) / 2
+        } else {
+            delay
+        }
+    }
+
+    // retry_delay - Returns how long to wait before retrying a failed attempt,
+    // or None if the result should be returned as is.
+    pub(crate) fn retry_delay(&self, req: &ApiRequest, attempt: u32, res: &Result<ApiResponse, JsbError>) -> Option<Duration> {
+        if !req.retryable || attempt >= self.max_attempts {
+            return None;
+        }
+
+        match res {
+            Ok(res) => {
+                let status = res.status.as_u16();
+                let kind = JsbError::from_api(status, "", "").kind;
+
+                if res.status.is_success() || !(self.retry_statuses.contains(&status) || self.retry_kinds.contains(&kind)) {
+                    return None;
+                }
+
+                // honor Retry-After (in seconds) on rate limits and maintenance,
+                // give up when the server asks to wait longer than max_delay
+                if status == 429 || status == 503 {
+                    let retry_after = res.headers.get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.trim().parse::<u64>().ok());
+
+                    if let Some(seconds) = retry_after {
+                        let delay = Duration::from_secs(seconds);
+                        return if delay <= self.max_delay { Some(delay) } else { None };
+                    }
+                }
+
+                Some(self.backoff(attempt))
+            }
+            Err(err) => {
+                let status_match = err.status.map(|s| self.retry_statuses.contains(&s)).unwrap_or(false);
+
+                if status_match || self.retry_kinds.contains(&err.kind) {
+                    Some(self.backoff(attempt))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// The attempts of one request, shared by both clients so they only differ in how they send and wait.
+/// Decides if a failed attempt is retried and reports the final result to the span and the metrics sink.
+pub(crate) struct Attempts<'a> {
+    req: &'a ApiRequest,
+    retry: &'a RetryPolicy,
+    span: &'a RequestSpan,
+    metrics: Option<&'a dyn MetricsSink>,
+    timer: RequestTimer,
+    attempt: u32,
+}
+
+impl<'a> Attempts<'a> {
+    // new - starts the attempts of a request, the policy of the call replaces the policy of the instance
+    pub fn new(req: &'a ApiRequest, retry: &'a RetryPolicy, span: &'a RequestSpan, metrics: Option<&'a dyn MetricsSink>) -> Self {
+        let retry = req.retry.as_ref().unwrap_or(retry);
+        Attempts { req, retry, span, metrics, timer: RequestTimer::start(), attempt: 1 }
+    }
+
+    // next - returns how long to wait before the next attempt,
+    // or None if `res` is the result of the request, it is reported before returning
+    pub fn next(&mut self, res: &Result<ApiResponse, JsbError>) -> Option<Duration> {
+        if let Some(delay) = self.retry.retry_delay(self.req, self.attempt, res) {
+            self.attempt += 1;
+            return Some(delay);
+        }
+
+        self.span.finish(self.attempt, res);
+        if let Some(sink) = self.metrics {
+            self.timer.finish(sink, self.req, self.attempt, res);
+        }
+
+        None
+    }
+}
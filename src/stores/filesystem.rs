@@ -0,0 +1,443 @@
+use std::fs::{self, Metadata, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::{JsonBankApi, JsonValue, JSONBANK};
+use crate::error::*;
+use crate::functions::*;
+use crate::structs::*;
+
+/// A [JsonBankApi](../trait.JsonBankApi.html) backend that stores documents as files.
+///
+/// Every project is a directory under `root`, folders are sub directories
+/// and documents are files, so `project/folder/name.json` is stored at `root/project/folder/name.json`.
+/// The id of a document or folder is its `project/path`, so ids and paths can be used interchangeably.
+/// Listings only contain `.json` files and directories, other files are left alone.
+///
+/// `updated_at` is the modification time of the file in nanoseconds. An update moves it forward
+/// even when the clock of the file system did not, so `update_own_document_after_check` sees every write.
+/// # Example
+/// ```no_run
+/// use jsonbank::JsonBankApi;
+/// use jsonbank::stores::FileSystemStore;
+/// use jsonbank::structs::CreateDocumentBody;
+///
+/// let store = FileSystemStore::new("./data");
+/// store.add_project("sdk-test").unwrap();
+///
+/// store.create_document(CreateDocumentBody {
+///     name: "index.json".to_string(),
+///     project: "sdk-test".to_string(),
+///     content: r#"{"author": "jsonbank"}"#.to_string(),
+///     folder: None,
+/// }).unwrap();
+///
+/// // written to ./data/sdk-test/index.json
+/// let data = store.get_own_content_value("sdk-test/index").unwrap();
+/// assert_eq!(data["author"], "jsonbank");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileSystemStore {
+    root: PathBuf,
+    username: String,
+}
+
+impl FileSystemStore {
+    /// Create a store in the `root` directory, documents belong to the user `jsonbank`.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self::with_username(root, JSONBANK)
+    }
+
+    /// Create a store in the `root` directory for a user.
+    pub fn with_username<P: Into<PathBuf>>(root: P, username: &str) -> Self {
+        FileSystemStore { root: root.into(), username: username.to_string() }
+    }
+
+    /// The root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Add a project, creating its directory (and the root directory) if missing.
+    pub fn add_project(&self, name: &str) -> Result<(), JsbError> {
+        check_segment(name)?;
+        fs::create_dir_all(self.root.join(name)).map_err(JsbError::from)
+    }
+
+    // project_dir - returns the directory of an existing project
+    fn project_dir(&self, project: &str) -> Result<PathBuf, JsbError> {
+        check_segment(project)?;
+        let dir = self.root.join(project);
+
+        if dir.is_dir() {
+            Ok(dir)
+        } else {
+            Err(err_not_found("Project"))
+        }
+    }
+
+    // resolve - splits `project/path` and returns the project, the path and the location on disk
+    fn resolve(&self, id_or_path: &str) -> Result<(String, String, PathBuf), JsbError> {
+        let (project, path) = match id_or_path.split_once('/') {
+            Some((project, path)) if !path.is_empty() => (project, path.trim_end_matches('/')),
+            _ => return Err(err_not_found("Document or folder")),
+        };
+
+        let mut location = self.project_dir(project)?;
+        for segment in path.split('/') {
+            check_segment(segment)?;
+            location.push(segment);
+        }
+
+        Ok((project.to_string(), path.to_string(), location))
+    }
+
+    // document_file - finds the file of a document, the `.json` extension is optional
+    fn document_file(&self, id_or_path: &str) -> Result<(String, String, PathBuf), JsbError> {
+        let (project, path, file) = self.resolve(id_or_path)?;
+
+        if file.is_file() {
+            return Ok((project, path, file));
+        }
+
+        if !path.ends_with(".json") {
+            let file = file.with_file_name(format!("{}.json", file_name(&file)));
+            if file.is_file() {
+                return Ok((project, format!("{}.json", path), file));
+            }
+        }
+
+        Err(err_not_found("Document"))
+    }
+
+    // folder_dir - finds the directory of a folder
+    fn folder_dir(&self, id_or_path: &str) -> Result<(String, String, PathBuf), JsbError> {
+        match self.resolve(id_or_path) {
+            Ok((project, path, dir)) if dir.is_dir() => Ok((project, path, dir)),
+            Ok(_) => Err(err_not_found("Folder")),
+            Err(err) => Err(err),
+        }
+    }
+
+    // parent_dir - finds the directory a new document or folder is created in.
+    // `folder` is a path inside the project or a folder id (`project/path`)
+    fn parent_dir(&self, project: &str, folder: Option<&str>) -> Result<(String, PathBuf), JsbError> {
+        let project_dir = self.project_dir(project)?;
+
+        let folder = match folder {
+            Some(folder) => folder.trim_matches('/'),
+            None => return Ok((String::new(), project_dir)),
+        };
+
+        // a path inside the project
+        if let Ok((_, path, dir)) = self.folder_dir(&format!("{}/{}", project, folder)) {
+            return Ok((format!("{}/", path), dir));
+        }
+
+        // a folder id of the same project
+        match folder.strip_prefix(&format!("{}/", project)) {
+            Some(path) => match self.folder_dir(&format!("{}/{}", project, path)) {
+                Ok((_, path, dir)) => Ok((format!("{}/", path), dir)),
+                Err(_) => Err(err_not_found("Folder")),
+            },
+            None => Err(err_not_found("Folder")),
+        }
+    }
+
+    // document_meta - builds the meta of a document file
+    fn document_meta(&self, project: &str, path: &str, file: &Path) -> Result<DocumentMeta, JsbError> {
+        let metadata = fs::metadata(file)?;
+        let size = metadata.len();
+
+        let folder_id = path.rsplit_once('/').map(|(folder, _)| format!("{}/{}", project, folder));
+        let (created_at, updated_at) = timestamps(&metadata);
+
+        Ok(DocumentMeta {
+            id: format!("{}/{}", project, path),
+            project: project.to_string(),
+            path: path.to_string(),
+            name: file_name(file),
+            folder_id,
+            content_size: ContentSize { number: size, string: format!("{} B", size) },
+            updated_at,
+            created_at,
+        })
+    }
+
+    // folder - builds a folder from its directory, counting its children if include_stats is true
+    fn folder(&self, project: &str, path: &str, dir: &Path, include_stats: bool) -> Result<Folder, JsbError> {
+        let metadata = fs::metadata(dir)?;
+        let (created_at, updated_at) = timestamps(&metadata);
+
+        let stats = if include_stats {
+            let mut stats = FolderStats { documents: 0, folders: 0 };
+
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                // skip hidden files like `.DS_Store`
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+
+                if entry.file_type()?.is_dir() {
+                    stats.folders += 1;
+                } else if is_json(&entry.path()) {
+                    stats.documents += 1;
+                }
+            }
+
+            Some(stats)
+        } else {
+            None
+        };
+
+        Ok(Folder {
+            id: format!("{}/{}", project, path),
+            name: file_name(dir),
+            path: path.to_string(),
+            project: project.to_string(),
+            created_at,
+            updated_at,
+            stats,
+        })
+    }
+
+    // contents - lists the documents and folders of a directory, `prefix` is its path inside the project
+    fn contents(&self, project: &str, prefix: &str, dir: &Path, options: &ListOptions) -> Result<ContentsPage, JsbError> {
+        let mut items = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            // skip hidden files like `.DS_Store`
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let path = format!("{}{}", prefix, name);
+            if entry.file_type()?.is_dir() {
+                items.push(ContentItem::Folder(self.folder(project, &path, &entry.path(), false)?));
+            } else if is_json(&entry.path()) {
+                items.push(ContentItem::Document(self.document_meta(project, &path, &entry.path())?));
+            }
+        }
+
+        paginate(items, options)
+    }
+}
+
+// JsonBankApi Implementation
+impl JsonBankApi for FileSystemStore {
+    fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError> {
+        let mut projects = Vec::new();
+
+        // every directory in the root is a project
+        if self.root.is_dir() {
+            for entry in fs::read_dir(&self.root)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    projects.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+
+        projects.sort();
+
+        Ok(AuthenticatedData {
+            authenticated: true,
+            username: self.username.clone(),
+            api_key: AuthenticatedKey { title: "filesystem".to_string(), projects },
+        })
+    }
+
+    fn get_username(&self) -> Result<String, JsbError> {
+        Ok(self.username.clone())
+    }
+
+    fn is_authenticated(&self) -> bool {
+        true
+    }
+
+    fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
+        let (project, path, file) = self.document_file(id_or_path)?;
+        self.document_meta(&project, &path, &file)
+    }
+
+    fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
+        let (_, _, file) = self.document_file(id_or_path)?;
+        fs::read_to_string(file).map_err(JsbError::from)
+    }
+
+    fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        validate_create_document(&content)?;
+        check_segment(&content.name)?;
+
+        let (folder_path, dir) = self.parent_dir(&content.project, content.folder.as_deref())?;
+        let file = dir.join(&content.name);
+
+        // a folder with the same name also counts as existing
+        if file.exists() {
+            return Err(err_name_exists());
+        }
+
+        // `create_new` fails if the file was created in the meantime
+        let written = OpenOptions::new().write(true).create_new(true).open(&file)
+            .and_then(|mut f| f.write_all(content.content.as_bytes()));
+
+        if let Err(err) = written {
+            return if err.kind() == std::io::ErrorKind::AlreadyExists {
+                Err(err_name_exists())
+            } else {
+                Err(JsbError::from(err))
+            };
+        }
+
+        let path = format!("{}{}", folder_path, content.name);
+        let meta = self.document_meta(&content.project, &path, &file)?;
+
+        Ok(NewDocument {
+            id: meta.id,
+            name: meta.name,
+            path: meta.path,
+            project: meta.project,
+            created_at: meta.created_at,
+            exists: false,
+        })
+    }
+
+    fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError> {
+        // check if content is a valid json
+        let new_value = match serde_json::from_str::<JsonValue>(&content) {
+            Ok(value) => value,
+            Err(_) => return Err(err_invalid_json()),
+        };
+
+        let (_, _, file) = self.document_file(id_or_path)?;
+        let current = fs::read_to_string(&file)?;
+
+        // documents are compared as json, so formatting changes are not updates
+        let changed = serde_json::from_str::<JsonValue>(&current).ok() != Some(new_value);
+
+        if changed {
+            let previous = fs::metadata(&file)?.modified();
+            fs::write(&file, content)?;
+
+            if let Ok(previous) = previous {
+                modified_after(&file, previous)?;
+            }
+        }
+
+        Ok(UpdatedDocument { changed })
+    }
+
+    fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError> {
+        let file = match self.document_file(id_or_path) {
+            Ok((_, _, file)) => file,
+            Err(err) => {
+                return if err.kind == ErrorKind::NotFound {
+                    Ok(DeletedDocument { deleted: false })
+                } else {
+                    Err(err)
+                };
+            }
+        };
+
+        fs::remove_file(file)?;
+        Ok(DeletedDocument { deleted: true })
+    }
+
+    fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError> {
+        validate_create_folder(&data)?;
+        check_segment(&data.name)?;
+
+        let (folder_path, parent) = self.parent_dir(&data.project, data.folder.as_deref())?;
+        let dir = parent.join(&data.name);
+
+        if let Err(err) = fs::create_dir(&dir) {
+            return if err.kind() == std::io::ErrorKind::AlreadyExists {
+                Err(err_name_exists())
+            } else {
+                Err(JsbError::from(err))
+            };
+        }
+
+        let path = format!("{}{}", folder_path, data.name);
+        self.folder(&data.project, &path, &dir, false)
+    }
+
+    fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        let (project, path, dir) = self.folder_dir(id_or_path)?;
+        self.folder(&project, &path, &dir, false)
+    }
+
+    fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        let (project, path, dir) = self.folder_dir(id_or_path)?;
+        self.folder(&project, &path, &dir, true)
+    }
+
+    fn list_project_contents(&self, project: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        let dir = self.project_dir(project)?;
+        self.contents(project, "", &dir, &options)
+    }
+
+    fn list_folder_contents(&self, id_or_path: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        let (project, path, dir) = self.folder_dir(id_or_path)?;
+        self.contents(&project, &format!("{}/", path), &dir, &options)
+    }
+}
+
+// check_segment - rejects path segments that would point outside of the store
+fn check_segment(segment: &str) -> Result<(), JsbError> {
+    if segment.is_empty() || segment == "." || segment == ".." || segment.contains('/') || segment.contains('\\') {
+        return Err(JsbError::new(ErrorKind::Validation, "bad_request", &format!("Invalid name: {:?}", segment)));
+    }
+
+    Ok(())
+}
+
+// file_name - the last part of a path as a string
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// is_json - documents are the `.json` files of a directory
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "json")
+}
+
+// modified_after - moves the modification time of a written file past `previous`,
+// the clock of the file system can be too coarse to tell two writes apart
+fn modified_after(file: &Path, previous: SystemTime) -> Result<(), JsbError> {
+    let handle = OpenOptions::new().write(true).open(file)?;
+
+    if handle.metadata()?.modified()? <= previous {
+        handle.set_modified(previous + Duration::from_nanos(1))?;
+    }
+
+    Ok(())
+}
+
+// timestamps - created and updated time of a file, the creation time is not available on every platform
+fn timestamps(metadata: &Metadata) -> (String, String) {
+    let updated_at = match metadata.modified() {
+        Ok(time) => format_timestamp_nanos(time),
+        Err(_) => now(),
+    };
+
+    let created_at = match metadata.created() {
+        Ok(time) => format_timestamp_nanos(time),
+        Err(_) => updated_at.clone(),
+    };
+
+    (created_at, updated_at)
+}
+
+// format_timestamp_nanos - like `format_timestamp` with nanoseconds, a millisecond is long enough for several writes
+fn format_timestamp_nanos(time: SystemTime) -> String {
+    let millis = format_timestamp(time);
+    let nanos = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+
+    // replace the `.mmmZ` suffix
+    format!("{}.{:09}Z", &millis[..millis.len() - 5], nanos)
+}
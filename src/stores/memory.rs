@@ -0,0 +1,525 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::{JsonBankApi, JsonValue, JSONBANK};
+use crate::error::*;
+use crate::functions::*;
+use crate::structs::*;
+
+// A stored document
+#[derive(Clone)]
+struct StoredDocument {
+    id: String,
+    project: String,
+    name: String,
+    folder_id: Option<String>,
+    // path inside the project, e.g. `folder/name.json`
+    path: String,
+    content: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl StoredDocument {
+    fn meta(&self) -> DocumentMeta {
+        let size = self.content.len() as u64;
+        DocumentMeta {
+            id: self.id.clone(),
+            project: self.project.clone(),
+            path: self.path.clone(),
+            name: self.name.clone(),
+            folder_id: self.folder_id.clone(),
+            content_size: ContentSize { number: size, string: format!("{} B", size) },
+            updated_at: self.updated_at.clone(),
+            created_at: self.created_at.clone(),
+        }
+    }
+}
+
+// A stored folder
+#[derive(Clone)]
+struct StoredFolder {
+    id: String,
+    project: String,
+    name: String,
+    parent_id: Option<String>,
+    // path inside the project, e.g. `parent/name`
+    path: String,
+    created_at: String,
+    updated_at: String,
+}
+
+// In-memory state of the store
+#[derive(Default)]
+struct State {
+    username: String,
+    // project name => is public
+    projects: HashMap<String, bool>,
+    documents: Vec<StoredDocument>,
+    folders: Vec<StoredFolder>,
+    next_id: u64,
+    // milliseconds since the epoch of the last timestamp given to a document or folder
+    clock: u64,
+}
+
+impl State {
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("{:024x}", self.next_id)
+    }
+
+    // timestamp - the current time, or a millisecond after the last timestamp when the clock did not move,
+    // so every change of a document has its own `updated_at` without waiting for the clock
+    fn timestamp(&mut self) -> String {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        self.clock = millis.max(self.clock + 1);
+        format_timestamp(UNIX_EPOCH + Duration::from_millis(self.clock))
+    }
+
+    // split_path - splits `project/path/in/project` into project and path
+    fn split_path(full_path: &str) -> (String, String) {
+        match full_path.split_once('/') {
+            Some((project, path)) => (project.to_string(), path.to_string()),
+            None => (full_path.to_string(), String::new()),
+        }
+    }
+
+    // find_document - finds a document by id or by `project/path`
+    fn find_document(&self, id_or_path: &str) -> Option<usize> {
+        if !id_or_path.contains('/') {
+            return self.documents.iter().position(|d| d.id == id_or_path);
+        }
+
+        let (project, path) = Self::split_path(id_or_path);
+        let found = self.documents.iter().position(|d| d.project == project && d.path == path);
+
+        // the `.json` extension is optional
+        if found.is_none() && !path.ends_with(".json") {
+            let path = format!("{}.json", path);
+            return self.documents.iter().position(|d| d.project == project && d.path == path);
+        }
+
+        found
+    }
+
+    // find_folder - finds a folder by id or by `project/path`
+    fn find_folder(&self, id_or_path: &str) -> Option<&StoredFolder> {
+        if !id_or_path.contains('/') {
+            return self.folders.iter().find(|f| f.id == id_or_path);
+        }
+
+        let (project, path) = Self::split_path(id_or_path);
+        self.folders.iter().find(|f| f.project == project && f.path == path)
+    }
+
+    // find_folder_in_project - finds a folder of a project by id or path inside the project
+    fn find_folder_in_project(&self, project: &str, id_or_path: &str) -> Option<&StoredFolder> {
+        self.folders.iter().find(|f| f.project == project && (f.id == id_or_path || f.path == id_or_path))
+    }
+
+    // name_exists - checks if a document or folder with the name exists in a folder
+    fn name_exists(&self, project: &str, folder_id: &Option<String>, name: &str) -> bool {
+        self.documents.iter().any(|d| d.project == project && &d.folder_id == folder_id && d.name == name)
+            || self.folders.iter().any(|f| f.project == project && &f.parent_id == folder_id && f.name == name)
+    }
+
+    // folder - converts a stored folder to a Folder, counting its children if include_stats is true
+    fn folder(&self, folder: &StoredFolder, include_stats: bool) -> Folder {
+        let stats = if include_stats {
+            let parent = Some(folder.id.clone());
+            Some(FolderStats {
+                documents: self.documents.iter().filter(|d| d.folder_id == parent).count() as i32,
+                folders: self.folders.iter().filter(|f| f.parent_id == parent).count() as i32,
+            })
+        } else {
+            None
+        };
+
+        Folder {
+            id: folder.id.clone(),
+            name: folder.name.clone(),
+            path: folder.path.clone(),
+            project: folder.project.clone(),
+            created_at: folder.created_at.clone(),
+            updated_at: folder.updated_at.clone(),
+            stats,
+        }
+    }
+
+    fn insert_folder(&mut self, project: &str, name: &str, parent: Option<&str>) -> Result<StoredFolder, JsbError> {
+        if !self.projects.contains_key(project) {
+            return Err(err_not_found("Project"));
+        }
+
+        let (parent_id, path) = match parent {
+            Some(parent) => match self.find_folder_in_project(project, parent) {
+                Some(folder) => (Some(folder.id.clone()), format!("{}/{}", folder.path, name)),
+                None => return Err(err_not_found("Folder")),
+            },
+            None => (None, name.to_string()),
+        };
+
+        if self.name_exists(project, &parent_id, name) {
+            return Err(err_name_exists());
+        }
+
+        let now = self.timestamp();
+        let folder = StoredFolder {
+            id: self.new_id(),
+            project: project.to_string(),
+            name: name.to_string(),
+            parent_id,
+            path,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        self.folders.push(folder.clone());
+        Ok(folder)
+    }
+
+    // update_document - replaces the content of a document, if it is still at the `expected` version
+    fn update_document(&mut self, id_or_path: &str, content: String, new_value: JsonValue, expected: Option<&ExpectedVersion>) -> Result<UpdatedDocument, JsbError> {
+        let index = match self.find_document(id_or_path) {
+            Some(index) => index,
+            None => return Err(err_not_found("Document")),
+        };
+
+        let doc = &self.documents[index];
+        let unchanged = match expected {
+            Some(ExpectedVersion::UpdatedAt(updated_at)) => doc.updated_at == *updated_at,
+            Some(ExpectedVersion::ContentHash(hash)) => content_hash(&doc.content) == *hash,
+            None => true,
+        };
+
+        if !unchanged {
+            return Err(err_conflict(id_or_path));
+        }
+
+        // documents are compared as json, so formatting changes are not updates
+        let changed = serde_json::from_str::<JsonValue>(&doc.content).ok() != Some(new_value);
+
+        if changed {
+            let updated_at = self.timestamp();
+            let doc = &mut self.documents[index];
+            doc.content = content;
+            doc.updated_at = updated_at;
+        }
+
+        Ok(UpdatedDocument { changed })
+    }
+
+    fn insert_document(&mut self, project: &str, name: &str, folder: Option<&str>, content: &str) -> Result<StoredDocument, JsbError> {
+        if !self.projects.contains_key(project) {
+            return Err(err_not_found("Project"));
+        }
+
+        if !is_valid_json(content) {
+            return Err(err_invalid_json());
+        }
+
+        let (folder_id, path) = match folder {
+            Some(folder) => match self.find_folder_in_project(project, folder) {
+                Some(folder) => (Some(folder.id.clone()), format!("{}/{}", folder.path, name)),
+                None => return Err(err_not_found("Folder")),
+            },
+            None => (None, name.to_string()),
+        };
+
+        if self.name_exists(project, &folder_id, name) {
+            return Err(err_name_exists());
+        }
+
+        let now = self.timestamp();
+        let document = StoredDocument {
+            id: self.new_id(),
+            project: project.to_string(),
+            name: name.to_string(),
+            folder_id,
+            path,
+            content: content.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        self.documents.push(document.clone());
+        Ok(document)
+    }
+}
+
+/// A [JsonBankApi](../trait.JsonBankApi.html) backend that keeps everything in memory.
+///
+/// Projects are not created implicitly, add them with [add_project](#method.add_project) first.
+/// Ids are generated like the api does, and documents can be found by id or by `project/path`.
+/// # Example
+/// ```
+/// use jsonbank::JsonBankApi;
+/// use jsonbank::stores::MemoryStore;
+///
+/// let store = MemoryStore::new();
+/// store.add_project("sdk-test", false);
+/// store.add_document("sdk-test", "folder/index.json", r#"{"author": "jsonbank"}"#).unwrap();
+///
+/// let meta = store.get_own_document_meta("sdk-test/folder/index").unwrap();
+/// assert_eq!(meta.path, "folder/index.json");
+/// ```
+pub struct MemoryStore {
+    state: Mutex<State>,
+}
+
+impl Debug for MemoryStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryStore").field("username", &self.lock().username).finish()
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    /// Create an empty store, documents belong to the user `jsonbank`.
+    pub fn new() -> Self {
+        Self::with_username(JSONBANK)
+    }
+
+    /// Create an empty store for a user.
+    pub fn with_username(username: &str) -> Self {
+        MemoryStore {
+            state: Mutex::new(State {
+                username: username.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    // lock - locks the state, a panic in another thread does not make the data invalid
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Add a project. Public projects are readable by the public routes of the mock server.
+    pub fn add_project(&self, name: &str, public: bool) {
+        self.lock().projects.insert(name.to_string(), public);
+    }
+
+    /// Check if a project exists.
+    pub fn has_project(&self, name: &str) -> bool {
+        self.lock().projects.contains_key(name)
+    }
+
+    /// Add a folder, `path` is the path inside the project e.g. `parent/name`.
+    /// Missing parent folders are created. Returns the id of the last folder.
+    pub fn add_folder(&self, project: &str, path: &str) -> Result<String, JsbError> {
+        let mut state = self.lock();
+
+        let mut parent: Option<String> = None;
+        let mut id = String::new();
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            let folder_path = match &parent {
+                Some(parent) => format!("{}/{}", parent, name),
+                None => name.to_string(),
+            };
+
+            id = match state.find_folder_in_project(project, &folder_path) {
+                Some(folder) => folder.id.clone(),
+                None => state.insert_folder(project, name, parent.as_deref())?.id,
+            };
+
+            parent = Some(folder_path);
+        }
+
+        Ok(id)
+    }
+
+    /// Add a document, `path` is the path inside the project e.g. `folder/name.json`.
+    /// Missing folders are created. Returns the id of the document.
+    pub fn add_document(&self, project: &str, path: &str, content: &str) -> Result<String, JsbError> {
+        let (folder, name) = match path.rsplit_once('/') {
+            Some((folder, name)) => (Some(folder), name),
+            None => (None, path),
+        };
+
+        if let Some(folder) = folder {
+            self.add_folder(project, folder)?;
+        }
+
+        let doc = self.lock().insert_document(project, name, folder, content)?;
+        Ok(doc.id)
+    }
+
+    /// Get the content of a document by id or `project/path`.
+    pub fn document_content(&self, id_or_path: &str) -> Option<String> {
+        let state = self.lock();
+        state.find_document(id_or_path).map(|index| state.documents[index].content.clone())
+    }
+
+    // authenticated_data - the data returned by `authenticate`, the key has access to every project
+    pub(crate) fn authenticated_data(&self, key_title: &str) -> AuthenticatedData {
+        let state = self.lock();
+        let mut projects: Vec<String> = state.projects.keys().cloned().collect();
+        projects.sort();
+
+        AuthenticatedData {
+            authenticated: true,
+            username: state.username.clone(),
+            api_key: AuthenticatedKey { title: key_title.to_string(), projects },
+        }
+    }
+
+    // get_public_document - finds a document of a public project by id or `username/project/path`
+    #[cfg(feature = "test-util")]
+    pub(crate) fn get_public_document(&self, id_or_path: &str) -> Result<(DocumentMeta, String), JsbError> {
+        let state = self.lock();
+        let index = if id_or_path.contains('/') {
+            match id_or_path.split_once('/') {
+                Some((username, path)) if username == state.username => state.find_document(path),
+                _ => None,
+            }
+        } else {
+            state.find_document(id_or_path)
+        };
+
+        match index {
+            Some(index) if state.projects.get(&state.documents[index].project) == Some(&true) => {
+                let doc = &state.documents[index];
+                Ok((doc.meta(), doc.content.clone()))
+            }
+            _ => Err(err_not_found("Document")),
+        }
+    }
+
+    // contents - lists the documents and folders of a project with the parent folder id
+    fn contents(&self, project: &str, parent: Option<String>, options: &ListOptions) -> Result<ContentsPage, JsbError> {
+        let state = self.lock();
+
+        let documents = state.documents.iter()
+            .filter(|d| d.project == project && d.folder_id == parent)
+            .map(|d| ContentItem::Document(d.meta()));
+
+        let folders = state.folders.iter()
+            .filter(|f| f.project == project && f.parent_id == parent)
+            .map(|f| ContentItem::Folder(state.folder(f, false)));
+
+        paginate(documents.chain(folders).collect(), options)
+    }
+
+    // folder - gets a folder by id or `project/path`
+    fn folder(&self, id_or_path: &str, include_stats: bool) -> Result<Folder, JsbError> {
+        let state = self.lock();
+        match state.find_folder(id_or_path) {
+            Some(folder) => Ok(state.folder(folder, include_stats)),
+            None => Err(err_not_found("Folder")),
+        }
+    }
+}
+
+// JsonBankApi Implementation
+impl JsonBankApi for MemoryStore {
+    fn authenticate(&mut self) -> Result<AuthenticatedData, JsbError> {
+        Ok(self.authenticated_data("memory"))
+    }
+
+    fn get_username(&self) -> Result<String, JsbError> {
+        Ok(self.lock().username.clone())
+    }
+
+    fn is_authenticated(&self) -> bool {
+        true
+    }
+
+    fn get_own_document_meta(&self, id_or_path: &str) -> Result<DocumentMeta, JsbError> {
+        let state = self.lock();
+        match state.find_document(id_or_path) {
+            Some(index) => Ok(state.documents[index].meta()),
+            None => Err(err_not_found("Document")),
+        }
+    }
+
+    fn get_own_content_as_string(&self, id_or_path: &str) -> Result<String, JsbError> {
+        match self.document_content(id_or_path) {
+            Some(content) => Ok(content),
+            None => Err(err_not_found("Document")),
+        }
+    }
+
+    fn create_document(&self, content: CreateDocumentBody) -> Result<NewDocument, JsbError> {
+        validate_create_document(&content)?;
+
+        let doc = self.lock().insert_document(&content.project, &content.name, content.folder.as_deref(), &content.content)?;
+        Ok(NewDocument {
+            id: doc.id,
+            name: doc.name,
+            path: doc.path,
+            project: doc.project,
+            created_at: doc.created_at,
+            exists: false,
+        })
+    }
+
+    fn update_own_document(&self, id_or_path: &str, content: String) -> Result<UpdatedDocument, JsbError> {
+        // check if content is a valid json
+        let new_value = match serde_json::from_str::<JsonValue>(&content) {
+            Ok(value) => value,
+            Err(_) => return Err(err_invalid_json()),
+        };
+
+        self.lock().update_document(id_or_path, content, new_value, None)
+    }
+
+    fn update_own_document_after_check(&self, id_or_path: &str, content: String, expected: &ExpectedVersion) -> Result<UpdatedDocument, JsbError> {
+        let new_value = match serde_json::from_str::<JsonValue>(&content) {
+            Ok(value) => value,
+            Err(_) => return Err(err_invalid_json()),
+        };
+
+        // the version is checked under the same lock as the update
+        self.lock().update_document(id_or_path, content, new_value, Some(expected))
+    }
+
+    fn delete_document(&self, id_or_path: &str) -> Result<DeletedDocument, JsbError> {
+        let mut state = self.lock();
+        match state.find_document(id_or_path) {
+            Some(index) => {
+                state.documents.remove(index);
+                Ok(DeletedDocument { deleted: true })
+            }
+            None => Ok(DeletedDocument { deleted: false }),
+        }
+    }
+
+    fn create_folder(&self, data: CreateFolderBody) -> Result<Folder, JsbError> {
+        validate_create_folder(&data)?;
+
+        let mut state = self.lock();
+        let folder = state.insert_folder(&data.project, &data.name, data.folder.as_deref())?;
+        Ok(state.folder(&folder, false))
+    }
+
+    fn get_folder(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        self.folder(id_or_path, false)
+    }
+
+    fn get_folder_with_stats(&self, id_or_path: &str) -> Result<Folder, JsbError> {
+        self.folder(id_or_path, true)
+    }
+
+    fn list_project_contents(&self, project: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        if !self.has_project(project) {
+            return Err(err_not_found("Project"));
+        }
+
+        self.contents(project, None, &options)
+    }
+
+    fn list_folder_contents(&self, id_or_path: &str, options: ListOptions) -> Result<ContentsPage, JsbError> {
+        let folder = self.folder(id_or_path, false)?;
+        self.contents(&folder.project, Some(folder.id), &options)
+    }
+}
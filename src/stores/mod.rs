@@ -0,0 +1,10 @@
+//! - [MemoryStore](struct.MemoryStore.html) keeps projects, folders and documents in memory,
+//!   useful as a fake in unit tests.
+//! - [FileSystemStore](struct.FileSystemStore.html) maps `project/folder/name` paths to
+//!   directories and files under a root directory.
+
+mod memory;
+mod filesystem;
+
+pub use memory::MemoryStore;
+pub use filesystem::FileSystemStore;
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// About the current authenticated api key.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AuthenticatedKey {
     /// The title of the api key.
     pub title: String,
@@ -8,7 +10,8 @@ pub struct AuthenticatedKey {
 }
 
 /// Holds the authentication data.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AuthenticatedData {
     /// If the user is authenticated.
     pub authenticated: bool,
@@ -33,14 +36,15 @@ impl Clone for AuthenticatedData {
 
 
 /// String and number information about the content size.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ContentSize {
     pub number: u64,
     pub string: String,
 }
 
 /// Metadata about a document.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DocumentMeta {
     /// The id of the document.
     pub id: String,
@@ -51,6 +55,7 @@ pub struct DocumentMeta {
     /// Name of the document.
     pub name: String,
     /// Optional folder the document belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub folder_id: Option<String>,
     /// The size of the document.
     pub content_size: ContentSize,
@@ -99,7 +104,8 @@ impl Clone for CreateFolderBody {
 /// The input body for uploading a document.
 #[derive(Debug)]
 pub struct UploadDocumentBody {
-    /// path of the file to upload
+    /// path of the file to upload, relative paths start at the current working directory.
+    /// `upload_from_reader` and `upload_bytes` do not read it, it only names the document when `name` is not set.
     pub file_path: String,
     /// The project the document belongs to.
     pub project: String,
@@ -122,7 +128,8 @@ impl Clone for CreateDocumentBody {
 }
 
 /// The input body for updating a document.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NewDocument {
     /// The id of the document.
     pub id: String,
@@ -138,18 +145,20 @@ pub struct NewDocument {
     /// If document was created or already exists
     /// this field is not returned by the api
     /// it is used by the `create_document_if_not_exists` function
+    #[serde(default)]
     pub exists: bool,
 }
 
 /// Contains the number of documents and folders in a folder
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct FolderStats {
     pub documents: i32,
     pub folders: i32,
 }
 
 /// About a folder.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Folder {
     /// The id of the folder.
     pub id: String,
@@ -165,20 +174,280 @@ pub struct Folder {
     pub updated_at: String,
     /// stats are only returned when the `include_stats` query parameter is set to true
     /// which is set to true in the `get_folder_stats` function
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stats: Option<FolderStats>,
 }
 
+/// Returned by `ensure_folder_path`.
+#[derive(Debug, Serialize)]
+pub struct EnsuredFolder {
+    /// The last folder of the path.
+    pub folder: Folder,
+    /// The paths inside the project of the folders that were created, parents first.
+    /// Empty when the whole path already existed.
+    pub created: Vec<String>,
+}
+
 /// Response from the api when a document is deleted.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeletedDocument {
     /// If `true`, the document was deleted else it was not deleted
+    #[serde(default)]
     pub deleted: bool,
 }
 
 /// Response from the api when a document is updated.
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UpdatedDocument {
     /// If `true`, the document was updated else it was not updated
     /// if a document is not updated, it means the content is the same.
+    #[serde(default)]
     pub changed: bool,
-}
\ No newline at end of file
+}
+
+/// An entry of a project or folder listing, see `list_project_contents`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ContentItem {
+    /// A document, its content is not included.
+    Document(DocumentMeta),
+    /// A folder, its stats are not included.
+    Folder(Folder),
+}
+
+impl ContentItem {
+    /// The id of the document or folder.
+    pub fn id(&self) -> &str {
+        match self {
+            ContentItem::Document(doc) => &doc.id,
+            ContentItem::Folder(folder) => &folder.id,
+        }
+    }
+
+    /// The name of the document or folder.
+    pub fn name(&self) -> &str {
+        match self {
+            ContentItem::Document(doc) => &doc.name,
+            ContentItem::Folder(folder) => &folder.name,
+        }
+    }
+
+    /// The path of the document or folder inside its project.
+    pub fn path(&self) -> &str {
+        match self {
+            ContentItem::Document(doc) => &doc.path,
+            ContentItem::Folder(folder) => &folder.path,
+        }
+    }
+
+    /// The last time the document or folder was updated.
+    pub fn updated_at(&self) -> &str {
+        match self {
+            ContentItem::Document(doc) => &doc.updated_at,
+            ContentItem::Folder(folder) => &folder.updated_at,
+        }
+    }
+}
+
+/// A page of a project or folder listing.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentsPage {
+    /// The documents and folders of this page.
+    pub items: Vec<ContentItem>,
+    /// The page number, starting at 1.
+    pub page: u32,
+    /// The maximum number of items per page.
+    pub per_page: u32,
+    /// The number of items in all pages.
+    pub total: u64,
+}
+
+impl ContentsPage {
+    /// Returns true if there are more pages after this one.
+    pub fn has_more(&self) -> bool {
+        !self.items.is_empty() && (self.page as u64) * (self.per_page as u64) < self.total
+    }
+}
+
+/// The field a listing is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort by name.
+    #[default]
+    Name,
+    /// Sort by the last update time.
+    UpdatedAt,
+}
+
+/// The order of a sorted listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest first, e.g. `a` to `z` or oldest first.
+    #[default]
+    Asc,
+    /// Largest first, e.g. `z` to `a` or newest first.
+    Desc,
+}
+
+/// Pagination and sorting of a project or folder listing.
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    /// The page to get, starting at 1.
+    pub page: u32,
+    /// The maximum number of items per page, between 1 and 100.
+    pub per_page: u32,
+    /// The field the items are sorted by.
+    pub sort: SortBy,
+    /// The order of the items.
+    pub order: SortOrder,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        ListOptions {
+            page: 1,
+            per_page: 50,
+            sort: SortBy::Name,
+            order: SortOrder::Asc,
+        }
+    }
+}
+
+/// Options of `sync_directory`.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Delete the documents of the project that do not exist in the local directory,
+    /// folders that do not exist locally are reported in `orphaned_folders`.
+    /// Needs a listing of the project, so it is only supported by the local stores,
+    /// `JsonBank` fails with `ErrorKind::Unsupported` before anything is changed.
+    pub delete: bool,
+    /// Only plan the changes, nothing is created, updated or deleted.
+    /// Print the returned [SyncReport](struct.SyncReport.html) to show the plan.
+    pub dry_run: bool,
+}
+
+/// What `sync_directory` changed, or would change in a dry run.
+/// Every entry is a path inside the project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// If true, nothing was changed.
+    pub dry_run: bool,
+    /// Folders that were created.
+    pub created_folders: Vec<String>,
+    /// Documents that were created.
+    pub created: Vec<String>,
+    /// Documents whose content was replaced by the local file.
+    pub updated: Vec<String>,
+    /// Documents with the same content as the local file.
+    pub unchanged: Vec<String>,
+    /// Documents that were deleted because they do not exist locally.
+    pub deleted: Vec<String>,
+    /// Folders of the project that do not exist locally, found with `delete`.
+    /// Folders cannot be deleted, so they are kept and only reported.
+    pub orphaned_folders: Vec<String>,
+}
+
+impl SyncReport {
+    /// Returns true if nothing was or would be changed.
+    pub fn is_empty(&self) -> bool {
+        self.created_folders.is_empty() && self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl std::fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.dry_run {
+            writeln!(f, "dry run, nothing was changed")?;
+        }
+
+        for path in &self.created_folders {
+            writeln!(f, "create folder {}", path)?;
+        }
+        for path in &self.created {
+            writeln!(f, "create {}", path)?;
+        }
+        for path in &self.updated {
+            writeln!(f, "update {}", path)?;
+        }
+        for path in &self.deleted {
+            writeln!(f, "delete {}", path)?;
+        }
+        for path in &self.orphaned_folders {
+            writeln!(f, "keep folder {}, it does not exist locally", path)?;
+        }
+
+        write!(
+            f,
+            "{} created, {} updated, {} unchanged, {} deleted",
+            self.created_folders.len() + self.created.len(),
+            self.updated.len(),
+            self.unchanged.len(),
+            self.deleted.len(),
+        )
+    }
+}
+
+/// A document of a [MirrorManifest](struct.MirrorManifest.html).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// The id of the document.
+    pub id: String,
+    /// The size of the document in bytes.
+    pub size: u64,
+    /// The last time the document was updated when it was downloaded.
+    pub updated_at: String,
+}
+
+/// Written next to the files of a mirror, lists the downloaded documents by path.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorManifest {
+    /// The mirrored project.
+    pub project: String,
+    /// The documents by path inside the project.
+    pub documents: std::collections::BTreeMap<String, ManifestEntry>,
+    /// The folder paths inside the project, missing in manifests of older versions.
+    #[serde(default)]
+    pub folders: Vec<String>,
+}
+
+/// What `mirror_project` wrote, every entry is a path inside the project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MirrorReport {
+    /// Documents that were new or changed and were downloaded.
+    pub downloaded: Vec<String>,
+    /// Documents that did not change since the last mirror.
+    pub unchanged: Vec<String>,
+    /// Files of the last mirror that were removed because the document no longer exists.
+    /// Directories left empty by them, or by deleted folders, are removed too.
+    pub removed: Vec<String>,
+    /// Documents that were not written because their path is reserved for the manifest.
+    pub skipped: Vec<String>,
+}
+
+/// Returned by `get_content_if_changed` and `get_own_content_if_changed`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionalContent<T> {
+    /// The content changed, with its new `ETag` if the server sent one.
+    Changed(T, Option<String>),
+    /// The content did not change since the given `ETag`.
+    NotModified,
+}
+
+/// The version of a document `update_own_document_after_check` expects to overwrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedVersion {
+    /// The `updated_at` of the [DocumentMeta](struct.DocumentMeta.html) that was read.
+    UpdatedAt(String),
+    /// The [content_hash](../fn.content_hash.html) of the content that was read.
+    ContentHash(String),
+}
+
+impl From<&DocumentMeta> for ExpectedVersion {
+    fn from(meta: &DocumentMeta) -> Self {
+        ExpectedVersion::UpdatedAt(meta.updated_at.clone())
+    }
+}
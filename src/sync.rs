@@ -0,0 +1,373 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use crate::{trace, JsonBankApi, JsonValue};
+use crate::error::*;
+use crate::functions::MAX_PER_PAGE;
+use crate::structs::*;
+
+/// The name of the manifest `mirror_project` writes in the mirror directory.
+/// It starts with a dot, so `sync_directory` skips it.
+pub const MIRROR_MANIFEST: &str = ".jsonbank-manifest.json";
+
+// A local directory, folders are listed before their children
+struct LocalTree {
+    // paths inside the project, e.g. `configs/prod`
+    folders: Vec<String>,
+    // path inside the project and content of every json file
+    documents: Vec<(String, String)>,
+}
+
+/// Mirrors `local_dir` to `project`, see `JsonBankApi::sync_directory`.
+pub(crate) fn sync_directory<A: JsonBankApi + ?Sized>(api: &A, local_dir: &Path, project: &str, options: &SyncOptions) -> Result<SyncReport, JsbError> {
+    let _span = trace::operation("sync_directory");
+
+    if project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    // read every file first, so an invalid file stops the sync before anything is changed
+    let tree = read_local_tree(local_dir)?;
+
+    // list the project first too, a backend without listing cannot delete and must not change anything
+    let remote = match options.delete {
+        true => Some(remote_tree(api, project)?),
+        false => None,
+    };
+
+    let mut report = SyncReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    for path in &tree.folders {
+        let (parent, name) = split_path(path);
+
+        if options.dry_run {
+            match api.get_folder(&format!("{}/{}", project, path)) {
+                Ok(_) => {}
+                Err(err) if err.kind == ErrorKind::NotFound => report.created_folders.push(path.clone()),
+                Err(err) => return Err(err),
+            }
+            continue;
+        }
+
+        let (_, exists) = api.create_folder_if_not_exists(CreateFolderBody {
+            name: name.to_string(),
+            project: project.to_string(),
+            folder: parent.map(|parent| parent.to_string()),
+        })?;
+
+        if !exists {
+            report.created_folders.push(path.clone());
+        }
+    }
+
+    for (path, content) in &tree.documents {
+        let id = format!("{}/{}", project, path);
+
+        // compare with the server, a cached copy may be outdated
+        match api.get_own_content_uncached(&id) {
+            Ok(remote) => {
+                if same_json(&remote, content) {
+                    report.unchanged.push(path.clone());
+                    continue;
+                }
+
+                if !options.dry_run {
+                    api.update_own_document(&id, content.clone())?;
+                }
+                report.updated.push(path.clone());
+            }
+            Err(err) if err.kind == ErrorKind::NotFound => {
+                if !options.dry_run {
+                    let (folder, name) = split_path(path);
+                    api.create_document_if_not_exists(CreateDocumentBody {
+                        name: name.to_string(),
+                        project: project.to_string(),
+                        content: content.clone(),
+                        folder: folder.map(|folder| folder.to_string()),
+                    })?;
+                }
+                report.created.push(path.clone());
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if let Some((folders, documents)) = remote {
+        let local: HashSet<&str> = tree.documents.iter().map(|(path, _)| path.as_str()).collect();
+
+        for path in documents.into_iter().map(|doc| doc.path) {
+            if local.contains(path.as_str()) {
+                continue;
+            }
+
+            if !options.dry_run {
+                api.delete_document(&format!("{}/{}", project, path))?;
+            }
+            report.deleted.push(path);
+        }
+
+        // folders cannot be deleted, they are reported instead
+        let local: HashSet<&str> = tree.folders.iter().map(|path| path.as_str()).collect();
+        report.orphaned_folders = folders.into_iter().filter(|path| !local.contains(path.as_str())).collect();
+    }
+
+    Ok(report)
+}
+
+/// Downloads `project` into `dest_dir`, see `JsonBankApi::mirror_project`.
+pub(crate) fn mirror_project<A: JsonBankApi + ?Sized>(api: &A, project: &str, dest_dir: &Path) -> Result<MirrorReport, JsbError> {
+    let _span = trace::operation("mirror_project");
+
+    if project.is_empty() {
+        return Err(err_required("Project"));
+    }
+
+    // a backend without listing fails before anything is read or written
+    let (folders, documents) = remote_tree(api, project)?;
+
+    let manifest_path = dest_dir.join(MIRROR_MANIFEST);
+    let previous = read_manifest(&manifest_path, project)?;
+
+    // check every remote path before writing anything
+    for path in folders.iter().chain(documents.iter().map(|doc| &doc.path)) {
+        check_local_path(path)?;
+    }
+
+    fs::create_dir_all(dest_dir)?;
+    for path in &folders {
+        fs::create_dir_all(dest_dir.join(path))?;
+    }
+
+    let mut report = MirrorReport::default();
+    let mut manifest = MirrorManifest {
+        project: project.to_string(),
+        documents: Default::default(),
+        folders: folders.clone(),
+    };
+
+    for doc in documents {
+        // the manifest name is reserved, a document with that name would overwrite it
+        if doc.path == MIRROR_MANIFEST {
+            report.skipped.push(doc.path);
+            continue;
+        }
+
+        let file = dest_dir.join(&doc.path);
+
+        // skip documents that did not change since the last mirror
+        let unchanged = match previous.documents.get(&doc.path) {
+            Some(entry) => entry.id == doc.id && entry.updated_at == doc.updated_at && file.is_file(),
+            None => false,
+        };
+
+        if unchanged {
+            report.unchanged.push(doc.path.clone());
+        } else {
+            // the document changed, a cached copy is outdated
+            let content = api.get_own_content_uncached(&format!("{}/{}", project, doc.path))?;
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file, content)?;
+            report.downloaded.push(doc.path.clone());
+        }
+
+        manifest.documents.insert(doc.path, ManifestEntry {
+            id: doc.id,
+            size: doc.content_size.number,
+            updated_at: doc.updated_at,
+        });
+    }
+
+    // remove the files of documents that were deleted, files the mirror did not write are kept
+    for path in previous.documents.keys() {
+        if manifest.documents.contains_key(path) || check_local_path(path).is_err() {
+            continue;
+        }
+
+        match fs::remove_file(dest_dir.join(path)) {
+            Ok(_) => report.removed.push(path.clone()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(JsbError::from(err)),
+        }
+    }
+
+    // remove the directories of deleted folders and of removed files, the ones that still have files are kept
+    let mut emptied: Vec<&str> = previous.folders.iter()
+        .filter(|path| !folders.contains(path))
+        .map(|path| path.as_str())
+        .chain(report.removed.iter().filter_map(|path| split_path(path).0))
+        .filter(|path| check_local_path(path).is_ok())
+        .collect();
+    // children first
+    emptied.sort_by(|a, b| b.cmp(a));
+    emptied.dedup();
+
+    for path in emptied {
+        let mut dir = Some(path);
+        while let Some(path) = dir {
+            if folders.iter().any(|folder| folder == path) || !remove_empty_dir(&dest_dir.join(path))? {
+                break;
+            }
+            dir = split_path(path).0;
+        }
+    }
+
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    Ok(report)
+}
+
+// read_manifest - reads the manifest of a previous mirror of the same project, if any
+fn read_manifest(path: &Path, project: &str) -> Result<MirrorManifest, JsbError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(MirrorManifest::default()),
+        Err(err) => return Err(JsbError::from(err)),
+    };
+
+    let manifest: MirrorManifest = serde_json::from_str(&content)?;
+
+    // a mirror of another project is downloaded again
+    if manifest.project != project {
+        return Ok(MirrorManifest::default());
+    }
+
+    Ok(manifest)
+}
+
+// remove_empty_dir - removes a directory if it is empty, returns false if it was not
+fn remove_empty_dir(dir: &Path) -> Result<bool, JsbError> {
+    let mut entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+        Err(err) => return Err(JsbError::from(err)),
+    };
+
+    if entries.next().is_some() {
+        return Ok(false);
+    }
+
+    fs::remove_dir(dir)?;
+    Ok(true)
+}
+
+// check_local_path - rejects remote paths that would be written outside of the mirror
+fn check_local_path(path: &str) -> Result<(), JsbError> {
+    let valid = path.split('/').all(|segment| {
+        !segment.is_empty() && segment != "." && segment != ".." && !segment.contains('\\') && !segment.contains(':')
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(JsbError::new(ErrorKind::Validation, "bad_request", &format!("Cannot mirror path: {}", path)))
+    }
+}
+
+// read_local_tree - lists the folders and json files of a directory, hidden files are skipped
+fn read_local_tree(local_dir: &Path) -> Result<LocalTree, JsbError> {
+    if !local_dir.is_dir() {
+        return Err(JsbError::new(ErrorKind::Io, "file_not_found", &format!("Directory does not exist: {}", local_dir.display())));
+    }
+
+    let mut tree = LocalTree { folders: Vec::new(), documents: Vec::new() };
+    read_local_dir(local_dir, "", &mut tree)?;
+    Ok(tree)
+}
+
+// read_local_dir - adds the entries of a directory to the tree, `prefix` is its path inside the project
+fn read_local_dir(dir: &Path, prefix: &str, tree: &mut LocalTree) -> Result<(), JsbError> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        entries.push(entry?);
+    }
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            // jsonbank paths are strings
+            Err(_) => continue,
+        };
+
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = format!("{}{}", prefix, name);
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            tree.folders.push(path.clone());
+            read_local_dir(&entry.path(), &format!("{}/", path), tree)?;
+        } else if name.ends_with(".json") {
+            let content = fs::read_to_string(entry.path())?;
+
+            if serde_json::from_str::<JsonValue>(&content).is_err() {
+                return Err(JsbError::new(ErrorKind::InvalidJson, "invalid_json_content", &format!("{} is not a valid JSON file", path)));
+            }
+
+            tree.documents.push((path, content));
+        }
+    }
+
+    Ok(())
+}
+
+// remote_tree - the folder paths and the documents of a project, including the ones in folders
+fn remote_tree<A: JsonBankApi + ?Sized>(api: &A, project: &str) -> Result<(Vec<String>, Vec<DocumentMeta>), JsbError> {
+    let mut documents = Vec::new();
+    let mut all_folders = Vec::new();
+    let mut folders: Vec<Option<String>> = vec![None];
+
+    while let Some(folder) = folders.pop() {
+        let mut options = ListOptions { per_page: MAX_PER_PAGE, ..Default::default() };
+
+        loop {
+            let page = match &folder {
+                Some(path) => api.list_folder_contents(&format!("{}/{}", project, path), options.clone())?,
+                None => api.list_project_contents(project, options.clone())?,
+            };
+
+            let has_more = page.has_more();
+            for item in page.items {
+                match item {
+                    ContentItem::Document(doc) => documents.push(doc),
+                    ContentItem::Folder(folder) => {
+                        all_folders.push(folder.path.clone());
+                        folders.push(Some(folder.path));
+                    }
+                }
+            }
+
+            if !has_more {
+                break;
+            }
+            options.page += 1;
+        }
+    }
+
+    all_folders.sort();
+    documents.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((all_folders, documents))
+}
+
+// split_path - splits `a/b/name` into the parent path and the name
+fn split_path(path: &str) -> (Option<&str>, &str) {
+    match path.rsplit_once('/') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path),
+    }
+}
+
+// same_json - compares two json strings by value, so formatting changes are not updates
+fn same_json(a: &str, b: &str) -> bool {
+    match (serde_json::from_str::<JsonValue>(a), serde_json::from_str::<JsonValue>(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -0,0 +1,404 @@
+//! A local JsonBank server for offline tests.
+//!
+//! [MockServer](struct.MockServer.html) runs in-process and implements the routes used by the SDK
+//! with in-memory projects, folders and documents. Only available with the `test-util` feature.
+//! # Example
+//! ```
+//! use jsonbank::{JsonBank, InitConfig, JsonValue};
+//! use jsonbank::testing::MockServer;
+//!
+//! let server = MockServer::start();
+//! server.add_project("sdk-test", true);
+//! server.add_document("sdk-test", "index.json", r#"{"author": "jsonbank"}"#);
+//!
+//! let jsb = JsonBank::new(InitConfig {
+//!     host: Some(server.url()),
+//!     keys: Some(server.keys()),
+//!     ..Default::default()
+//! });
+//!
+//! let data: JsonValue = jsb.get_own_content("sdk-test/index.json").unwrap();
+//! assert_eq!(data["author"], "jsonbank");
+//! ```
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+use crate::{JsonBankApi, JsonObject, Keys};
+use crate::error::{ErrorKind, JsbError};
+use crate::stores::MemoryStore;
+use crate::structs::{CreateDocumentBody, CreateFolderBody};
+
+/// Public key accepted by the mock server
+pub const MOCK_PUBLIC_KEY: &str = "jsb_mock_public_key";
+/// Private key accepted by the mock server
+pub const MOCK_PRIVATE_KEY: &str = "jsb_mock_private_key";
+
+// An error returned by a route, sent as `{"error": {"code", "message"}}`
+struct RouteError {
+    status: u16,
+    code: String,
+    message: String,
+}
+
+impl RouteError {
+    fn new(status: u16, code: &str, message: &str) -> RouteError {
+        RouteError { status, code: code.to_string(), message: message.to_string() }
+    }
+
+    fn not_found(message: &str) -> RouteError {
+        RouteError::new(404, "notFound", message)
+    }
+}
+
+// Errors of the store are sent with the status the api uses for their kind
+impl From<JsbError> for RouteError {
+    fn from(err: JsbError) -> Self {
+        let status = match err.kind {
+            ErrorKind::NotFound => 404,
+            ErrorKind::AlreadyExists => 409,
+            ErrorKind::Unauthorized => 401,
+            ErrorKind::InvalidJson | ErrorKind::Validation => 400,
+            _ => 500,
+        };
+
+        RouteError::new(err.status.unwrap_or(status), &err.code, &err.message)
+    }
+}
+
+type RouteResult = Result<(u16, String), RouteError>;
+
+// ok_json - a successful response with a serialized body
+fn ok_json<T: Serialize>(data: &T) -> RouteResult {
+    match serde_json::to_string(data) {
+        Ok(body) => Ok((200, body)),
+        Err(err) => Err(RouteError::new(500, "server_error", &err.to_string())),
+    }
+}
+
+// Server state that is not part of the store
+#[derive(Default)]
+struct State {
+    github: HashMap<String, String>,
+    requests: u64,
+}
+
+/// A JsonBank api server running on a local port.
+///
+/// Projects, folders and documents are kept in a [MemoryStore](../stores/struct.MemoryStore.html),
+/// available with [store](#method.store).
+/// The server is stopped when it is dropped.
+pub struct MockServer {
+    url: String,
+    store: Arc<MemoryStore>,
+    state: Arc<Mutex<State>>,
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Debug for MockServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockServer").field("url", &self.url).finish()
+    }
+}
+
+impl MockServer {
+    /// Start a server on a random local port.
+    /// Documents belong to the user `jsonbank`, use [keys](#method.keys) to access them.
+    pub fn start() -> MockServer {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Failed to start mock server"));
+        let url = format!("http://{}", server.server_addr());
+
+        let store = Arc::new(MemoryStore::new());
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let handle = {
+            let server = server.clone();
+            let store = store.clone();
+            let state = state.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle_request(&store, &state, request);
+                }
+            })
+        };
+
+        MockServer { url, store, state, server, handle: Some(handle) }
+    }
+
+    /// The url of the server, to be used as host.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Api keys accepted by the server.
+    pub fn keys(&self) -> Keys {
+        Keys {
+            public: Some(MOCK_PUBLIC_KEY.to_string()),
+            private: Some(MOCK_PRIVATE_KEY.to_string()),
+        }
+    }
+
+    /// The store holding the projects, folders and documents of the server.
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    /// Number of requests handled so far.
+    pub fn request_count(&self) -> u64 {
+        self.state.lock().unwrap().requests
+    }
+
+    /// Add a project. Documents of public projects can be read without api keys.
+    pub fn add_project(&self, name: &str, public: bool) {
+        self.store.add_project(name, public);
+    }
+
+    /// Add a folder, `path` is the path inside the project e.g. `parent/name`.
+    /// Missing parent folders and the project are created.
+    pub fn add_folder(&self, project: &str, path: &str) -> String {
+        self.ensure_project(project);
+        match self.store.add_folder(project, path) {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err.message),
+        }
+    }
+
+    /// Add a document, `path` is the path inside the project e.g. `folder/name.json`.
+    /// Missing folders and the project are created. Returns the id of the document.
+    pub fn add_document(&self, project: &str, path: &str, content: &str) -> String {
+        self.ensure_project(project);
+        match self.store.add_document(project, path, content) {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err.message),
+        }
+    }
+
+    /// Add a file served by the github route, `path` is `owner/repo/path/to/file.json`.
+    pub fn add_github_file(&self, path: &str, content: &str) {
+        self.state.lock().unwrap().github.insert(path.to_string(), content.to_string());
+    }
+
+    /// Get the content of a document by id or `project/path`.
+    pub fn document_content(&self, id_or_path: &str) -> Option<String> {
+        self.store.document_content(id_or_path)
+    }
+
+    // ensure_project - adds a private project if it does not exist
+    fn ensure_project(&self, project: &str) {
+        if !self.store.has_project(project) {
+            self.store.add_project(project, false);
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// percent_decode - decodes a percent encoded url path
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers().iter()
+        .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+// check_key - checks the public or private key header of a request
+fn check_key(request: &Request, name: &str, expected: &str) -> Result<(), RouteError> {
+    match header(request, name) {
+        Some(key) if key == expected => Ok(()),
+        Some(_) => Err(RouteError::new(401, "unauthorized", "Invalid api key")),
+        None => Err(RouteError::new(401, "unauthorized", "Api key is required")),
+    }
+}
+
+fn handle_request(store: &MemoryStore, state: &Mutex<State>, mut request: Request) {
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    let url = request.url().to_string();
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (percent_decode(path), query.to_string()),
+        None => (percent_decode(&url), String::new()),
+    };
+
+    state.lock().unwrap().requests += 1;
+    let result = route(store, state, &request, &path, &query, &body);
+
+    let (status, data) = match result {
+        Ok(res) => res,
+        Err(err) => (err.status, json!({ "error": { "code": err.code, "message": err.message } }).to_string()),
+    };
+
+    // content responses have an etag and honor `If-None-Match`
+    let etag = match is_content_route(request.method(), &path) && status == 200 {
+        true => Some(content_etag(&data)),
+        false => None,
+    };
+    let not_modified = match (&etag, header(&request, "If-None-Match")) {
+        (Some(etag), Some(previous)) => previous.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"),
+        _ => false,
+    };
+
+    let mut response = match not_modified {
+        true => Response::from_string(String::new()).with_status_code(304),
+        false => Response::from_string(data)
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap()),
+    };
+    if let Some(etag) = etag {
+        response.add_header(Header::from_bytes("ETag", etag).unwrap());
+    }
+
+    let _ = request.respond(response);
+}
+
+// is_content_route - true for the public and own content routes
+fn is_content_route(method: &Method, path: &str) -> bool {
+    let path = path.trim_start_matches('/');
+    *method == Method::Get && (path.starts_with("f/") || path.starts_with("v1/file/"))
+}
+
+// content_etag - a strong etag of the content of a document
+fn content_etag(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+// route - handles a request and returns the status and body of the response
+fn route(store: &MemoryStore, state: &Mutex<State>, request: &Request, path: &str, query: &str, body: &str) -> RouteResult {
+    let method = request.method().clone();
+    let path = path.trim_start_matches('/');
+
+    // public routes
+    if method == Method::Get {
+        if let Some(path) = path.strip_prefix("meta/f/") {
+            let (meta, _) = store.get_public_document(path)?;
+            return ok_json(&meta);
+        }
+
+        if let Some(path) = path.strip_prefix("f/") {
+            let (_, content) = store.get_public_document(path)?;
+            return Ok((200, content));
+        }
+
+        if let Some(path) = path.strip_prefix("gh/") {
+            return match state.lock().unwrap().github.get(path) {
+                Some(content) => Ok((200, content.clone())),
+                None => Err(RouteError::not_found("File not found")),
+            };
+        }
+    }
+
+    let path = match path.strip_prefix("v1/") {
+        Some(path) => path,
+        None => return Err(RouteError::not_found("Route not found")),
+    };
+
+    let data: JsonObject = if body.is_empty() {
+        JsonObject::new()
+    } else {
+        match serde_json::from_str(body) {
+            Ok(data) => data,
+            Err(_) => return Err(RouteError::new(400, "bad_request", "Body is not valid json")),
+        }
+    };
+
+    let string_field = |name: &str| data.get(name).and_then(|v| v.as_str()).map(|v| v.to_string());
+
+    match (method, path) {
+        (Method::Post, "authenticate") => {
+            check_key(request, "jsb-pub-key", MOCK_PUBLIC_KEY)?;
+            ok_json(&store.authenticated_data("mock"))
+        }
+        (Method::Get, path) if path.starts_with("meta/file/") => {
+            check_key(request, "jsb-pub-key", MOCK_PUBLIC_KEY)?;
+            ok_json(&store.get_own_document_meta(&path["meta/file/".len()..])?)
+        }
+        (Method::Get, path) if path.starts_with("file/") => {
+            check_key(request, "jsb-pub-key", MOCK_PUBLIC_KEY)?;
+            Ok((200, store.get_own_content_as_string(&path["file/".len()..])?))
+        }
+        (Method::Post, path) if path.starts_with("file/") => {
+            check_key(request, "jsb-prv-key", MOCK_PRIVATE_KEY)?;
+            let content = string_field("content").unwrap_or_default();
+            ok_json(&store.update_own_document(&path["file/".len()..], content)?)
+        }
+        (Method::Delete, path) if path.starts_with("file/") => {
+            check_key(request, "jsb-prv-key", MOCK_PRIVATE_KEY)?;
+            match store.delete_document(&path["file/".len()..])? {
+                res if res.deleted => ok_json(&res),
+                _ => Err(RouteError::not_found("Document not found")),
+            }
+        }
+        (Method::Get, path) if path.starts_with("folder/") => {
+            check_key(request, "jsb-pub-key", MOCK_PUBLIC_KEY)?;
+            let id_or_path = &path["folder/".len()..];
+            if query.split('&').any(|q| q == "stats=true") {
+                ok_json(&store.get_folder_with_stats(id_or_path)?)
+            } else {
+                ok_json(&store.get_folder(id_or_path)?)
+            }
+        }
+        (Method::Post, path) if path.starts_with("project/") => {
+            check_key(request, "jsb-prv-key", MOCK_PRIVATE_KEY)?;
+            let (project, action) = match path["project/".len()..].split_once('/') {
+                Some(parts) => parts,
+                None => return Err(RouteError::not_found("Route not found")),
+            };
+
+            let name = string_field("name").unwrap_or_default();
+            let folder = string_field("folder");
+
+            match action {
+                "document" => ok_json(&store.create_document(CreateDocumentBody {
+                    name,
+                    project: project.to_string(),
+                    content: string_field("content").unwrap_or_default(),
+                    folder,
+                })?),
+                "folder" => ok_json(&store.create_folder(CreateFolderBody {
+                    name,
+                    project: project.to_string(),
+                    folder,
+                })?),
+                _ => Err(RouteError::not_found("Route not found")),
+            }
+        }
+        _ => Err(RouteError::not_found("Route not found")),
+    }
+}
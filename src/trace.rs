@@ -0,0 +1,116 @@
+// Spans of the `tracing` feature.
+//
+// Every request gets a `jsonbank.request` span, operations made of several requests
+// get a `jsonbank.operation` span that the spans of their requests nest in.
+// Headers are never recorded, so keys can not end up in a span.
+// Without the feature every type here is empty and every function does nothing.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+use crate::error::JsbError;
+use crate::request::{ApiRequest, ApiResponse};
+
+/// Span of a single request, including its retries.
+#[cfg(feature = "tracing")]
+pub(crate) struct RequestSpan {
+    span: tracing::Span,
+    start: Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl RequestSpan {
+    pub fn new(req: &ApiRequest) -> Self {
+        // only the path is recorded, the query may hold anything
+        let url = reqwest::Url::parse(&req.url).ok();
+        let span = tracing::info_span!(
+            "jsonbank.request",
+            operation = req.operation,
+            method = %req.method,
+            host = url.as_ref().and_then(|url| url.host_str()),
+            path = url.as_ref().map(|url| url.path()),
+            status = tracing::field::Empty,
+            bytes = tracing::field::Empty,
+            retries = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            error = tracing::field::Empty,
+        );
+
+        RequestSpan { span, start: Instant::now() }
+    }
+
+    pub fn enter(&self) -> tracing::span::Entered<'_> {
+        self.span.enter()
+    }
+
+    #[cfg(feature = "async")]
+    pub fn instrument<F: std::future::Future>(&self, future: F) -> tracing::instrument::Instrumented<F> {
+        tracing::Instrument::instrument(future, self.span.clone())
+    }
+
+    // finish - records the outcome of the last attempt
+    pub fn finish(&self, attempts: u32, res: &Result<ApiResponse, JsbError>) {
+        self.span.record("retries", attempts - 1);
+        self.span.record("latency_ms", self.start.elapsed().as_millis() as u64);
+
+        match res {
+            Ok(res) => {
+                self.span.record("status", res.status.as_u16());
+                self.span.record("bytes", res.body.len() as u64);
+                tracing::debug!(parent: &self.span, status = res.status.as_u16(), "jsonbank request finished");
+            }
+            Err(err) => {
+                self.span.record("error", err.code.as_str());
+                if let Some(status) = err.status {
+                    self.span.record("status", status);
+                }
+                tracing::debug!(parent: &self.span, error = err.code.as_str(), "jsonbank request failed");
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct RequestSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Entered;
+
+#[cfg(not(feature = "tracing"))]
+impl RequestSpan {
+    pub fn new(_req: &ApiRequest) -> Self {
+        RequestSpan
+    }
+
+    pub fn enter(&self) -> Entered {
+        Entered
+    }
+
+    #[cfg(feature = "async")]
+    pub fn instrument<F: std::future::Future>(&self, future: F) -> F {
+        future
+    }
+
+    pub fn finish(&self, _attempts: u32, _res: &Result<ApiResponse, JsbError>) {}
+}
+
+// operation - enters the span of an operation made of several requests, it is left when the guard is dropped
+#[cfg(feature = "tracing")]
+pub(crate) fn operation(name: &'static str) -> tracing::span::EnteredSpan {
+    tracing::info_span!("jsonbank.operation", operation = name).entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn operation(_name: &'static str) -> Entered {
+    Entered
+}
+
+// instrument_operation - runs a future in the span of an operation made of several requests
+#[cfg(all(feature = "async", feature = "tracing"))]
+pub(crate) fn instrument_operation<F: std::future::Future>(name: &'static str, future: F) -> tracing::instrument::Instrumented<F> {
+    tracing::Instrument::instrument(future, tracing::info_span!("jsonbank.operation", operation = name))
+}
+
+#[cfg(all(feature = "async", not(feature = "tracing")))]
+pub(crate) fn instrument_operation<F: std::future::Future>(_name: &'static str, future: F) -> F {
+    future
+}
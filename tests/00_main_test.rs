@@ -1,15 +1,22 @@
 mod functions;
 
 use jsonbank::*;
+use jsonbank::error::ErrorKind;
 use functions::*;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
 
 
 // init - initializes test
 fn init() -> (JsonBank, TestData) {
-    let env = load_env();
-
-    let mut jsb = JsonBank::new_without_config();
-    jsb.set_host(env.host.as_str());
+    let jsb = match load_env().build() {
+        Ok(jsb) => jsb,
+        Err(err) => panic!("{:?}", err),
+    };
 
     prepare_instance(jsb, false)
 }
@@ -135,4 +142,94 @@ fn get_github_content_as_string() {
 
     // string contains prepublishOnly
     assert!(content.contains("prepublishOnly"));
-}
\ No newline at end of file
+}
+
+// keep_alive_server - starts a server that keeps connections open and answers every request with `{"author":"jsonbank"}`,
+// returns its url and the number of accepted connections
+fn keep_alive_server() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            accepted.fetch_add(1, Ordering::SeqCst);
+
+            thread::spawn(move || {
+                let body = r#"{"author":"jsonbank"}"#;
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+
+                // requests without a body end with an empty line
+                while let Ok(n) = stream.read(&mut buf) {
+                    if n == 0 {
+                        break;
+                    }
+
+                    request.extend_from_slice(&buf[..n]);
+                    while let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        request.drain(..end + 4);
+                        let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+                        stream.write_all(response.as_bytes()).unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    (url, connections)
+}
+
+#[test]
+fn pooled_client_reuses_connections() {
+    let (url, connections) = keep_alive_server();
+    let jsb = JsonBank::builder()
+        .host(&url)
+        .pool(PoolConfig {
+            idle_timeout: Some(Duration::from_secs(30)),
+            max_idle_per_host: Some(2),
+            http_version: HttpVersion::Http1Only,
+        })
+        .build()
+        .unwrap();
+
+    // every read goes through the same connection
+    for _ in 0..5 {
+        let content: JsonObject = match jsb.get_content("jsonbank/sdk-test/index.json") {
+            Ok(content) => content,
+            Err(err) => panic!("{:?}", err),
+        };
+
+        assert_eq!(content["author"], JSONBANK);
+    }
+
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn missing_key_error_kind() {
+    let jsb = JsonBank::new_without_config();
+
+    // private key is required to delete a document
+    let err = match jsb.delete_document("sdk-test/index.json") {
+        Ok(_) => panic!("Expected missing key error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.kind, ErrorKind::MissingKey);
+    assert_eq!(err.status, None);
+}
+
+#[test]
+fn invalid_json_error_kind() {
+    let jsb = JsonBank::new_without_config();
+
+    let err = match jsb.update_own_document("sdk-test/index.json", "{invalid".to_string()) {
+        Ok(_) => panic!("Expected invalid json error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+}
@@ -1,11 +1,14 @@
+#![allow(clippy::bool_assert_comparison)]
+
 extern crate dotenv;
 
 mod functions;
 
 use std::time::{SystemTime, UNIX_EPOCH};
-use jsonbank::{JsonBank, InitConfig, Keys, JsonObject, JSONBANK};
+use jsonbank::{JsonBank, JsonObject, JSONBANK};
 use functions::*;
 use jsonbank::structs::{CreateDocumentBody, CreateFolderBody, Folder, UploadDocumentBody};
+use jsonbank::error::ErrorKind;
 
 // test_file_content - returns test file content
 fn test_file_content() -> String {
@@ -17,18 +20,11 @@ fn test_file_content() -> String {
 
 // init - initializes test
 fn init() -> (JsonBank, TestData) {
-    let env = load_env();
-    let config = InitConfig {
-        host: Some(env.host.clone()),
-        keys: Some(Keys {
-            public: Some(env.public_key),
-            private: Some(env.private_key),
-        }),
+    let jsb = match load_env().build() {
+        Ok(jsb) => jsb,
+        Err(err) => panic!("{:?}", err),
     };
 
-    let mut jsb = JsonBank::new(config);
-    jsb.set_host(env.host.as_str());
-
     prepare_instance(jsb, true)
 }
 
@@ -80,6 +76,29 @@ fn has_own_document(){
 }
 
 
+#[test]
+fn missing_document_is_not_found() {
+    let (jsb, data) = init();
+
+    let path = format!("{}/{}", data.project, "does-not-exist.json");
+    let err = match jsb.get_own_content::<JsonObject>(&path) {
+        Ok(_) => panic!("Expected not found error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.kind, ErrorKind::NotFound);
+    assert_eq!(err.status, Some(404));
+
+    // has_own_document maps not found to false
+    let has = match jsb.has_own_document(&path) {
+        Ok(has) => has,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert!(!has);
+}
+
+
 #[test]
 fn get_own_content() {
     let (jsb, data) = init();
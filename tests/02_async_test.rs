@@ -0,0 +1,120 @@
+extern crate dotenv;
+
+mod functions;
+
+use jsonbank::{AsyncJsonBank, JsonObject, JSONBANK};
+use jsonbank::structs::{CreateDocumentBody, CreateFolderBody};
+use functions::*;
+
+// init - initializes test
+fn init() -> AsyncJsonBank {
+    match load_env().build_async() {
+        Ok(jsb) => jsb,
+        Err(err) => panic!("{:?}", err),
+    }
+}
+
+#[tokio::test]
+async fn get_content() {
+    let jsb = init();
+
+    let content: JsonObject = match jsb.get_content("jsonbank/sdk-test/index.json").await {
+        Ok(content) => content,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(content["author"], JSONBANK);
+
+    let meta = match jsb.get_document_meta("jsonbank/sdk-test/index.json").await {
+        Ok(meta) => meta,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(meta.path, "index.json");
+}
+
+#[tokio::test]
+async fn authenticate() {
+    let mut jsb = init();
+
+    let auth = match jsb.authenticate().await {
+        Ok(auth) => auth,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert!(auth.authenticated);
+    assert!(jsb.is_authenticated());
+    assert_eq!(jsb.get_username().unwrap(), JSONBANK);
+}
+
+#[tokio::test]
+async fn get_own_content() {
+    let jsb = init();
+
+    let content: JsonObject = match jsb.get_own_content("sdk-test/index.json").await {
+        Ok(content) => content,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(content["author"], JSONBANK);
+    assert!(jsb.has_own_document("sdk-test/index.json").await.unwrap());
+}
+
+#[tokio::test]
+async fn create_update_and_delete_document() {
+    let jsb = init();
+    let path = "sdk-test/async.json";
+
+    // delete test file if it exists
+    let _ = jsb.delete_document(path).await;
+
+    let new_doc = match jsb.create_document_if_not_exists(CreateDocumentBody {
+        name: "async.json".to_string(),
+        project: "sdk-test".to_string(),
+        folder: None,
+        content: r#"{"async": true}"#.to_string(),
+    }).await {
+        Ok(doc) => doc,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(new_doc.path, "async.json");
+    assert!(!new_doc.exists);
+
+    let res = match jsb.update_own_document(path, r#"{"async": false}"#.to_string()).await {
+        Ok(res) => res,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert!(res.changed);
+
+    let res = match jsb.delete_document(path).await {
+        Ok(res) => res,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert!(res.deleted);
+}
+
+#[tokio::test]
+async fn create_folder_if_not_exists() {
+    let jsb = init();
+
+    let (folder, _) = match jsb.create_folder_if_not_exists(CreateFolderBody {
+        name: "folder".to_string(),
+        project: "sdk-test".to_string(),
+        folder: None,
+    }).await {
+        Ok(res) => res,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(folder.name, "folder");
+
+    let folder = match jsb.get_folder_with_stats(&folder.id).await {
+        Ok(folder) => folder,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert!(folder.stats.is_some());
+}
@@ -0,0 +1,82 @@
+use jsonbank::structs::{AuthenticatedData, DocumentMeta, Folder, NewDocument};
+
+#[test]
+fn decode_document_meta() {
+    let meta: DocumentMeta = serde_json::from_str(r#"{
+        "id": "doc-id",
+        "project": "sdk-test",
+        "path": "folder/index.json",
+        "name": "index.json",
+        "folderId": "folder-id",
+        "contentSize": { "number": 58, "string": "58 B" },
+        "updatedAt": "2023-01-01T00:00:00.000Z",
+        "createdAt": "2023-01-01T00:00:00.000Z"
+    }"#).unwrap();
+
+    assert_eq!(meta.folder_id, Some("folder-id".to_string()));
+    assert_eq!(meta.content_size.number, 58);
+}
+
+#[test]
+fn decode_reports_missing_field() {
+    // contentSize is missing
+    let err = match serde_json::from_str::<DocumentMeta>(r#"{
+        "id": "doc-id",
+        "project": "sdk-test",
+        "path": "index.json",
+        "name": "index.json",
+        "updatedAt": "2023-01-01T00:00:00.000Z",
+        "createdAt": "2023-01-01T00:00:00.000Z"
+    }"#) {
+        Ok(_) => panic!("Expected decode error"),
+        Err(err) => err,
+    };
+
+    assert!(err.to_string().contains("contentSize"));
+}
+
+#[test]
+fn decode_folder_and_new_document() {
+    let folder: Folder = serde_json::from_str(r#"{
+        "id": "folder-id",
+        "name": "folder",
+        "path": "folder",
+        "project": "sdk-test",
+        "createdAt": "2023-01-01T00:00:00.000Z",
+        "updatedAt": "2023-01-01T00:00:00.000Z",
+        "stats": { "documents": 2, "folders": 0 }
+    }"#).unwrap();
+
+    assert_eq!(folder.stats.unwrap().documents, 2);
+
+    // exists is not returned by the api
+    let doc: NewDocument = serde_json::from_str(r#"{
+        "id": "doc-id",
+        "name": "index.json",
+        "path": "index.json",
+        "project": "sdk-test",
+        "createdAt": "2023-01-01T00:00:00.000Z"
+    }"#).unwrap();
+
+    assert!(!doc.exists);
+}
+
+#[test]
+fn decode_authenticated_data() {
+    let data: AuthenticatedData = serde_json::from_str(r#"{
+        "authenticated": true,
+        "username": "jsonbank",
+        "apiKey": { "title": "sdk", "projects": ["sdk-test"] }
+    }"#).unwrap();
+
+    assert_eq!(data.api_key.projects, vec!["sdk-test".to_string()]);
+
+    // wrong type is an error, not a panic
+    let res = serde_json::from_str::<AuthenticatedData>(r#"{
+        "authenticated": "yes",
+        "username": "jsonbank",
+        "apiKey": { "title": "sdk", "projects": [] }
+    }"#);
+
+    assert!(res.is_err());
+}
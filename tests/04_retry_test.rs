@@ -0,0 +1,141 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use jsonbank::{JsonBank, InitConfig, Keys, JsonObject, RequestOptions, RetryPolicy};
+use jsonbank::structs::CreateDocumentBody;
+
+// serve - starts a server that answers each request with the next canned response
+// and returns its url and the list of received request lines
+fn serve(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+
+    thread::spawn(move || {
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut buf = [0; 8192];
+            let n = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            log.lock().unwrap().push(request.lines().next().unwrap_or("").to_string());
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, received)
+}
+
+const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE_LONG: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 3600\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE_DATE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: Wed, 21 Oct 2015 07:28:00 GMT\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_GATEWAY: &str = "HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 49\r\nConnection: close\r\n\r\n{\"error\":{\"code\":\"notFound\",\"message\":\"Missing\"}}";
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 20\r\nConnection: close\r\n\r\n{\"author\":\"retried\"}";
+
+// init - initializes an instance with a fast retry policy
+fn init(url: String) -> JsonBank {
+    JsonBank::new(InitConfig {
+        host: Some(url),
+        keys: Some(Keys {
+            public: Some("public".to_string()),
+            private: Some("private".to_string()),
+        }),
+        retry: Some(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn retries_idempotent_reads() {
+    let (url, received) = serve(vec![UNAVAILABLE, BAD_GATEWAY, OK]);
+    let jsb = init(url);
+
+    let content: JsonObject = match jsb.get_own_content("sdk-test/index.json") {
+        Ok(content) => content,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(content["author"], "retried");
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let (url, received) = serve(vec![BAD_GATEWAY, BAD_GATEWAY, BAD_GATEWAY, OK]);
+    let jsb = init(url);
+
+    let err = match jsb.get_own_content::<JsonObject>("sdk-test/index.json") {
+        Ok(_) => panic!("Expected error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.status, Some(502));
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn gives_up_when_retry_after_exceeds_max_delay() {
+    let (url, received) = serve(vec![UNAVAILABLE_LONG, OK]);
+    let jsb = init(url);
+
+    let err = match jsb.get_own_content::<JsonObject>("sdk-test/index.json") {
+        Ok(_) => panic!("Expected error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.status, Some(503));
+    assert_eq!(received.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn retry_after_date_falls_back_to_backoff() {
+    let (url, received) = serve(vec![UNAVAILABLE_DATE, OK]);
+    let jsb = init(url);
+
+    let content: JsonObject = match jsb.get_own_content("sdk-test/index.json") {
+        Ok(content) => content,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    assert_eq!(content["author"], "retried");
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn does_not_retry_not_found() {
+    let (url, received) = serve(vec![NOT_FOUND, OK]);
+    let jsb = init(url);
+
+    assert!(!jsb.has_own_document("sdk-test/missing.json").unwrap());
+    assert_eq!(received.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn writes_are_retried_only_when_opted_in() {
+    let body = || CreateDocumentBody {
+        name: "retry.json".to_string(),
+        project: "sdk-test".to_string(),
+        content: "{}".to_string(),
+        folder: None,
+    };
+
+    // not retried by default
+    let (url, received) = serve(vec![UNAVAILABLE, OK]);
+    let jsb = init(url);
+    assert!(jsb.create_document(body()).is_err());
+    assert_eq!(received.lock().unwrap().len(), 1);
+
+    // retried when opted in
+    let (url, received) = serve(vec![UNAVAILABLE, OK]);
+    let jsb = init(url);
+    let _ = jsb.create_document_with(body(), RequestOptions { retry_writes: true, ..Default::default() });
+    assert_eq!(received.lock().unwrap().len(), 2);
+    assert!(received.lock().unwrap()[1].starts_with("POST /v1/project/sdk-test/document"));
+}
@@ -0,0 +1,49 @@
+use jsonbank::{JsonBank, InitConfig, Keys, JsonValue};
+use jsonbank::error::ErrorKind;
+use jsonbank::testing::{MockServer, MOCK_PUBLIC_KEY};
+
+#[test]
+fn rejects_invalid_keys() {
+    let server = MockServer::start();
+    server.add_document("private", "config.json", r#"{"secret": true}"#);
+
+    let jsb = JsonBank::new(InitConfig {
+        host: Some(server.url()),
+        keys: Some(Keys {
+            public: Some(MOCK_PUBLIC_KEY.to_string()),
+            private: Some("wrong".to_string()),
+        }),
+        ..Default::default()
+    });
+
+    // public key is valid, so reads work
+    let data: JsonValue = jsb.get_own_content("private/config.json").unwrap();
+    assert_eq!(data["secret"], true);
+
+    // private key is invalid
+    let err = match jsb.delete_document("private/config.json") {
+        Ok(_) => panic!("Expected unauthorized error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.kind, ErrorKind::Unauthorized);
+    assert_eq!(err.status, Some(401));
+    assert!(server.document_content("private/config.json").is_some());
+}
+
+#[test]
+fn private_projects_are_not_public() {
+    let server = MockServer::start();
+    server.add_document("private", "config.json", r#"{"secret": true}"#);
+
+    let mut jsb = JsonBank::new_without_config();
+    jsb.set_host(&server.url());
+
+    let err = match jsb.get_content::<JsonValue>("jsonbank/private/config.json") {
+        Ok(_) => panic!("Expected not found error"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.kind, ErrorKind::NotFound);
+    assert_eq!(err.code, "notFound");
+}
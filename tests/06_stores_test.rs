@@ -0,0 +1,223 @@
+mod functions;
+
+use std::fs;
+use jsonbank::{JsonBankApi, JsonValue};
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::{FileSystemStore, MemoryStore};
+use jsonbank::structs::{CreateDocumentBody, CreateFolderBody, ExpectedVersion};
+use functions::{mock_client, temp_dir};
+
+// run_scenario - runs the same document and folder operations against any backend
+fn run_scenario(api: &dyn JsonBankApi, project: &str) {
+    let (folder, existed) = api.create_folder_if_not_exists(CreateFolderBody {
+        name: "configs".to_string(),
+        project: project.to_string(),
+        folder: None,
+    }).unwrap();
+
+    assert!(!existed);
+    assert_eq!(folder.name, "configs");
+    assert_eq!(folder.path, "configs");
+
+    let body = CreateDocumentBody {
+        name: "app.json".to_string(),
+        project: project.to_string(),
+        content: r#"{"debug": false}"#.to_string(),
+        folder: Some("configs".to_string()),
+    };
+
+    let doc = api.create_document(body.clone()).unwrap();
+    assert_eq!(doc.path, "configs/app.json");
+    assert!(!doc.exists);
+
+    // creating it again fails, unless it is allowed to exist
+    let err = api.create_document(body.clone()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::AlreadyExists);
+
+    let existing = api.create_document_if_not_exists(body).unwrap();
+    assert!(existing.exists);
+    assert_eq!(existing.id, doc.id);
+
+    let path = format!("{}/configs/app", project);
+    assert!(api.has_own_document(&path).unwrap());
+
+    let meta = api.get_own_document_meta(&path).unwrap();
+    assert_eq!(meta.id, doc.id);
+    assert_eq!(meta.name, "app.json");
+
+    let data = api.get_own_content_value(&path).unwrap();
+    assert_eq!(data["debug"], false);
+
+    // same json with other formatting is not a change
+    let res = api.update_own_document(&path, r#"{ "debug" : false }"#.to_string()).unwrap();
+    assert!(!res.changed);
+
+    let res = api.update_own_document(&path, r#"{"debug": true}"#.to_string()).unwrap();
+    assert!(res.changed);
+    assert_eq!(api.get_own_content_value(&doc.id).unwrap()["debug"], true);
+
+    let stats = api.get_folder_with_stats(&format!("{}/configs", project)).unwrap().stats.unwrap();
+    assert_eq!(stats.documents, 1);
+    assert_eq!(stats.folders, 0);
+
+    assert!(api.delete_document(&path).unwrap().deleted);
+    assert!(!api.delete_document(&path).unwrap().deleted);
+    assert!(!api.has_own_document(&path).unwrap());
+
+    let err = api.get_own_content_as_string(&path).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn memory_store() {
+    let mut store = MemoryStore::new();
+    store.add_project("app", false);
+
+    run_scenario(&store, "app");
+
+    let auth = store.authenticate().unwrap();
+    assert_eq!(auth.username, "jsonbank");
+    assert_eq!(auth.api_key.projects, vec!["app"]);
+
+    // projects are not created implicitly
+    let err = store.create_folder(CreateFolderBody {
+        name: "configs".to_string(),
+        project: "missing".to_string(),
+        folder: None,
+    }).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn memory_store_versions() {
+    let store = MemoryStore::new();
+    store.add_project("app", false);
+    store.add_document("app", "config.json", r#"{"v": 0}"#).unwrap();
+
+    // updates within the same millisecond still get their own `updated_at`
+    let meta = store.get_own_document_meta("app/config.json").unwrap();
+    store.update_own_document("app/config.json", r#"{"v": 1}"#.to_string()).unwrap();
+    let updated = store.get_own_document_meta("app/config.json").unwrap();
+    assert!(updated.updated_at > meta.updated_at);
+
+    let err = store.update_own_document_after_check("app/config.json", r#"{"v": 2}"#.to_string(), &ExpectedVersion::from(&meta)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Conflict);
+    assert!(store.update_own_document_after_check("app/config.json", r#"{"v": 2}"#.to_string(), &ExpectedVersion::from(&updated)).unwrap().changed);
+}
+
+#[test]
+fn filesystem_store() {
+    let root = temp_dir("fs-store");
+    let mut store = FileSystemStore::new(&root);
+    store.add_project("app").unwrap();
+
+    run_scenario(&store, "app");
+
+    let auth = store.authenticate().unwrap();
+    assert_eq!(auth.api_key.projects, vec!["app"]);
+
+    // documents are plain files under the root
+    store.create_document(CreateDocumentBody {
+        name: "db.json".to_string(),
+        project: "app".to_string(),
+        content: r#"{"port": 5432}"#.to_string(),
+        folder: Some("configs".to_string()),
+    }).unwrap();
+
+    let content = fs::read_to_string(root.join("app/configs/db.json")).unwrap();
+    assert_eq!(content, r#"{"port": 5432}"#);
+
+    // the id of a folder can be used as parent
+    let folder = store.create_folder(CreateFolderBody {
+        name: "nested".to_string(),
+        project: "app".to_string(),
+        folder: Some("app/configs".to_string()),
+    }).unwrap();
+    assert_eq!(folder.id, "app/configs/nested");
+    assert!(root.join("app/configs/nested").is_dir());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn filesystem_store_versions() {
+    let root = temp_dir("fs-store-versions");
+    let store = FileSystemStore::new(&root);
+    store.add_project("app").unwrap();
+    fs::write(root.join("app/config.json"), r#"{"v": 0}"#).unwrap();
+
+    // updates right after each other still get their own `updated_at`
+    let meta = store.get_own_document_meta("app/config.json").unwrap();
+    store.update_own_document("app/config.json", r#"{"v": 1}"#.to_string()).unwrap();
+    let updated = store.get_own_document_meta("app/config.json").unwrap();
+    assert!(updated.updated_at > meta.updated_at);
+
+    let err = store.update_own_document_after_check("app/config.json", r#"{"v": 2}"#.to_string(), &ExpectedVersion::from(&meta)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Conflict);
+    assert!(store.update_own_document_after_check("app/config.json", r#"{"v": 2}"#.to_string(), &ExpectedVersion::from(&updated)).unwrap().changed);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn filesystem_store_lists_json_files() {
+    let root = temp_dir("fs-store-json");
+    let store = FileSystemStore::new(&root);
+    store.add_project("app").unwrap();
+    fs::create_dir(root.join("app/configs")).unwrap();
+    fs::write(root.join("app/index.json"), "{}").unwrap();
+    fs::write(root.join("app/notes.txt"), "not a document").unwrap();
+    fs::write(root.join("app/configs/db.json"), "{}").unwrap();
+    fs::write(root.join("app/configs/db.json.bak"), "{}").unwrap();
+
+    let mut names: Vec<String> = store.list_project_contents("app", Default::default()).unwrap()
+        .items.iter().map(|item| item.path().to_string()).collect();
+    names.sort();
+    assert_eq!(names, vec!["configs", "index.json"]);
+
+    let stats = store.get_folder_with_stats("app/configs").unwrap().stats.unwrap();
+    assert_eq!((stats.documents, stats.folders), (1, 0));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn filesystem_store_stays_in_root() {
+    let root = temp_dir("fs-store-root");
+    let store = FileSystemStore::new(root.join("data"));
+    store.add_project("app").unwrap();
+    fs::write(root.join("secret.json"), "{}").unwrap();
+
+    let err = store.get_own_content_as_string("app/../../secret.json").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+
+    let err = store.create_document(CreateDocumentBody {
+        name: "..".to_string(),
+        project: "app".to_string(),
+        content: "{}".to_string(),
+        folder: None,
+    }).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn json_bank_implements_api() {
+    let (_server, jsb) = mock_client("app");
+
+    run_scenario(&jsb, "app");
+}
+
+#[test]
+fn backends_can_be_boxed() {
+    let store = MemoryStore::new();
+    store.add_project("app", false);
+    store.add_document("app", "index.json", r#"{"name": "memory"}"#).unwrap();
+
+    let mut api: Box<dyn JsonBankApi> = Box::new(store);
+    assert!(api.authenticate().unwrap().authenticated);
+
+    let data: JsonValue = api.get_own_content_value("app/index").unwrap();
+    assert_eq!(data["name"], "memory");
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+use jsonbank::{InitConfig, JsonBank, JsonBankBuilder, RetryPolicy, ENV_HOST, ENV_PRIVATE_KEY, ENV_PUBLIC_KEY};
+use jsonbank::error::ErrorKind;
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+
+#[test]
+fn builder_configures_instance() {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+
+    let mut jsb = JsonBank::builder()
+        .host(&format!("{}/", server.url()))
+        .keys(MOCK_PUBLIC_KEY, MOCK_PRIVATE_KEY)
+        .timeout(Duration::from_secs(5))
+        .connect_timeout(Duration::from_secs(1))
+        .user_agent("jsonbank-tests/1.0")
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    // the trailing slash is removed
+    assert_eq!(jsb.config.host, server.url());
+
+    let auth = jsb.authenticate().unwrap();
+    assert_eq!(auth.api_key.projects, vec!["sdk-test"]);
+}
+
+#[test]
+fn builder_rejects_malformed_values() {
+    let err = JsonBank::builder().keys("public key", "private").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+    assert!(err.message.starts_with("Public key is malformed"));
+
+    let err = JsonBank::builder().private_key("").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+    assert_eq!(err.message, "Private key is empty");
+
+    let err = JsonBank::builder().host("api.jsonbank.io").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+
+    let err = JsonBank::builder().host("ftp://api.jsonbank.io").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+
+    let err = JsonBank::builder().proxy("not a url").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+
+    let err = JsonBank::builder().user_agent("bad\nagent").build().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+}
+
+#[test]
+fn new_returns_client_errors_from_requests() {
+    // `new` does not panic, every request returns the error instead
+    let jsb = JsonBank::new(InitConfig {
+        user_agent: Some("bad\nagent".to_string()),
+        ..Default::default()
+    });
+
+    let err = jsb.get_content_as_string("sdk-test/index.json").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Config);
+    assert_eq!(jsb.get_content_as_string("sdk-test/index.json").unwrap_err().kind, ErrorKind::Config);
+}
+
+// every env case is in one test, the environment is shared by all test threads
+#[test]
+fn from_env() {
+    std::env::remove_var(ENV_HOST);
+    std::env::remove_var(ENV_PUBLIC_KEY);
+    std::env::remove_var(ENV_PRIVATE_KEY);
+
+    // public key is required
+    let err = JsonBank::from_env().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::MissingKey);
+    assert_eq!(err.message, "JSB_PUBLIC_KEY is not set");
+
+    // empty is the same as not set
+    std::env::set_var(ENV_PUBLIC_KEY, "  ");
+    assert_eq!(JsonBank::from_env().err().unwrap().kind, ErrorKind::MissingKey);
+
+    std::env::set_var(ENV_PUBLIC_KEY, "jsb_pub\u{e9}");
+    let err = JsonBank::from_env().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+    assert!(err.message.starts_with("JSB_PUBLIC_KEY is malformed"));
+
+    std::env::set_var(ENV_PUBLIC_KEY, MOCK_PUBLIC_KEY);
+    std::env::set_var(ENV_HOST, "localhost:8080");
+    let err = JsonBank::from_env().err().unwrap();
+    assert_eq!(err.kind, ErrorKind::Config);
+    assert!(err.message.starts_with("JSB_HOST"));
+
+    // the private key is optional
+    let server = MockServer::start();
+    server.add_document("sdk-test", "index.json", r#"{"author": "jsonbank"}"#);
+    std::env::set_var(ENV_HOST, server.url());
+
+    let jsb = JsonBank::from_env().unwrap();
+    assert!(jsb.has_own_document("sdk-test/index").unwrap());
+    assert_eq!(jsb.delete_document("sdk-test/index").err().unwrap().kind, ErrorKind::MissingKey);
+
+    std::env::set_var(ENV_PRIVATE_KEY, MOCK_PRIVATE_KEY);
+    let jsb = JsonBankBuilder::from_env().unwrap().retry(RetryPolicy::none()).build().unwrap();
+    assert!(jsb.delete_document("sdk-test/index").unwrap().deleted);
+
+    std::env::remove_var(ENV_HOST);
+    std::env::remove_var(ENV_PUBLIC_KEY);
+    std::env::remove_var(ENV_PRIVATE_KEY);
+}
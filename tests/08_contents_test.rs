@@ -0,0 +1,166 @@
+mod functions;
+
+use std::fs;
+use std::time::Duration;
+use jsonbank::{JsonBankApi, ContentsIter};
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::{FileSystemStore, MemoryStore};
+use jsonbank::structs::{ContentItem, ListOptions, SortBy, SortOrder};
+use functions::{mock_client, temp_dir};
+
+// listing_store - a store with 5 documents and 2 folders in the root of `listing`
+fn listing_store() -> MemoryStore {
+    let store = MemoryStore::new();
+    store.add_project("listing", false);
+
+    for name in ["e.json", "a.json", "d.json", "b.json", "c.json"] {
+        store.add_document("listing", name, r#"{"n": 1}"#).unwrap();
+    }
+
+    store.add_document("listing", "folder-x/inner.json", "{}").unwrap();
+    store.add_folder("listing", "folder-y").unwrap();
+
+    store
+}
+
+fn names(items: &[ContentItem]) -> Vec<&str> {
+    items.iter().map(|item| item.name()).collect()
+}
+
+#[test]
+fn list_project_contents_paginates() {
+    let jsb = listing_store();
+
+    let options = ListOptions { per_page: 3, ..Default::default() };
+    let page = jsb.list_project_contents("listing", options.clone()).unwrap();
+
+    assert_eq!(names(&page.items), vec!["a.json", "b.json", "c.json"]);
+    assert_eq!(page.total, 7);
+    assert!(page.has_more());
+
+    let last = jsb.list_project_contents("listing", ListOptions { page: 3, ..options }).unwrap();
+    assert_eq!(names(&last.items), vec!["folder-y"]);
+    assert!(!last.has_more());
+
+    match &last.items[0] {
+        ContentItem::Folder(folder) => assert_eq!(folder.path, "folder-y"),
+        ContentItem::Document(_) => panic!("Expected a folder"),
+    }
+}
+
+#[test]
+fn list_sorted_by_updated_at() {
+    let jsb = listing_store();
+
+    // timestamps have millisecond precision
+    std::thread::sleep(Duration::from_millis(5));
+    jsb.update_own_document("listing/c.json", r#"{"n": 2}"#.to_string()).unwrap();
+
+    let page = jsb.list_project_contents("listing", ListOptions {
+        sort: SortBy::UpdatedAt,
+        order: SortOrder::Desc,
+        ..Default::default()
+    }).unwrap();
+
+    assert_eq!(page.items[0].name(), "c.json");
+
+    let page = jsb.list_project_contents("listing", ListOptions {
+        order: SortOrder::Desc,
+        ..Default::default()
+    }).unwrap();
+
+    assert_eq!(page.items[0].name(), "folder-y");
+}
+
+#[test]
+fn list_folder_contents_by_path_and_id() {
+    let jsb = listing_store();
+
+    let page = jsb.list_folder_contents("listing/folder-x", ListOptions::default()).unwrap();
+    assert_eq!(names(&page.items), vec!["inner.json"]);
+    assert_eq!(page.items[0].path(), "folder-x/inner.json");
+
+    let folder = jsb.get_folder("listing/folder-x").unwrap();
+    let page = jsb.list_folder_contents(&folder.id, ListOptions::default()).unwrap();
+    assert_eq!(page.total, 1);
+
+    let err = jsb.list_folder_contents("listing/missing", ListOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn iterator_follows_pages() {
+    let jsb = listing_store();
+
+    let mut iter = jsb.iter_project_contents("listing", ListOptions { per_page: 2, ..Default::default() });
+
+    // pages are only fetched when needed, so a document added before the first one is listed
+    jsb.add_document("listing", "0.json", "{}").unwrap();
+    assert_eq!(iter.next().unwrap().unwrap().name(), "0.json");
+
+    let rest: Vec<String> = iter.map(|item| item.unwrap().name().to_string()).collect();
+    assert_eq!(rest, vec!["a.json", "b.json", "c.json", "d.json", "e.json", "folder-x", "folder-y"]);
+}
+
+#[test]
+fn iterator_stops_after_error() {
+    let jsb = listing_store();
+
+    let api: &dyn JsonBankApi = &jsb;
+    let mut iter = ContentsIter::project(api, "missing", ListOptions::default());
+
+    assert_eq!(iter.next().unwrap().unwrap_err().kind, ErrorKind::NotFound);
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn invalid_options() {
+    let jsb = listing_store();
+
+    for options in [
+        ListOptions { page: 0, ..Default::default() },
+        ListOptions { per_page: 0, ..Default::default() },
+        ListOptions { per_page: 101, ..Default::default() },
+    ] {
+        let err = jsb.list_project_contents("listing", options).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Validation);
+    }
+}
+
+#[test]
+fn filesystem_store_lists_directories() {
+    let root = temp_dir("fs-contents");
+    let store = FileSystemStore::new(&root);
+    store.add_project("listing").unwrap();
+
+    fs::create_dir_all(root.join("listing/folder")).unwrap();
+    fs::write(root.join("listing/folder/b.json"), "{}").unwrap();
+    fs::write(root.join("listing/folder/a.json"), "{}").unwrap();
+    fs::write(root.join("listing/folder/.hidden"), "").unwrap();
+    fs::write(root.join("listing/index.json"), "{}").unwrap();
+
+    let page = store.list_project_contents("listing", ListOptions::default()).unwrap();
+    assert_eq!(names(&page.items), vec!["folder", "index.json"]);
+
+    let items: Vec<String> = store.iter_folder_contents("listing/folder", ListOptions { per_page: 1, ..Default::default() })
+        .map(|item| item.unwrap().id().to_string())
+        .collect();
+    assert_eq!(items, vec!["listing/folder/a.json", "listing/folder/b.json"]);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn http_listing_is_unsupported() {
+    let (server, jsb) = mock_client("listing");
+    server.add_document("listing", "a.json", "{}");
+
+    let err = jsb.list_project_contents("listing", ListOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Unsupported);
+
+    let err = jsb.iter_folder_contents("listing/folder", ListOptions::default()).next().unwrap().unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Unsupported);
+
+    // nothing was sent
+    assert_eq!(server.request_count(), 0);
+}
@@ -0,0 +1,100 @@
+mod functions;
+
+use std::fs;
+use jsonbank::JsonBankApi;
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::{FileSystemStore, MemoryStore};
+use jsonbank::structs::CreateFolderBody;
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_client, temp_dir};
+
+// ensure_path - runs the same mkdir -p checks against any backend
+fn ensure_path(api: &dyn JsonBankApi) {
+    let res = api.ensure_folder_path("folders/a/b/c").unwrap();
+    assert_eq!(res.folder.name, "c");
+    assert_eq!(res.folder.path, "a/b/c");
+    assert_eq!(res.folder.project, "folders");
+    assert_eq!(res.created, vec!["a", "a/b", "a/b/c"]);
+
+    // nothing left to create
+    let res = api.ensure_folder_path("/folders/a/b/c/").unwrap();
+    assert_eq!(res.folder.path, "a/b/c");
+    assert!(res.created.is_empty());
+
+    // only the new segment is created
+    let res = api.ensure_folder_path("folders/a/b/d").unwrap();
+    assert_eq!(res.created, vec!["a/b/d"]);
+
+    let stats = api.get_folder_with_stats("folders/a/b").unwrap().stats.unwrap();
+    assert_eq!(stats.folders, 2);
+
+    let err = api.ensure_folder_path("folders").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+}
+
+#[test]
+fn create_folder_in_parent_folder() {
+    let (server, jsb) = mock_client("folders");
+    server.add_folder("folders", "parent");
+
+    let folder = jsb.create_folder(CreateFolderBody {
+        name: "child".to_string(),
+        project: "folders".to_string(),
+        folder: Some("parent".to_string()),
+    }).unwrap();
+
+    assert_eq!(folder.path, "parent/child");
+    assert!(jsb.get_folder("folders/parent/child").is_ok());
+}
+
+#[test]
+fn create_folder_in_missing_parent_folder() {
+    let (_server, jsb) = mock_client("folders");
+
+    let err = jsb.create_folder(CreateFolderBody {
+        name: "child".to_string(),
+        project: "folders".to_string(),
+        folder: Some("missing".to_string()),
+    }).unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn ensure_folder_path() {
+    let (_server, jsb) = mock_client("folders");
+    ensure_path(&jsb);
+}
+
+#[test]
+fn ensure_folder_path_memory_store() {
+    let store = MemoryStore::new();
+    store.add_project("folders", false);
+    ensure_path(&store);
+}
+
+#[test]
+fn ensure_folder_path_filesystem_store() {
+    let root = temp_dir("ensure_folder_path");
+    let store = FileSystemStore::new(root.clone());
+    store.add_project("folders").unwrap();
+
+    ensure_path(&store);
+    assert!(root.join("folders/a/b/c").is_dir());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn async_ensure_folder_path() {
+    let server = MockServer::start();
+    server.add_folder("folders", "a");
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let res = jsb.ensure_folder_path("folders/a/b").await.unwrap();
+    assert_eq!(res.folder.path, "a/b");
+    assert_eq!(res.created, vec!["a/b"]);
+}
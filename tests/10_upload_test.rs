@@ -0,0 +1,129 @@
+mod functions;
+
+use std::fs;
+use std::io::Cursor;
+use jsonbank::JsonBankApi;
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::MemoryStore;
+use jsonbank::structs::UploadDocumentBody;
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_client, temp_dir};
+
+fn body(file_path: &str, name: Option<&str>) -> UploadDocumentBody {
+    UploadDocumentBody {
+        file_path: file_path.to_string(),
+        project: "uploads".to_string(),
+        name: name.map(|name| name.to_string()),
+        folder: None,
+    }
+}
+
+#[test]
+fn upload_document_from_absolute_path() {
+    let (server, jsb) = mock_client("uploads");
+
+    let dir = temp_dir("upload_absolute");
+    let file = dir.join("absolute.json");
+    fs::write(&file, r#"{"absolute": true}"#).unwrap();
+
+    let res = jsb.upload_document(body(file.to_str().unwrap(), None)).unwrap();
+    assert_eq!(res.name, "absolute.json");
+    assert_eq!(server.document_content("uploads/absolute.json").unwrap(), r#"{"absolute": true}"#);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn upload_document_missing_file() {
+    let (_server, jsb) = mock_client("uploads");
+
+    let err = jsb.upload_document(body("tests/missing.json", None)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Io);
+    assert_eq!(err.code, "file_not_found");
+}
+
+#[test]
+fn upload_from_reader() {
+    let (server, jsb) = mock_client("uploads");
+
+    let file = fs::File::open("tests/upload.json").unwrap();
+    let res = jsb.upload_from_reader(file, body("", Some("from-reader.json"))).unwrap();
+    assert_eq!(res.name, "from-reader.json");
+
+    let expected = fs::read_to_string("tests/upload.json").unwrap();
+    assert_eq!(server.document_content("uploads/from-reader.json").unwrap(), expected);
+
+    // named after the file path
+    let res = jsb.upload_from_reader(Cursor::new("[1, 2]"), body("some/dir/list.json", None)).unwrap();
+    assert_eq!(res.name, "list.json");
+}
+
+#[test]
+fn upload_bytes() {
+    let (server, jsb) = mock_client("uploads");
+
+    let res = jsb.upload_bytes(br#"{"bytes": 1}"#, body("bytes.json", None)).unwrap();
+    assert_eq!(res.name, "bytes.json");
+    assert_eq!(server.document_content("uploads/bytes.json").unwrap(), r#"{"bytes": 1}"#);
+}
+
+#[test]
+fn upload_invalid_content() {
+    let (server, jsb) = mock_client("uploads");
+
+    let err = jsb.upload_bytes(b"not json", body("bad.json", None)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+
+    let err = jsb.upload_bytes(&[0xff, 0xfe], body("binary.json", None)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+
+    let err = jsb.upload_from_reader(Cursor::new(vec![0xff, 0xfe]), body("binary.json", None)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+
+    // no name and no file name
+    let err = jsb.upload_bytes(b"{}", body("", None)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+
+    // nothing was sent
+    assert_eq!(server.request_count(), 0);
+}
+
+#[test]
+fn upload_bytes_memory_store() {
+    let store = MemoryStore::new();
+    store.add_project("uploads", false);
+
+    let api: &dyn JsonBankApi = &store;
+    api.upload_bytes(b"{}", body("empty.json", None)).unwrap();
+    assert_eq!(store.document_content("uploads/empty.json").unwrap(), "{}");
+}
+
+#[tokio::test]
+async fn async_upload_bytes() {
+    let server = MockServer::start();
+    server.add_project("uploads", false);
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let res = jsb.upload_bytes(b"{}", body("async.json", None)).await.unwrap();
+    assert_eq!(res.name, "async.json");
+
+    // files and readers are read without blocking the runtime
+    let res = jsb.upload_document(body("tests/upload.json", None)).await.unwrap();
+    assert_eq!(res.name, "upload.json");
+    let expected = fs::read_to_string("tests/upload.json").unwrap();
+    assert_eq!(server.document_content("uploads/upload.json").unwrap(), expected);
+
+    let err = jsb.upload_document(body("tests/missing.json", None)).await.unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Io);
+    assert_eq!(err.code, "file_not_found");
+
+    let file = tokio::fs::File::open("tests/upload.json").await.unwrap();
+    let res = jsb.upload_from_reader(file, body("", Some("from-reader.json"))).await.unwrap();
+    assert_eq!(res.name, "from-reader.json");
+
+    let err = jsb.upload_from_reader(&b"{"[..], body("", Some("broken.json"))).await.unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+}
@@ -0,0 +1,188 @@
+mod functions;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use jsonbank::{CacheConfig, JsonBankApi};
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::{FileSystemStore, MemoryStore};
+use jsonbank::structs::{CreateDocumentBody, SyncOptions};
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_client, temp_dir};
+
+// local_tree - writes the local side of every scenario
+fn local_tree(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    fs::create_dir_all(dir.join("configs/prod")).unwrap();
+    fs::create_dir_all(dir.join("empty")).unwrap();
+    fs::create_dir_all(dir.join(".git")).unwrap();
+
+    fs::write(dir.join("root.json"), r#"{"root": true}"#).unwrap();
+    fs::write(dir.join("same.json"), "{\n  \"a\": 1\n}").unwrap();
+    fs::write(dir.join("configs/app.json"), r#"{"debug": false}"#).unwrap();
+    fs::write(dir.join("configs/prod/db.json"), r#"{"host": "db"}"#).unwrap();
+    fs::write(dir.join(".hidden.json"), "{}").unwrap();
+    fs::write(dir.join(".git/config.json"), "{}").unwrap();
+    fs::write(dir.join("notes.txt"), "not synced").unwrap();
+
+    dir
+}
+
+// add_document - creates a remote document, `folder` must exist
+fn add_document(api: &dyn JsonBankApi, folder: Option<&str>, name: &str, content: &str) {
+    api.create_document(CreateDocumentBody {
+        name: name.to_string(),
+        project: "sync".to_string(),
+        content: content.to_string(),
+        folder: folder.map(|folder| folder.to_string()),
+    }).unwrap();
+}
+
+// run_scenario - the remote project `sync` must exist and be empty
+fn run_scenario(api: &dyn JsonBankApi, local: &Path) {
+    api.ensure_folder_path("sync/configs").unwrap();
+    api.ensure_folder_path("sync/legacy/v1").unwrap();
+    add_document(api, None, "same.json", r#"{"a":1}"#);
+    add_document(api, None, "old.json", "{}");
+    add_document(api, Some("configs"), "app.json", r#"{"debug": true}"#);
+    add_document(api, Some("configs"), "old.json", "{}");
+
+    // dry run
+    let plan = api.sync_directory(local, "sync", SyncOptions { delete: true, dry_run: true }).unwrap();
+    assert!(plan.dry_run);
+    assert_eq!(plan.created_folders, vec!["configs/prod", "empty"]);
+    assert_eq!(plan.created, vec!["configs/prod/db.json", "root.json"]);
+    assert_eq!(plan.updated, vec!["configs/app.json"]);
+    assert_eq!(plan.unchanged, vec!["same.json"]);
+    assert_eq!(plan.deleted, vec!["configs/old.json", "old.json"]);
+    assert_eq!(plan.orphaned_folders, vec!["legacy", "legacy/v1"]);
+    assert!(plan.to_string().contains("create folder configs/prod"));
+    assert!(plan.to_string().contains("keep folder legacy/v1"));
+
+    // nothing changed
+    assert!(!api.has_own_document("sync/root.json").unwrap());
+    assert!(api.get_folder("sync/empty").is_err());
+    assert_eq!(api.get_own_content_value("sync/configs/app.json").unwrap()["debug"], true);
+
+    // without delete
+    let report = api.sync_directory(local, "sync", SyncOptions::default()).unwrap();
+    assert_eq!(report.created_folders, plan.created_folders);
+    assert_eq!(report.created, plan.created);
+    assert_eq!(report.updated, plan.updated);
+    assert!(report.deleted.is_empty() && report.orphaned_folders.is_empty());
+
+    assert_eq!(api.get_own_content_value("sync/configs/prod/db.json").unwrap()["host"], "db");
+    assert_eq!(api.get_own_content_value("sync/configs/app.json").unwrap()["debug"], false);
+    assert!(api.get_folder("sync/empty").is_ok());
+    assert!(api.has_own_document("sync/old.json").unwrap());
+    assert!(!api.has_own_document("sync/.hidden.json").unwrap());
+
+    // with delete
+    let report = api.sync_directory(local, "sync", SyncOptions { delete: true, dry_run: false }).unwrap();
+    assert_eq!(report.deleted, vec!["configs/old.json", "old.json"]);
+    assert_eq!(report.orphaned_folders, vec!["legacy", "legacy/v1"]);
+    assert!(report.created.is_empty() && report.updated.is_empty());
+    assert!(api.get_folder("sync/legacy/v1").is_ok());
+    assert!(!api.has_own_document("sync/old.json").unwrap());
+    assert!(!api.has_own_document("sync/configs/old.json").unwrap());
+
+    // in sync
+    let report = api.sync_directory(local, "sync", SyncOptions { delete: true, dry_run: false }).unwrap();
+    assert!(report.is_empty());
+    assert_eq!(report.unchanged.len(), 4);
+}
+
+#[test]
+fn sync_directory_memory_store() {
+    let local = local_tree("sync_memory");
+    let store = MemoryStore::new();
+    store.add_project("sync", false);
+
+    run_scenario(&store, &local);
+    fs::remove_dir_all(local).unwrap();
+}
+
+#[test]
+fn sync_directory_filesystem_store() {
+    let local = local_tree("sync_fs_local");
+    let root = temp_dir("sync_fs_remote");
+    let store = FileSystemStore::new(root.clone());
+    store.add_project("sync").unwrap();
+
+    run_scenario(&store, &local);
+    assert!(root.join("sync/configs/prod/db.json").is_file());
+
+    fs::remove_dir_all(local).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn sync_directory_http() {
+    let local = local_tree("sync_http");
+    let (_server, jsb) = mock_client("sync");
+    jsb.ensure_folder_path("sync/configs").unwrap();
+    add_document(&jsb, None, "same.json", r#"{"a":1}"#);
+    add_document(&jsb, Some("configs"), "app.json", r#"{"debug": true}"#);
+
+    let report = jsb.sync_directory(&local, "sync", SyncOptions::default()).unwrap();
+    assert_eq!(report.created_folders, vec!["configs/prod", "empty"]);
+    assert_eq!(report.created, vec!["configs/prod/db.json", "root.json"]);
+    assert_eq!(report.updated, vec!["configs/app.json"]);
+    assert_eq!(report.unchanged, vec!["same.json"]);
+    assert_eq!(jsb.get_own_content_value("sync/configs/app.json").unwrap()["debug"], false);
+
+    // deleting needs a listing of the project, it fails before anything is changed
+    fs::write(local.join("new.json"), "{}").unwrap();
+    let err = jsb.sync_directory(&local, "sync", SyncOptions { delete: true, dry_run: false }).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Unsupported);
+    assert!(!jsb.has_own_document("sync/new.json").unwrap());
+
+    fs::remove_dir_all(local).unwrap();
+}
+
+#[test]
+fn sync_directory_compares_with_server_not_cache() {
+    let local = temp_dir("sync_cache");
+    fs::write(local.join("a.json"), r#"{"a": 2}"#).unwrap();
+
+    let server = MockServer::start();
+    server.add_document("sync", "a.json", r#"{"a": 1}"#);
+
+    let jsb = mock_builder(&server)
+        .cache(CacheConfig::default())
+        .build()
+        .unwrap();
+
+    // cache the content, then change it with another client
+    jsb.get_own_content_as_string("sync/a.json").unwrap();
+    server.store().update_own_document("sync/a.json", r#"{"a": 2}"#.to_string()).unwrap();
+
+    let report = jsb.sync_directory(&local, "sync", SyncOptions::default()).unwrap();
+    assert_eq!(report.unchanged, vec!["a.json"]);
+    assert!(report.updated.is_empty());
+
+    // the cache holds the content read by the sync
+    assert_eq!(jsb.get_own_content_as_string("sync/a.json").unwrap(), r#"{"a": 2}"#);
+
+    fs::remove_dir_all(local).unwrap();
+}
+
+#[test]
+fn sync_directory_invalid_file() {
+    let local = local_tree("sync_invalid");
+    fs::write(local.join("configs/broken.json"), "{").unwrap();
+
+    let store = MemoryStore::new();
+    store.add_project("sync", false);
+
+    let err = store.sync_directory(&local, "sync", SyncOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidJson);
+    assert!(err.message.contains("configs/broken.json"));
+
+    // nothing was created
+    assert!(store.get_folder("sync/configs").is_err());
+
+    let err = store.sync_directory(&local.join("missing"), "sync", SyncOptions::default()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Io);
+
+    fs::remove_dir_all(local).unwrap();
+}
@@ -0,0 +1,151 @@
+mod functions;
+
+use std::fs;
+use std::thread::sleep;
+use std::time::Duration;
+use jsonbank::{JsonBankApi, MIRROR_MANIFEST};
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::MemoryStore;
+use jsonbank::structs::{MirrorManifest, SyncOptions};
+use functions::{mock_client, temp_dir};
+
+// mirror_store - a project with two root documents, a nested document and an empty folder
+fn mirror_store() -> MemoryStore {
+    let store = MemoryStore::new();
+    store.add_project("mirror", false);
+    store.add_document("mirror", "index.json", r#"{"index": true}"#).unwrap();
+    store.add_document("mirror", "old.json", "{}").unwrap();
+    store.add_document("mirror", "configs/prod/db.json", r#"{"host": "db"}"#).unwrap();
+    store.add_folder("mirror", "empty").unwrap();
+
+    store
+}
+
+#[test]
+fn mirror_project() {
+    let jsb = mirror_store();
+    let dest = temp_dir("mirror_project");
+
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert_eq!(report.downloaded, vec!["configs/prod/db.json", "index.json", "old.json"]);
+    assert!(report.unchanged.is_empty());
+
+    assert_eq!(fs::read_to_string(dest.join("configs/prod/db.json")).unwrap(), r#"{"host": "db"}"#);
+    assert!(dest.join("empty").is_dir());
+
+    let manifest: MirrorManifest = serde_json::from_str(&fs::read_to_string(dest.join(MIRROR_MANIFEST)).unwrap()).unwrap();
+    assert_eq!(manifest.project, "mirror");
+    let entry = &manifest.documents["index.json"];
+    assert_eq!(entry.id, jsb.get_own_document_meta("mirror/index.json").unwrap().id);
+    assert_eq!(entry.size, 15);
+
+    // only the updated document is downloaded again
+    sleep(Duration::from_millis(10));
+    jsb.update_own_document("mirror/index.json", r#"{"index": false}"#.to_string()).unwrap();
+    jsb.delete_document("mirror/old.json").unwrap();
+    fs::write(dest.join("local.json"), "{}").unwrap();
+
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert_eq!(report.downloaded, vec!["index.json"]);
+    assert_eq!(report.unchanged, vec!["configs/prod/db.json"]);
+    assert_eq!(report.removed, vec!["old.json"]);
+
+    assert_eq!(fs::read_to_string(dest.join("index.json")).unwrap(), r#"{"index": false}"#);
+    assert!(!dest.join("old.json").exists());
+    // files the mirror did not write are kept
+    assert!(dest.join("local.json").exists());
+
+    fs::remove_dir_all(dest).unwrap();
+}
+
+#[test]
+fn mirror_project_missing_file_is_downloaded() {
+    let jsb = mirror_store();
+    let dest = temp_dir("mirror_missing_file");
+
+    jsb.mirror_project("mirror", &dest).unwrap();
+    fs::remove_file(dest.join("index.json")).unwrap();
+
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert_eq!(report.downloaded, vec!["index.json"]);
+    assert!(dest.join("index.json").is_file());
+
+    fs::remove_dir_all(dest).unwrap();
+}
+
+#[test]
+fn mirror_project_removes_empty_directories() {
+    let jsb = mirror_store();
+    jsb.add_document("mirror", "configs/dev/db.json", "{}").unwrap();
+    jsb.add_folder("mirror", "legacy/v1").unwrap();
+    let dest = temp_dir("mirror_empty_dirs");
+
+    jsb.mirror_project("mirror", &dest).unwrap();
+    assert!(dest.join("configs/dev/db.json").is_file() && dest.join("legacy/v1").is_dir());
+
+    // the stores cannot delete folders, the same project without them is mirrored instead
+    let jsb = mirror_store();
+    fs::create_dir_all(dest.join("configs/local")).unwrap();
+    fs::write(dest.join("configs/local/app.json"), "{}").unwrap();
+
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert_eq!(report.removed, vec!["configs/dev/db.json"]);
+
+    assert!(!dest.join("configs/dev").exists() && !dest.join("legacy").exists());
+    // remote folders and directories with files the mirror did not write are kept
+    assert!(dest.join("configs/prod/db.json").is_file() && dest.join("empty").is_dir());
+    assert!(dest.join("configs/local/app.json").is_file());
+
+    fs::remove_dir_all(dest).unwrap();
+}
+
+#[test]
+fn mirror_project_skips_the_manifest_name() {
+    let jsb = mirror_store();
+    jsb.add_document("mirror", MIRROR_MANIFEST, r#"{"project": "other"}"#).unwrap();
+    let dest = temp_dir("mirror_manifest_name");
+
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert_eq!(report.skipped, vec![MIRROR_MANIFEST]);
+    assert!(!report.downloaded.contains(&MIRROR_MANIFEST.to_string()));
+
+    let manifest: MirrorManifest = serde_json::from_str(&fs::read_to_string(dest.join(MIRROR_MANIFEST)).unwrap()).unwrap();
+    assert_eq!(manifest.project, "mirror");
+
+    // the next run still finds its own manifest
+    let report = jsb.mirror_project("mirror", &dest).unwrap();
+    assert!(report.downloaded.is_empty());
+
+    fs::remove_dir_all(dest).unwrap();
+}
+
+#[test]
+fn http_mirror_is_unsupported() {
+    let (server, jsb) = mock_client("mirror");
+    server.add_document("mirror", "index.json", "{}");
+    let dest = temp_dir("mirror_http");
+
+    let err = jsb.mirror_project("mirror", &dest).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Unsupported);
+    assert!(!dest.join(MIRROR_MANIFEST).exists() && !dest.join("index.json").exists());
+
+    fs::remove_dir_all(dest).unwrap();
+}
+
+#[test]
+fn mirror_then_sync_back() {
+    let store = MemoryStore::new();
+    store.add_project("source", false);
+    store.add_project("copy", false);
+    store.add_document("source", "configs/app.json", r#"{"debug": true}"#).unwrap();
+
+    let dest = temp_dir("mirror_sync_back");
+    store.mirror_project("source", &dest).unwrap();
+
+    // the manifest is hidden, so it is not synced
+    let report = store.sync_directory(&dest, "copy", SyncOptions::default()).unwrap();
+    assert_eq!(report.created, vec!["configs/app.json"]);
+    assert_eq!(store.get_own_content_value("copy/configs/app.json").unwrap()["debug"], true);
+
+    fs::remove_dir_all(dest).unwrap();
+}
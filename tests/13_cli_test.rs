@@ -0,0 +1,228 @@
+mod functions;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use jsonbank::JsonValue;
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+use functions::temp_dir;
+
+// jsonbank - the cli without any key from the environment or a profile
+fn jsonbank(home: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_jsonbank"));
+    cmd.env_remove("JSB_HOST")
+        .env_remove("JSB_PUBLIC_KEY")
+        .env_remove("JSB_PRIVATE_KEY")
+        .env_remove("JSB_PROFILE")
+        .env_remove("JSB_PROFILE_FILE")
+        .env("HOME", home)
+        .env("USERPROFILE", home);
+    cmd
+}
+
+// cli - the cli with the host and keys of the server as flags
+fn cli(server: &MockServer, home: &Path, args: &[&str]) -> Output {
+    jsonbank(home)
+        .args(["--host", &server.url(), "--public-key", MOCK_PUBLIC_KEY, "--private-key", MOCK_PRIVATE_KEY])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn json(output: &Output) -> JsonValue {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn read_commands() {
+    let home = temp_dir("cli_read");
+    let server = MockServer::start();
+    server.add_project("public-project", true);
+    server.add_document("public-project", "index.json", r#"{"public": true}"#);
+    server.add_document("cli", "configs/app.json", r#"{"debug": true}"#);
+
+    let out = cli(&server, &home, &["get", "cli/configs/app.json"]);
+    assert!(out.status.success());
+    assert_eq!(stdout(&out), "{\n  \"debug\": true\n}\n");
+
+    let out = cli(&server, &home, &["--json", "get", "cli/configs/app.json"]);
+    assert_eq!(json(&out)["debug"], true);
+
+    // public documents need no keys
+    let out = jsonbank(&home).args(["--host", &server.url(), "--json", "get", "--public", "jsonbank/public-project/index.json"]).output().unwrap();
+    assert_eq!(json(&out)["public"], true);
+
+    let out = cli(&server, &home, &["get", "cli/configs/app.json", "--pointer", "/debug"]);
+    assert_eq!(stdout(&out), "true\n");
+
+    let out = cli(&server, &home, &["get", "cli/configs/app.json", "--pointer", "/missing"]);
+    assert_eq!(out.status.code(), Some(3));
+
+    let out = cli(&server, &home, &["--json", "meta", "cli/configs/app.json"]);
+    assert_eq!(json(&out)["path"], "configs/app.json");
+
+    let out = cli(&server, &home, &["meta", "cli/configs/app.json"]);
+    assert!(stdout(&out).contains("path: configs/app.json"));
+
+    let out = cli(&server, &home, &["folder", "--stats", "cli/configs"]);
+    assert!(stdout(&out).contains("documents: 1"));
+
+    let out = cli(&server, &home, &["--json", "whoami"]);
+    assert_eq!(json(&out)["username"], "jsonbank");
+
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn write_commands() {
+    let home = temp_dir("cli_write");
+    let server = MockServer::start();
+    server.add_project("cli", false);
+
+    // content from stdin
+    let mut child = jsonbank(&home)
+        .args(["--host", &server.url(), "--public-key", MOCK_PUBLIC_KEY, "--private-key", MOCK_PRIVATE_KEY])
+        .args(["create", "cli/stdin.json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(br#"{"stdin": true}"#).unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success());
+    assert!(stdout(&out).starts_with("created cli/stdin.json"));
+    assert_eq!(server.document_content("cli/stdin.json").unwrap(), r#"{"stdin": true}"#);
+
+    let out = cli(&server, &home, &["--json", "mkdir", "-p", "cli/a/b"]);
+    assert_eq!(json(&out)["created"], serde_json::json!(["a", "a/b"]));
+
+    let out = cli(&server, &home, &["mkdir", "cli/a/b/c"]);
+    assert_eq!(stdout(&out), "created cli/a/b/c\n");
+
+    let out = cli(&server, &home, &["create", "cli/a/b/doc.json", "--content", "{}"]);
+    assert!(out.status.success());
+
+    let out = cli(&server, &home, &["update", "cli/a/b/doc.json", "--content", r#"{"v": 2}"#]);
+    assert_eq!(stdout(&out), "updated cli/a/b/doc.json\n");
+
+    let out = cli(&server, &home, &["update", "cli/a/b/doc.json", "--content", r#"{"v": 2}"#]);
+    assert_eq!(stdout(&out), "unchanged cli/a/b/doc.json\n");
+
+    let out = cli(&server, &home, &["--json", "upload", "tests/upload.json", "cli", "--folder", "a"]);
+    assert_eq!(json(&out)["path"], "a/upload.json");
+
+    let out = cli(&server, &home, &["delete", "cli/a/upload.json"]);
+    assert_eq!(stdout(&out), "deleted cli/a/upload.json\n");
+
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn sync() {
+    let home = temp_dir("cli_sync");
+    let server = MockServer::start();
+    server.add_project("cli", false);
+
+    let local = home.join("local");
+    fs::create_dir_all(local.join("configs")).unwrap();
+    fs::write(local.join("configs/app.json"), "{}").unwrap();
+    let local = local.to_str().unwrap();
+
+    let out = cli(&server, &home, &["sync", "--dry-run", local, "cli"]);
+    assert!(stdout(&out).contains("create configs/app.json"));
+    assert!(server.document_content("cli/configs/app.json").is_none());
+
+    let out = cli(&server, &home, &["--json", "sync", local, "cli"]);
+    assert_eq!(json(&out)["created"], serde_json::json!(["configs/app.json"]));
+    assert_eq!(server.document_content("cli/configs/app.json").unwrap(), "{}");
+
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn keys_from_env_and_profile() {
+    let home = temp_dir("cli_keys");
+    let server = MockServer::start();
+    server.add_document("cli", "index.json", "{}");
+
+    // environment
+    let out = jsonbank(&home)
+        .env("JSB_HOST", server.url())
+        .env("JSB_PUBLIC_KEY", MOCK_PUBLIC_KEY)
+        .args(["get", "cli/index.json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    // default profile of the default profile file
+    fs::create_dir_all(home.join(".jsonbank")).unwrap();
+    let profiles = serde_json::json!({
+        "default": { "host": server.url(), "publicKey": MOCK_PUBLIC_KEY },
+        "broken": { "host": server.url(), "publicKey": "wrong" },
+    });
+    fs::write(home.join(".jsonbank/profiles.json"), profiles.to_string()).unwrap();
+
+    let out = jsonbank(&home).args(["get", "cli/index.json"]).output().unwrap();
+    assert!(out.status.success());
+
+    // named profile
+    let out = jsonbank(&home).args(["--profile", "broken", "get", "cli/index.json"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(5));
+
+    // flags win over the profile
+    let out = jsonbank(&home)
+        .args(["--profile", "broken", "--public-key", MOCK_PUBLIC_KEY, "get", "cli/index.json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let out = jsonbank(&home).args(["--profile", "missing", "get", "cli/index.json"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(7));
+
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn exit_codes() {
+    let home = temp_dir("cli_exit_codes");
+    let server = MockServer::start();
+    server.add_document("cli", "index.json", "{}");
+
+    let out = cli(&server, &home, &["--json", "get", "cli/missing.json"]);
+    assert_eq!(out.status.code(), Some(3));
+    let err: JsonValue = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["kind"], "NotFound");
+
+    let out = cli(&server, &home, &["delete", "cli/missing.json"]);
+    assert_eq!(out.status.code(), Some(3));
+
+    let out = cli(&server, &home, &["create", "cli/index.json", "--content", "{}"]);
+    assert_eq!(out.status.code(), Some(4));
+
+    let out = cli(&server, &home, &["update", "cli/index.json", "--content", "{"]);
+    assert_eq!(out.status.code(), Some(6));
+
+    let out = cli(&server, &home, &["upload", "tests/missing.json", "cli"]);
+    assert_eq!(out.status.code(), Some(8));
+
+    // no keys
+    let out = jsonbank(&home).args(["--host", &server.url(), "get", "cli/index.json"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(5));
+
+    let out = jsonbank(&home).args(["get"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+
+    // listing a project is not supported by the api, so there is no pull and no sync --delete
+    let out = jsonbank(&home).args(["pull", "cli", "."]).output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let out = jsonbank(&home).args(["sync", "--delete", ".", "cli"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+
+    fs::remove_dir_all(home).unwrap();
+}
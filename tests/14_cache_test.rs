@@ -0,0 +1,249 @@
+mod functions;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{self, sleep};
+use std::time::Duration;
+use jsonbank::{CacheConfig, JsonBankApi, JsonValue};
+use jsonbank::error::JsbError;
+use jsonbank::middleware::{ApiRequest, ApiResponse, Middleware};
+use jsonbank::testing::MockServer;
+use functions::{add_public_index, mock_builder, mock_cached_client};
+
+// add_letters - adds cache/a.json, cache/b.json and cache/c.json, 13 bytes each
+fn add_letters(server: &MockServer) {
+    for name in ["a", "b", "c"] {
+        server.add_document("cache", &format!("{}.json", name), &format!(r#"{{"name": "{}"}}"#, name));
+    }
+}
+
+#[test]
+fn reads_are_cached() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig::default());
+    add_public_index(&server);
+    add_letters(&server);
+
+    let first: JsonValue = jsb.get_own_content("cache/a.json").unwrap();
+    let second = jsb.get_own_content_as_string("/cache/a.json").unwrap();
+    assert_eq!(first["name"], "a");
+    assert_eq!(second, r#"{"name": "a"}"#);
+    assert_eq!(server.request_count(), 1);
+
+    let public: JsonValue = jsb.get_content("jsonbank/public-project/index.json").unwrap();
+    assert_eq!(public["public"], true);
+    jsb.get_content_as_string("jsonbank/public-project/index.json").unwrap();
+    assert_eq!(server.request_count(), 2);
+    assert_eq!(jsb.cache().unwrap().len(), 2);
+
+    // errors are not cached
+    assert!(jsb.get_own_content_as_string("cache/missing.json").is_err());
+    assert!(jsb.get_own_content_as_string("cache/missing.json").is_err());
+    assert_eq!(server.request_count(), 4);
+}
+
+#[test]
+fn entries_expire() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig {
+        ttl: Duration::from_millis(100),
+        document_ttls: HashMap::from([
+            ("cache/b.json".to_string(), Duration::ZERO),
+            ("cache/c.json".to_string(), Duration::from_secs(60)),
+        ]),
+        ..Default::default()
+    });
+    add_letters(&server);
+
+    for path in ["cache/a.json", "cache/b.json", "cache/c.json"] {
+        jsb.get_own_content_as_string(path).unwrap();
+        jsb.get_own_content_as_string(path).unwrap();
+    }
+    // b is never cached
+    assert_eq!(server.request_count(), 4);
+
+    sleep(Duration::from_millis(150));
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    jsb.get_own_content_as_string("cache/c.json").unwrap();
+    assert_eq!(server.request_count(), 5);
+}
+
+#[test]
+fn least_recently_used_is_evicted() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig {
+        max_entries: Some(2),
+        ..Default::default()
+    });
+    add_letters(&server);
+
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    jsb.get_own_content_as_string("cache/b.json").unwrap();
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    // evicts b
+    jsb.get_own_content_as_string("cache/c.json").unwrap();
+    assert_eq!(server.request_count(), 3);
+
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    assert_eq!(server.request_count(), 3);
+    jsb.get_own_content_as_string("cache/b.json").unwrap();
+    assert_eq!(server.request_count(), 4);
+    assert_eq!(jsb.cache().unwrap().len(), 2);
+}
+
+#[test]
+fn byte_budget() {
+    // each document is 13 bytes
+    let (server, jsb) = mock_cached_client("cache", CacheConfig {
+        max_entries: None,
+        max_bytes: Some(30),
+        ..Default::default()
+    });
+    add_letters(&server);
+
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    jsb.get_own_content_as_string("cache/b.json").unwrap();
+    jsb.get_own_content_as_string("cache/c.json").unwrap();
+    assert_eq!(jsb.cache().unwrap().len(), 2);
+
+    // a was evicted
+    jsb.get_own_content_as_string("cache/c.json").unwrap();
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    assert_eq!(server.request_count(), 4);
+}
+
+#[test]
+fn writes_evict() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig::default());
+    add_letters(&server);
+
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    jsb.update_own_document("cache/a.json", r#"{"name": "A"}"#.to_string()).unwrap();
+    assert_eq!(jsb.get_own_content_as_string("cache/a.json").unwrap(), r#"{"name": "A"}"#);
+
+    jsb.get_own_content_as_string("cache/b.json").unwrap();
+    jsb.delete_document("cache/b.json").unwrap();
+    assert!(jsb.get_own_content_as_string("cache/b.json").is_err());
+
+    // changed by someone else
+    jsb.get_own_content_as_string("cache/c.json").unwrap();
+    server.store().update_own_document("cache/c.json", "{}".to_string()).unwrap();
+    assert_eq!(jsb.get_own_content_as_string("cache/c.json").unwrap(), r#"{"name": "c"}"#);
+    jsb.cache().unwrap().invalidate("cache/c.json");
+    assert_eq!(jsb.get_own_content_as_string("cache/c.json").unwrap(), "{}");
+}
+
+#[test]
+fn writes_evict_id_and_path() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig::default());
+    add_letters(&server);
+    let a = server.store().get_own_document_meta("cache/a.json").unwrap().id;
+    let b = server.store().get_own_document_meta("cache/b.json").unwrap().id;
+    let cache = jsb.cache().unwrap();
+
+    // the meta tells the cache that the id and the path are the same document
+    jsb.get_own_document_meta(&a).unwrap();
+    jsb.get_own_content_as_string(&a).unwrap();
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    jsb.get_own_content_as_string(&b).unwrap();
+    assert_eq!(cache.len(), 3);
+
+    jsb.update_own_document(&a, r#"{"name": "A"}"#.to_string()).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(jsb.get_own_content_as_string("cache/a.json").unwrap(), r#"{"name": "A"}"#);
+
+    // without a meta every entry read by id whose path is not known is evicted
+    jsb.get_own_content_as_string(&b).unwrap();
+    jsb.update_own_document("cache/b.json", r#"{"name": "B"}"#.to_string()).unwrap();
+    assert_eq!(jsb.get_own_content_as_string(&b).unwrap(), r#"{"name": "B"}"#);
+}
+
+// Pause - holds the first content response until the test lets it go
+struct Pause {
+    reached: Mutex<Option<Sender<()>>>,
+    resume: Mutex<Option<Receiver<()>>>,
+}
+
+impl Middleware for Pause {
+    fn on_response(&self, req: &ApiRequest, res: Result<ApiResponse, JsbError>) -> Result<ApiResponse, JsbError> {
+        if req.operation == "get_own_content" {
+            if let (Some(reached), Some(resume)) = (self.reached.lock().unwrap().take(), self.resume.lock().unwrap().take()) {
+                reached.send(()).unwrap();
+                resume.recv().unwrap();
+            }
+        }
+        res
+    }
+}
+
+#[test]
+fn content_read_before_an_update_is_not_cached() {
+    let server = MockServer::start();
+    server.add_project("cache", false);
+    add_letters(&server);
+
+    let (reached, on_reached) = channel();
+    let (resume, on_resume) = channel();
+    let jsb = mock_builder(&server)
+        .cache(CacheConfig::default())
+        .middleware(Pause { reached: Mutex::new(Some(reached)), resume: Mutex::new(Some(on_resume)) })
+        .build()
+        .unwrap();
+
+    thread::scope(|scope| {
+        let read = scope.spawn(|| jsb.get_own_content_as_string("cache/a.json").unwrap());
+
+        // the read has its content, the update happens before it is cached
+        on_reached.recv().unwrap();
+        jsb.update_own_document("cache/a.json", r#"{"name": "A"}"#.to_string()).unwrap();
+        resume.send(()).unwrap();
+
+        assert_eq!(read.join().unwrap(), r#"{"name": "a"}"#);
+    });
+
+    assert!(jsb.cache().unwrap().is_empty());
+    assert_eq!(jsb.get_own_content_as_string("cache/a.json").unwrap(), r#"{"name": "A"}"#);
+}
+
+#[test]
+fn invalidate_and_clear() {
+    let (server, jsb) = mock_cached_client("cache", CacheConfig::default());
+    add_public_index(&server);
+    add_letters(&server);
+    let cache = jsb.cache().unwrap();
+
+    jsb.get_content_as_string("jsonbank/public-project/index.json").unwrap();
+    jsb.get_own_content_as_string("public-project/index.json").unwrap();
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    assert_eq!(cache.len(), 3);
+
+    // removes the public path too
+    cache.invalidate("public-project/index.json");
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+
+    jsb.get_own_content_as_string("cache/a.json").unwrap();
+    assert_eq!(server.request_count(), 4);
+
+    // disabled by default
+    let jsb = mock_builder(&server).build().unwrap();
+    assert!(jsb.cache().is_none());
+}
+
+#[tokio::test]
+async fn async_reads_are_cached() {
+    let server = MockServer::start();
+    server.add_document("cache", "a.json", "{}");
+
+    let jsb = mock_builder(&server)
+        .cache(CacheConfig::default())
+        .build_async()
+        .unwrap();
+
+    let _: JsonValue = jsb.get_own_content("cache/a.json").await.unwrap();
+    let _: JsonValue = jsb.get_own_content("cache/a.json").await.unwrap();
+    assert_eq!(server.request_count(), 1);
+
+    jsb.update_own_document("cache/a.json", "[]".to_string()).await.unwrap();
+    assert_eq!(jsb.get_own_content_as_string("cache/a.json").await.unwrap(), "[]");
+}
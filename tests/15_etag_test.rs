@@ -0,0 +1,106 @@
+mod functions;
+
+use std::thread::sleep;
+use std::time::Duration;
+use jsonbank::{CacheConfig, JsonBankApi, JsonValue};
+use jsonbank::structs::ConditionalContent;
+use jsonbank::testing::MockServer;
+use functions::{add_public_index, mock_builder, mock_cached_client, mock_client};
+
+const A: &str = r#"{"name": "a"}"#;
+
+#[test]
+fn own_content_if_changed() {
+    let (server, jsb) = mock_client("etag");
+    server.add_document("etag", "a.json", A);
+
+    let etag = match jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", None).unwrap() {
+        ConditionalContent::Changed(content, etag) => {
+            assert_eq!(content["name"], "a");
+            etag.unwrap()
+        }
+        ConditionalContent::NotModified => panic!("expected content"),
+    };
+
+    let res = jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", Some(&etag)).unwrap();
+    assert_eq!(res, ConditionalContent::NotModified);
+
+    jsb.update_own_document("etag/a.json", r#"{"name": "A"}"#.to_string()).unwrap();
+    match jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", Some(&etag)).unwrap() {
+        ConditionalContent::Changed(content, new_etag) => {
+            assert_eq!(content["name"], "A");
+            assert_ne!(new_etag.unwrap(), etag);
+        }
+        ConditionalContent::NotModified => panic!("expected content"),
+    }
+
+    // an etag that was never sent
+    let res = jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", Some("\"unknown\"")).unwrap();
+    assert!(matches!(res, ConditionalContent::Changed(..)));
+
+    let err = jsb.get_own_content_if_changed::<JsonValue>("etag/missing.json", None).unwrap_err();
+    assert_eq!(err.code, "notFound");
+}
+
+#[test]
+fn public_content_if_changed() {
+    let (server, jsb) = mock_client("etag");
+    add_public_index(&server);
+    let path = "jsonbank/public-project/index.json";
+
+    let etag = match jsb.get_content_if_changed::<JsonValue>(path, None).unwrap() {
+        ConditionalContent::Changed(content, etag) => {
+            assert_eq!(content["public"], true);
+            etag
+        }
+        ConditionalContent::NotModified => panic!("expected content"),
+    };
+
+    let res = jsb.get_content_if_changed::<JsonValue>(path, etag.as_deref()).unwrap();
+    assert_eq!(res, ConditionalContent::NotModified);
+}
+
+#[test]
+fn cache_revalidates_expired_entries() {
+    let (server, jsb) = mock_cached_client("etag", CacheConfig {
+        ttl: Duration::from_millis(100),
+        ..Default::default()
+    });
+    server.add_document("etag", "a.json", A);
+
+    jsb.get_own_content_as_string("etag/a.json").unwrap();
+    jsb.get_own_content_as_string("etag/a.json").unwrap();
+    assert_eq!(server.request_count(), 1);
+
+    // revalidated with a 304, the ttl restarts
+    sleep(Duration::from_millis(150));
+    assert_eq!(jsb.get_own_content_as_string("etag/a.json").unwrap(), r#"{"name": "a"}"#);
+    jsb.get_own_content_as_string("etag/a.json").unwrap();
+    assert_eq!(server.request_count(), 2);
+    assert_eq!(jsb.cache().unwrap().len(), 1);
+
+    // changed by someone else
+    server.store().update_own_document("etag/a.json", "{}".to_string()).unwrap();
+    sleep(Duration::from_millis(150));
+    assert_eq!(jsb.get_own_content_as_string("etag/a.json").unwrap(), "{}");
+    assert_eq!(jsb.get_own_content_as_string("etag/a.json").unwrap(), "{}");
+    assert_eq!(server.request_count(), 3);
+}
+
+#[tokio::test]
+async fn async_content_if_changed() {
+    let server = MockServer::start();
+    server.add_document("etag", "a.json", "{}");
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let etag = match jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", None).await.unwrap() {
+        ConditionalContent::Changed(_, etag) => etag,
+        ConditionalContent::NotModified => panic!("expected content"),
+    };
+
+    let res = jsb.get_own_content_if_changed::<JsonValue>("etag/a.json", etag.as_deref()).await.unwrap();
+    assert_eq!(res, ConditionalContent::NotModified);
+}
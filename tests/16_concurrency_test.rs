@@ -0,0 +1,119 @@
+mod functions;
+
+use std::thread::sleep;
+use std::time::Duration;
+use jsonbank::{content_hash, CacheConfig, JsonBankApi, JsonValue};
+use jsonbank::error::ErrorKind;
+use jsonbank::structs::ExpectedVersion;
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_cached_client};
+
+const CONFIG: &str = r#"{"deploys": 0}"#;
+
+// external_update - a change made by another client
+fn external_update(server: &MockServer, content: &str) {
+    // updated_at has a millisecond precision
+    sleep(Duration::from_millis(5));
+    server.store().update_own_document("deploy/config.json", content.to_string()).unwrap();
+}
+
+#[test]
+fn update_after_check_by_updated_at() {
+    let (server, jsb) = mock_cached_client("deploy", CacheConfig::default());
+    server.add_document("deploy", "config.json", CONFIG);
+
+    let meta = jsb.get_own_document_meta("deploy/config.json").unwrap();
+    sleep(Duration::from_millis(5));
+    let res = jsb.update_own_document_after_check("deploy/config.json", r#"{"deploys": 1}"#.to_string(), &ExpectedVersion::from(&meta)).unwrap();
+    assert!(res.changed);
+
+    // meta is outdated now
+    let err = jsb.update_own_document_after_check("deploy/config.json", "{}".to_string(), &ExpectedVersion::from(&meta)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Conflict);
+    assert_eq!(err.code, "conflict");
+    assert_eq!(server.document_content("deploy/config.json").unwrap(), r#"{"deploys": 1}"#);
+}
+
+#[test]
+fn update_after_check_by_content_hash() {
+    let (server, jsb) = mock_cached_client("deploy", CacheConfig::default());
+    server.add_document("deploy", "config.json", CONFIG);
+
+    // cached content, changed by another client
+    let content = jsb.get_own_content_as_string("deploy/config.json").unwrap();
+    external_update(&server, r#"{"deploys": 5}"#);
+
+    let expected = ExpectedVersion::ContentHash(content_hash(&content));
+    let err = jsb.update_own_document_after_check("deploy/config.json", "{}".to_string(), &expected).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Conflict);
+
+    let expected = ExpectedVersion::ContentHash(content_hash(r#"{"deploys": 5}"#));
+    assert!(jsb.update_own_document_after_check("deploy/config.json", "{}".to_string(), &expected).is_ok());
+
+    let err = jsb.update_own_document_after_check("deploy/missing.json", "{}".to_string(), &expected).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn modify_retries_on_conflict() {
+    let (server, jsb) = mock_cached_client("deploy", CacheConfig::default());
+    server.add_document("deploy", "config.json", CONFIG);
+
+    let mut calls = 0;
+    jsb.modify_document("deploy/config.json", |config| {
+        calls += 1;
+        if calls == 1 {
+            // another deploy job wins the race
+            external_update(&server, r#"{"deploys": 1, "other": true}"#);
+        }
+        config["deploys"] = (config["deploys"].as_u64().unwrap() + 1).into();
+    }).unwrap();
+
+    assert_eq!(calls, 2);
+    let config: JsonValue = serde_json::from_str(&server.document_content("deploy/config.json").unwrap()).unwrap();
+    assert_eq!(config["deploys"], 2);
+    assert_eq!(config["other"], true);
+}
+
+#[test]
+fn modify_gives_up() {
+    let (server, jsb) = mock_cached_client("deploy", CacheConfig::default());
+    server.add_document("deploy", "config.json", CONFIG);
+
+    let mut calls = 0;
+    let err = jsb.modify_document("deploy/config.json", |config| {
+        calls += 1;
+        external_update(&server, &format!(r#"{{"deploys": {}}}"#, calls * 10));
+        config["deploys"] = 1.into();
+    }).unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::Conflict);
+    assert_eq!(calls, 5);
+
+    // through the trait
+    let api: &dyn JsonBankApi = &jsb;
+    let meta = api.get_own_document_meta("deploy/config.json").unwrap();
+    assert!(api.update_own_document_after_check("deploy/config.json", "[]".to_string(), &ExpectedVersion::from(&meta)).is_ok());
+}
+
+#[tokio::test]
+async fn async_modify() {
+    let server = MockServer::start();
+    server.add_document("deploy", "config.json", CONFIG);
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let mut calls = 0;
+    jsb.modify_document("deploy/config.json", |config| {
+        calls += 1;
+        if calls == 1 {
+            server.store().update_own_document("deploy/config.json", r#"{"deploys": 3}"#.to_string()).unwrap();
+        }
+        config["deploys"] = (config["deploys"].as_u64().unwrap() + 1).into();
+    }).await.unwrap();
+
+    assert_eq!(calls, 2);
+    assert_eq!(server.document_content("deploy/config.json").unwrap(), r#"{"deploys":4}"#);
+}
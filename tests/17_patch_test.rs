@@ -0,0 +1,91 @@
+mod functions;
+
+use jsonbank::{JsonBankApi, JsonPatch, JsonValue};
+use jsonbank::error::ErrorKind;
+use jsonbank::testing::MockServer;
+use serde_json::json;
+use functions::{mock_builder, mock_client};
+
+const CONFIG: &str = r#"{"version": 1, "legacy": true, "features": {"a": true, "b": false}, "tags": ["x"]}"#;
+
+fn patch(ops: JsonValue) -> JsonPatch {
+    serde_json::from_value(ops).unwrap()
+}
+
+fn content(server: &MockServer) -> JsonValue {
+    serde_json::from_str(&server.document_content("patch/config.json").unwrap()).unwrap()
+}
+
+#[test]
+fn json_patch() {
+    let (server, jsb) = mock_client("patch");
+    server.add_document("patch", "config.json", CONFIG);
+
+    let ops = patch(json!([
+        {"op": "replace", "path": "/version", "value": 2},
+        {"op": "remove", "path": "/legacy"},
+        {"op": "add", "path": "/tags/-", "value": "y"},
+    ]));
+    // the meta, the content, the meta again to check it did not change, and the update
+    let count = server.request_count();
+    assert!(jsb.patch_own_document("patch/config.json", &ops).unwrap().changed);
+    assert_eq!(content(&server), json!({"version": 2, "features": {"a": true, "b": false}, "tags": ["x", "y"]}));
+    assert_eq!(server.request_count(), count + 4);
+
+    // nothing to change, nothing is sent
+    let count = server.request_count();
+    let ops = patch(json!([{"op": "replace", "path": "/version", "value": 2}]));
+    assert!(!jsb.patch_own_document("patch/config.json", &ops).unwrap().changed);
+    assert_eq!(server.request_count(), count + 2);
+}
+
+#[test]
+fn invalid_patch() {
+    let (server, jsb) = mock_client("patch");
+    server.add_document("patch", "config.json", CONFIG);
+
+    // a failing test operation leaves the document untouched
+    let ops = patch(json!([
+        {"op": "remove", "path": "/legacy"},
+        {"op": "test", "path": "/version", "value": 5},
+    ]));
+    let err = jsb.patch_own_document("patch/config.json", &ops).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+    assert_eq!(err.code, "invalid_patch");
+
+    let ops = patch(json!([{"op": "remove", "path": "/missing"}]));
+    assert_eq!(jsb.patch_own_document("patch/config.json", &ops).unwrap_err().code, "invalid_patch");
+    assert_eq!(server.document_content("patch/config.json").unwrap(), CONFIG);
+
+    let ops = patch(json!([]));
+    assert_eq!(jsb.patch_own_document("patch/missing.json", &ops).unwrap_err().kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn merge_patch() {
+    let (server, jsb) = mock_client("patch");
+    server.add_document("patch", "config.json", CONFIG);
+
+    let api: &dyn JsonBankApi = &jsb;
+    let res = api.merge_own_document("patch/config.json", &json!({"legacy": null, "features": {"b": true}})).unwrap();
+    assert!(res.changed);
+    assert_eq!(content(&server), json!({"version": 1, "features": {"a": true, "b": true}, "tags": ["x"]}));
+
+    let res = api.merge_own_document("patch/config.json", &json!({"version": 1})).unwrap();
+    assert!(!res.changed);
+}
+
+#[tokio::test]
+async fn async_patch_and_merge() {
+    let server = MockServer::start();
+    server.add_document("patch", "config.json", CONFIG);
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let ops = patch(json!([{"op": "replace", "path": "/version", "value": 3}]));
+    assert!(jsb.patch_own_document("patch/config.json", &ops).await.unwrap().changed);
+    assert!(jsb.merge_own_document("patch/config.json", &json!({"tags": []})).await.unwrap().changed);
+    assert_eq!(content(&server), json!({"version": 3, "legacy": true, "features": {"a": true, "b": false}, "tags": []}));
+}
@@ -0,0 +1,95 @@
+mod functions;
+
+use std::collections::HashMap;
+use jsonbank::{CacheConfig, JsonBankApi, JsonValue};
+use jsonbank::error::ErrorKind;
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_cached_client, mock_client};
+
+const FLAGS: &str = r#"{"features": {"checkout": {"enabled": true, "percent": 20}, "a/b": 1}, "regions": ["eu", "us"]}"#;
+
+// add_public_flags - makes the project `flags` public and adds `flags.json` to it
+fn add_public_flags(server: &MockServer) {
+    server.add_project("flags", true);
+    server.add_document("flags", "flags.json", FLAGS);
+}
+
+#[test]
+fn own_content_at() {
+    let (server, jsb) = mock_client("flags");
+    add_public_flags(&server);
+
+    let enabled: bool = jsb.get_own_content_at("flags/flags.json", "/features/checkout/enabled").unwrap();
+    assert!(enabled);
+
+    let checkout: HashMap<String, JsonValue> = jsb.get_own_content_at("flags/flags.json", "/features/checkout").unwrap();
+    assert_eq!(checkout["percent"], 20);
+
+    let region: String = jsb.get_own_content_at("flags/flags.json", "/regions/1").unwrap();
+    assert_eq!(region, "us");
+
+    // escaped `/`
+    let value: u32 = jsb.get_own_content_at("flags/flags.json", "/features/a~1b").unwrap();
+    assert_eq!(value, 1);
+
+    // the whole document
+    let all: JsonValue = jsb.get_own_content_at("flags/flags.json", "").unwrap();
+    assert_eq!(all["regions"][0], "eu");
+
+    // through the trait
+    let api = &jsb;
+    let percent: u8 = JsonBankApi::get_own_content_at(api, "flags/flags.json", "/features/checkout/percent").unwrap();
+    assert_eq!(percent, 20);
+}
+
+#[test]
+fn pointer_errors() {
+    let (server, jsb) = mock_client("flags");
+    add_public_flags(&server);
+
+    let err = jsb.get_own_content_at::<JsonValue>("flags/flags.json", "/features/search").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::PathNotFound);
+    assert_eq!(err.code, "path_not_found");
+    assert_eq!(err.message, "Path /features/search not found in document");
+
+    let err = jsb.get_own_content_at::<JsonValue>("flags/flags.json", "/regions/5").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::PathNotFound);
+
+    let err = jsb.get_own_content_at::<JsonValue>("flags/flags.json", "features").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+
+    // wrong type
+    let err = jsb.get_own_content_at::<bool>("flags/flags.json", "/regions").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Decode);
+
+    let err = jsb.get_own_content_at::<JsonValue>("flags/missing.json", "/a").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn public_content_at_is_cached() {
+    let (server, jsb) = mock_cached_client("flags", CacheConfig::default());
+    add_public_flags(&server);
+
+    let percent: u8 = jsb.get_content_at("jsonbank/flags/flags.json", "/features/checkout/percent").unwrap();
+    let regions: Vec<String> = jsb.get_content_at("jsonbank/flags/flags.json", "/regions").unwrap();
+    assert_eq!(percent, 20);
+    assert_eq!(regions, ["eu", "us"]);
+    assert_eq!(server.request_count(), 1);
+}
+
+#[tokio::test]
+async fn async_content_at() {
+    let server = MockServer::start();
+    server.add_document("flags", "flags.json", FLAGS);
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let enabled: bool = jsb.get_own_content_at("flags/flags.json", "/features/checkout/enabled").await.unwrap();
+    assert!(enabled);
+
+    let err = jsb.get_own_content_at::<bool>("flags/flags.json", "/missing").await.unwrap_err();
+    assert_eq!(err.kind, ErrorKind::PathNotFound);
+}
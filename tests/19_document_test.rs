@@ -0,0 +1,116 @@
+mod functions;
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use jsonbank::JsonBankApi;
+use jsonbank::error::ErrorKind;
+use jsonbank::stores::MemoryStore;
+use jsonbank::testing::MockServer;
+use functions::{mock_builder, mock_client};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct AppConfig {
+    debug: bool,
+    replicas: u32,
+}
+
+const CONFIG: &str = r#"{"debug": true, "replicas": 2}"#;
+
+#[test]
+fn get_and_set() {
+    let (server, jsb) = mock_client("sdk-test");
+    server.add_document("sdk-test", "config.json", CONFIG);
+    let config = jsb.document::<AppConfig>("sdk-test/config.json");
+    assert_eq!(config.path(), "sdk-test/config.json");
+
+    let mut app = config.get().unwrap();
+    assert_eq!(app, AppConfig { debug: true, replicas: 2 });
+
+    app.replicas = 3;
+    assert!(config.set(&app).unwrap().changed);
+    assert!(!config.set(&app).unwrap().changed);
+    assert_eq!(server.document_content("sdk-test/config.json").unwrap(), r#"{"debug":true,"replicas":3}"#);
+
+    assert_eq!(config.meta().unwrap().path, "config.json");
+    assert!(config.exists().unwrap());
+    assert!(config.delete().unwrap().deleted);
+    assert!(!config.exists().unwrap());
+}
+
+#[test]
+fn set_creates() {
+    let (server, jsb) = mock_client("sdk-test");
+    server.add_document("sdk-test", "config.json", CONFIG);
+    server.add_folder("sdk-test", "envs");
+
+    let prod = jsb.document::<AppConfig>("sdk-test/envs/prod.json");
+    assert_eq!(prod.get().unwrap_err().kind, ErrorKind::NotFound);
+
+    let app = AppConfig { debug: false, replicas: 5 };
+    assert!(prod.set(&app).unwrap().changed);
+    assert_eq!(prod.get().unwrap(), app);
+    assert_eq!(prod.meta().unwrap().path, "envs/prod.json");
+
+    // an id can not be created
+    let err = jsb.document::<AppConfig>("unknown-id").set(&app).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+}
+
+// Fails to serialize
+struct Broken;
+
+impl Serialize for Broken {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("broken"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Broken {
+    fn deserialize<D: serde::Deserializer<'de>>(_: D) -> Result<Self, D::Error> {
+        Ok(Broken)
+    }
+}
+
+#[test]
+fn typed_errors() {
+    let (server, jsb) = mock_client("sdk-test");
+    server.add_document("sdk-test", "config.json", CONFIG);
+
+    // content does not match the type
+    let err = jsb.document::<Vec<String>>("sdk-test/config.json").get().unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Decode);
+
+    let err = jsb.document::<Broken>("sdk-test/config.json").set(&Broken).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Encode);
+    assert_eq!(err.code, "encode_error");
+    assert_eq!(server.document_content("sdk-test/config.json").unwrap(), r#"{"debug": true, "replicas": 2}"#);
+}
+
+#[test]
+fn any_backend() {
+    let store = MemoryStore::new();
+    store.add_project("local", false);
+
+    let doc = JsonBankApi::document::<BTreeMap<String, u32>>(&store, "local/counts.json");
+    doc.set(&BTreeMap::from([("a".to_string(), 1)])).unwrap();
+    assert_eq!(doc.get().unwrap()["a"], 1);
+}
+
+#[tokio::test]
+async fn async_document() {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    let config = jsb.document::<AppConfig>("sdk-test/config.json");
+    assert!(!config.exists().await.unwrap());
+
+    let app = AppConfig { debug: true, replicas: 1 };
+    config.set(&app).await.unwrap();
+    assert_eq!(config.get().await.unwrap(), app);
+    assert_eq!(config.meta().await.unwrap().name, "config.json");
+    assert!(config.delete().await.unwrap().deleted);
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use jsonbank::{CacheConfig, JsonBank, JsonValue, Keys, RequestOptions, RetryPolicy};
+use jsonbank::error::ErrorKind;
+use jsonbank::structs::{ConditionalContent, CreateFolderBody, ExpectedVersion};
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+// serve - answers each request with the next canned response after `delay`,
+// returns the url and the head of every received request
+fn serve(responses: Vec<&'static str>, delay: Duration) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+
+    thread::spawn(move || {
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut buf = [0; 8192];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            log.lock().unwrap().push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+            thread::sleep(delay);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    (url, received)
+}
+
+fn client(url: &str, public_key: &str) -> JsonBank {
+    JsonBank::builder()
+        .host(url)
+        .keys(public_key, MOCK_PRIVATE_KEY)
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+fn tenant_keys() -> Keys {
+    Keys {
+        public: Some(MOCK_PUBLIC_KEY.to_string()),
+        private: Some(MOCK_PRIVATE_KEY.to_string()),
+    }
+}
+
+#[test]
+fn headers_and_idempotency_key() {
+    let (url, received) = serve(vec![OK], Duration::ZERO);
+    let jsb = client(&url, "public");
+
+    let options = RequestOptions {
+        headers: HashMap::from([("X-Request-Id".to_string(), "abc-123".to_string())]),
+        idempotency_key: Some("create-1".to_string()),
+        ..Default::default()
+    };
+    // only the sent request matters, the canned reply is not a folder
+    let _ = jsb.create_folder_with(CreateFolderBody { name: "a".to_string(), project: "p".to_string(), folder: None }, options);
+
+    let request = received.lock().unwrap()[0].clone();
+    assert!(request.contains("x-request-id: abc-123"));
+    assert!(request.contains("idempotency-key: create-1"));
+    assert!(request.contains("jsb-prv-key: jsb_mock_private_key"));
+}
+
+#[test]
+fn invalid_headers() {
+    let jsb = client("http://127.0.0.1:1", "public");
+
+    let options = RequestOptions {
+        headers: HashMap::from([("jsb-pub-key".to_string(), "other".to_string())]),
+        ..Default::default()
+    };
+    let err = jsb.get_own_document_meta_with("p/a.json", options).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Validation);
+
+    let options = RequestOptions {
+        headers: HashMap::from([("bad header".to_string(), "value".to_string())]),
+        ..Default::default()
+    };
+    assert_eq!(jsb.get_own_document_meta_with("p/a.json", options).unwrap_err().kind, ErrorKind::Validation);
+
+    let options = RequestOptions {
+        keys: Some(Keys { public: Some("bad\nkey".to_string()), private: None }),
+        ..Default::default()
+    };
+    assert_eq!(jsb.get_own_document_meta_with("p/a.json", options).unwrap_err().kind, ErrorKind::Config);
+}
+
+#[test]
+fn timeout() {
+    let (url, _) = serve(vec![OK, OK], Duration::from_millis(500));
+    let jsb = client(&url, "public");
+
+    let options = RequestOptions {
+        timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let err = jsb.get_own_content_as_string_with("p/a.json", options).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Transport);
+
+    // no timeout by default
+    assert_eq!(jsb.get_own_content_as_string("p/a.json").unwrap(), "{}");
+}
+
+#[test]
+fn retry_override() {
+    let (url, received) = serve(vec![UNAVAILABLE, OK], Duration::ZERO);
+    let jsb = client(&url, "public");
+
+    let options = RequestOptions {
+        retry: Some(RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(10),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let content: JsonValue = jsb.get_own_content_with("p/a.json", options).unwrap();
+    assert_eq!(content, serde_json::json!({}));
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn keys_override() {
+    let server = MockServer::start();
+    server.add_document("tenant", "config.json", r#"{"tenant": true}"#);
+
+    // the instance keys are not accepted by the server
+    let mut jsb = JsonBank::builder()
+        .host(&server.url())
+        .keys("other_public_key", "other_private_key")
+        .cache(CacheConfig::default())
+        .build()
+        .unwrap();
+    assert_eq!(jsb.get_own_content_as_string("tenant/config.json").unwrap_err().kind, ErrorKind::Unauthorized);
+
+    let tenant = || RequestOptions { keys: Some(tenant_keys()), ..Default::default() };
+    let content: JsonValue = jsb.get_own_content_with("tenant/config.json", tenant()).unwrap();
+    assert_eq!(content["tenant"], true);
+    assert!(jsb.has_own_document_with("tenant/config.json", tenant()).unwrap());
+    assert_eq!(jsb.get_own_document_meta_with("tenant/config.json", tenant()).unwrap().path, "config.json");
+    assert!(jsb.get_own_content_at_with::<bool>("tenant/config.json", "/tenant", tenant()).unwrap());
+    let res = jsb.get_own_content_if_changed_with::<JsonValue>("tenant/config.json", None, tenant()).unwrap();
+    assert!(matches!(res, ConditionalContent::Changed(..)));
+
+    // composite operations send every request with the options
+    assert!(jsb.authenticate_with(tenant()).unwrap().authenticated && jsb.is_authenticated());
+    assert!(jsb.modify_document_with("tenant/config.json", |config| config["step"] = 1.into(), tenant()).unwrap().changed);
+    let ops = serde_json::from_value(serde_json::json!([{"op": "replace", "path": "/step", "value": 2}])).unwrap();
+    assert!(jsb.patch_own_document_with("tenant/config.json", &ops, tenant()).unwrap().changed);
+    assert!(jsb.merge_own_document_with("tenant/config.json", &serde_json::json!({"step": 3}), tenant()).unwrap().changed);
+    let meta = jsb.get_own_document_meta_with("tenant/config.json", tenant()).unwrap();
+    let content = r#"{"tenant": true, "step": 4}"#.to_string();
+    assert!(jsb.update_own_document_after_check_with("tenant/config.json", content, &ExpectedVersion::from(&meta), tenant()).unwrap().changed);
+    assert_eq!(jsb.ensure_folder_path_with("tenant/configs/prod", tenant()).unwrap().created, vec!["configs", "configs/prod"]);
+    assert_eq!(server.document_content("tenant/config.json").unwrap(), r#"{"tenant": true, "step": 4}"#);
+
+    assert!(jsb.delete_document_with("tenant/config.json", tenant()).unwrap().deleted);
+
+    // content of other keys is not cached
+    assert!(jsb.cache().unwrap().is_empty());
+
+    // keys are never printed
+    let debug = format!("{:?}", tenant());
+    assert!(!debug.contains(MOCK_PUBLIC_KEY));
+    assert!(debug.contains("***"));
+}
+
+#[tokio::test]
+async fn async_options() {
+    let server = MockServer::start();
+    server.add_document("tenant", "config.json", "{}");
+
+    let jsb = JsonBank::builder()
+        .host(&server.url())
+        .build_async()
+        .unwrap();
+
+    let options = RequestOptions {
+        keys: Some(tenant_keys()),
+        timeout: Some(Duration::from_secs(5)),
+        ..Default::default()
+    };
+    assert_eq!(jsb.get_own_content_as_string_with("tenant/config.json", options.clone()).await.unwrap(), "{}");
+    assert_eq!(jsb.get_folder_with("tenant/missing", options.clone()).await.unwrap_err().kind, ErrorKind::NotFound);
+    assert!(jsb.merge_own_document_with("tenant/config.json", &serde_json::json!({"a": 1}), options.clone()).await.unwrap().changed);
+    assert!(jsb.modify_document_with("tenant/config.json", |config| config["a"] = 2.into(), options.clone()).await.unwrap().changed);
+    assert_eq!(jsb.get_own_content_at_with::<u64>("tenant/config.json", "/a", options.clone()).await.unwrap(), 2);
+    assert_eq!(jsb.ensure_folder_path_with("tenant/configs", options).await.unwrap().created, vec!["configs"]);
+    assert_eq!(jsb.get_own_content_as_string("tenant/config.json").await.unwrap_err().kind, ErrorKind::MissingKey);
+}
@@ -0,0 +1,140 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+use jsonbank::{JsonBank, RetryPolicy, TlsBackend, TlsConfig};
+use jsonbank::error::ErrorKind;
+
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 13\r\nConnection: close\r\n\r\n{\"tls\": true}";
+
+// tls_server - starts an https server for `localhost` with a certificate signed by a new CA,
+// returns the url and the PEM of the CA
+fn tls_server() -> (String, String) {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name.push(DnType::CommonName, "jsonbank test CA");
+    let ca = Certificate::from_params(params).unwrap();
+
+    let server = Certificate::from_params(CertificateParams::new(vec!["localhost".to_string()])).unwrap();
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![rustls::Certificate(server.serialize_der_with_signer(&ca).unwrap())],
+            rustls::PrivateKey(server.serialize_private_key_der()),
+        )
+        .unwrap();
+    let config = Arc::new(config);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("https://localhost:{}", listener.local_addr().unwrap().port());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let connection = rustls::ServerConnection::new(config.clone()).unwrap();
+            let mut stream = rustls::StreamOwned::new(connection, stream.unwrap());
+
+            // the handshake fails when the client does not trust the CA
+            let mut buf = [0; 4096];
+            if stream.read(&mut buf).is_ok() {
+                let _ = stream.write_all(OK.as_bytes());
+                stream.conn.send_close_notify();
+                let _ = stream.flush();
+            }
+        }
+    });
+
+    (url, ca.serialize_pem().unwrap())
+}
+
+fn client(url: &str, tls: TlsConfig) -> JsonBank {
+    JsonBank::builder()
+        .host(url)
+        .retry(RetryPolicy::none())
+        .tls(tls)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn untrusted_certificate() {
+    let (url, _) = tls_server();
+
+    for backend in [TlsBackend::Auto, TlsBackend::NativeTls, TlsBackend::Rustls] {
+        let jsb = client(&url, TlsConfig { backend, ..Default::default() });
+        let err = jsb.get_content_as_string("p/a.json").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Transport, "{:?}", backend);
+    }
+}
+
+#[test]
+fn root_certificate() {
+    let (url, ca) = tls_server();
+
+    for backend in [TlsBackend::Auto, TlsBackend::NativeTls, TlsBackend::Rustls] {
+        let jsb = JsonBank::builder()
+            .host(&url)
+            .tls_backend(backend)
+            .add_root_certificate(&ca)
+            .build()
+            .unwrap();
+        assert_eq!(jsb.get_content_as_string("p/a.json").unwrap(), r#"{"tls": true}"#, "{:?}", backend);
+    }
+
+    let jsb = client(&url, TlsConfig {
+        backend: TlsBackend::Rustls,
+        root_certificates: vec![ca],
+        only_root_certificates: true,
+    });
+    assert_eq!(jsb.get_content_as_string("p/a.json").unwrap(), r#"{"tls": true}"#);
+}
+
+#[test]
+fn invalid_root_certificate() {
+    for pem in ["not a certificate", "-----BEGIN CERTIFICATE-----\nbm90IGRlcg==\n-----END CERTIFICATE-----\n"] {
+        match JsonBank::builder().add_root_certificate(pem).build() {
+            Err(err) => assert_eq!(err.kind, ErrorKind::Config),
+            Ok(_) => panic!("Expected an invalid certificate"),
+        }
+    }
+}
+
+#[test]
+fn proxy() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy = format!("http://user:secret@{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(String::new()));
+    let log = received.clone();
+
+    thread::spawn(move || {
+        let mut stream = listener.incoming().next().unwrap().unwrap();
+        let mut buf = [0; 4096];
+        let n = stream.read(&mut buf).unwrap();
+        log.lock().unwrap().push_str(&String::from_utf8_lossy(&buf[..n]).to_lowercase());
+        stream.write_all(OK.as_bytes()).unwrap();
+    });
+
+    let jsb = JsonBank::builder()
+        .host("http://api.jsonbank.invalid")
+        .proxy(&proxy)
+        .build()
+        .unwrap();
+    assert_eq!(jsb.get_content_as_string("p/a.json").unwrap(), r#"{"tls": true}"#);
+
+    let request = received.lock().unwrap().clone();
+    assert!(request.starts_with("get http://api.jsonbank.invalid/"));
+    assert!(request.contains("proxy-authorization: basic"));
+}
+
+#[tokio::test]
+async fn async_root_certificate() {
+    let (url, ca) = tls_server();
+
+    let jsb = JsonBank::builder()
+        .host(&url)
+        .add_root_certificate(&ca)
+        .build_async()
+        .unwrap();
+    assert_eq!(jsb.get_content_as_string("p/a.json").await.unwrap(), r#"{"tls": true}"#);
+}
@@ -0,0 +1,250 @@
+mod functions;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use jsonbank::{JsonBank, RetryPolicy};
+use jsonbank::structs::{CreateDocumentBody, ExpectedVersion, UploadDocumentBody};
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+use functions::mock_builder;
+
+// A recorded span
+#[derive(Debug, Clone)]
+struct SpanData {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<String, String>,
+}
+
+impl SpanData {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(|value| value.as_str())
+    }
+}
+
+struct Fields<'a>(&'a mut HashMap<String, String>);
+
+impl<'a> Visit for Fields<'a> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+// Recorder - keeps every span in creation order
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<(u64, SpanData)>>>,
+}
+
+impl Recorder {
+    fn spans(&self) -> Vec<(u64, SpanData)> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    fn requests(&self) -> Vec<SpanData> {
+        self.spans().into_iter().map(|(_, span)| span).filter(|span| span.name == "jsonbank.request").collect()
+    }
+
+    // operation - the operation span with the given name and the request spans in it
+    fn operation(&self, name: &str) -> (SpanData, Vec<SpanData>) {
+        let spans = self.spans();
+        let (id, operation) = spans.iter()
+            .find(|(_, span)| span.name == "jsonbank.operation" && span.field("operation") == Some(name))
+            .cloned()
+            .unwrap_or_else(|| panic!("No span of {}", name));
+        let children = spans.into_iter().map(|(_, span)| span).filter(|span| span.parent == Some(id)).collect();
+        (operation, children)
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        attrs.record(&mut Fields(&mut fields));
+        let parent = ctx.span(id).and_then(|span| span.parent()).map(|parent| parent.id().into_u64());
+        self.spans.lock().unwrap().push((id.into_u64(), SpanData { name: attrs.metadata().name(), parent, fields }));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut spans = self.spans.lock().unwrap();
+        if let Some((_, span)) = spans.iter_mut().find(|(span_id, _)| *span_id == id.into_u64()) {
+            values.record(&mut Fields(&mut span.fields));
+        }
+    }
+}
+
+fn server() -> MockServer {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+    server.add_document("sdk-test", "config.json", r#"{"debug": true}"#);
+    server
+}
+
+fn record() -> (Recorder, tracing::subscriber::DefaultGuard) {
+    let recorder = Recorder::default();
+    let guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+    (recorder, guard)
+}
+
+fn init() -> (MockServer, JsonBank, Recorder, tracing::subscriber::DefaultGuard) {
+    let server = server();
+    let jsb = mock_builder(&server).build().unwrap();
+    let (recorder, guard) = record();
+
+    (server, jsb, recorder, guard)
+}
+
+#[test]
+fn request_span() {
+    let (_server, jsb, recorder, _guard) = init();
+    jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+
+    let requests = recorder.requests();
+    assert_eq!(requests.len(), 1);
+
+    let span = &requests[0];
+    assert_eq!(span.field("operation"), Some("get_own_content"));
+    assert_eq!(span.field("method"), Some("GET"));
+    assert_eq!(span.field("host"), Some("127.0.0.1"));
+    assert_eq!(span.field("path"), Some("/v1/file/sdk-test/config.json"));
+    assert_eq!(span.field("status"), Some("200"));
+    assert_eq!(span.field("bytes"), Some("15"));
+    assert_eq!(span.field("retries"), Some("0"));
+    assert!(span.field("latency_ms").is_some());
+    assert_eq!(span.field("error"), None);
+
+    jsb.get_own_content_as_string("sdk-test/missing.json").unwrap_err();
+    assert_eq!(recorder.requests()[1].field("status"), Some("404"));
+
+    // a request that got no response records the error code
+    let jsb = JsonBank::builder().host("http://127.0.0.1:1").retry(RetryPolicy::none()).build().unwrap();
+    jsb.get_content_as_string("sdk-test/config.json").unwrap_err();
+    let span = &recorder.requests()[2];
+    assert_eq!(span.field("status"), None);
+    assert!(span.field("error").is_some());
+}
+
+#[test]
+fn keys_are_never_recorded() {
+    let (_server, jsb, recorder, _guard) = init();
+    jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+    jsb.update_own_document("sdk-test/config.json", "{}".to_string()).unwrap();
+
+    for (_, span) in recorder.spans() {
+        for value in span.fields.values() {
+            assert!(!value.contains(MOCK_PUBLIC_KEY) && !value.contains(MOCK_PRIVATE_KEY), "{:?}", span);
+        }
+    }
+}
+
+#[test]
+fn composite_operations() {
+    let (_server, jsb, recorder, _guard) = init();
+
+    let body = CreateDocumentBody {
+        name: "config.json".to_string(),
+        project: "sdk-test".to_string(),
+        content: "{}".to_string(),
+        folder: None,
+    };
+    assert!(jsb.create_document_if_not_exists(body).unwrap().exists);
+
+    let (_, children) = recorder.operation("create_document_if_not_exists");
+    let operations: Vec<_> = children.iter().map(|span| span.field("operation").unwrap()).collect();
+    assert_eq!(operations, vec!["create_document", "get_own_document_meta"]);
+    assert_ne!(children[0].field("status"), Some("200"));
+    assert_eq!(children[1].field("status"), Some("200"));
+
+    let dir = std::env::temp_dir().join(format!("jsonbank-tracing-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("upload.json");
+    std::fs::write(&file, "{}").unwrap();
+
+    jsb.upload_document(UploadDocumentBody {
+        file_path: file.to_string_lossy().to_string(),
+        project: "sdk-test".to_string(),
+        name: None,
+        folder: None,
+    }).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let (_, children) = recorder.operation("upload_document");
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].field("operation"), Some("create_document"));
+
+    // operations nest in each other
+    jsb.ensure_folder_path("sdk-test/a/b").unwrap();
+    let (_, children) = recorder.operation("ensure_folder_path");
+    assert_eq!(children.len(), 2);
+    assert!(children.iter().all(|span| span.field("operation") == Some("create_folder_if_not_exists")));
+}
+
+#[test]
+fn retries() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        let responses = [
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+        ];
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let _ = stream.read(&mut [0; 4096]);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    let jsb = JsonBank::builder()
+        .host(&url)
+        .retry(RetryPolicy { base_delay: Duration::from_millis(10), ..Default::default() })
+        .build()
+        .unwrap();
+
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+    jsb.get_content_as_string("sdk-test/config.json").unwrap();
+
+    let requests = recorder.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].field("retries"), Some("1"));
+    assert_eq!(requests[0].field("status"), Some("200"));
+}
+
+#[tokio::test]
+async fn async_composite_operations() {
+    let server = server();
+    let (recorder, _guard) = record();
+    let jsb = mock_builder(&server)
+        .build_async()
+        .unwrap();
+
+    jsb.modify_document("sdk-test/config.json", |value| value["debug"] = false.into()).await.unwrap();
+
+    // the version is checked by reading the meta again right before the update
+    let (_, children) = recorder.operation("modify_document");
+    let operations: Vec<_> = children.iter().map(|span| span.field("operation").unwrap()).collect();
+    assert_eq!(operations, vec!["get_own_document_meta", "get_own_content", "get_own_document_meta", "update_own_document"]);
+
+    let meta = jsb.get_own_document_meta("sdk-test/config.json").await.unwrap();
+    jsb.update_own_document_after_check("sdk-test/config.json", "{}".to_string(), &ExpectedVersion::from(&meta)).await.unwrap();
+
+    let (_, children) = recorder.operation("update_own_document_after_check");
+    let operations: Vec<_> = children.iter().map(|span| span.field("operation").unwrap()).collect();
+    assert_eq!(operations, vec!["get_own_document_meta", "update_own_document"]);
+}
@@ -0,0 +1,183 @@
+mod functions;
+
+use std::sync::{Arc, Mutex};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::MetricKind;
+use jsonbank::{JsonBank, JsonValue, RetryPolicy};
+use jsonbank::error::ErrorKind;
+use jsonbank::metrics::{MetricsCrateSink, MetricsSink, RequestMetrics, StatusClass};
+use jsonbank::structs::{ConditionalContent, CreateDocumentBody};
+use jsonbank::testing::MockServer;
+use functions::mock_builder;
+
+// Keeps every record
+#[derive(Clone, Default)]
+struct Records(Arc<Mutex<Vec<RequestMetrics>>>);
+
+impl Records {
+    fn all(&self) -> Vec<RequestMetrics> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn last(&self) -> RequestMetrics {
+        self.all().pop().unwrap()
+    }
+}
+
+impl MetricsSink for Records {
+    fn record(&self, metrics: &RequestMetrics) {
+        self.0.lock().unwrap().push(metrics.clone());
+    }
+}
+
+fn init() -> (MockServer, JsonBank, Records) {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+    server.add_document("sdk-test", "config.json", r#"{"debug": true}"#);
+
+    let records = Records::default();
+    let jsb = mock_builder(&server)
+        .metrics(records.clone())
+        .build()
+        .unwrap();
+
+    (server, jsb, records)
+}
+
+fn document(name: &str) -> CreateDocumentBody {
+    CreateDocumentBody {
+        name: name.to_string(),
+        project: "sdk-test".to_string(),
+        content: r#"{"a": 1}"#.to_string(),
+        folder: None,
+    }
+}
+
+#[test]
+fn records_requests() {
+    let (_server, jsb, records) = init();
+
+    jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+    let metrics = records.last();
+    assert_eq!(metrics.operation, "get_own_content");
+    assert_eq!(metrics.method, "GET");
+    assert_eq!(metrics.status, Some(200));
+    assert_eq!(metrics.status_class, StatusClass::Success);
+    assert_eq!(metrics.error, None);
+    assert_eq!(metrics.bytes_sent, 0);
+    assert_eq!(metrics.bytes_received, 15);
+    assert_eq!(metrics.retries, 0);
+
+    jsb.create_document(document("new.json")).unwrap();
+    let metrics = records.last();
+    assert_eq!(metrics.operation, "create_document");
+    assert_eq!(metrics.method, "POST");
+    assert!(metrics.bytes_sent > 0);
+
+    // every request of a composite operation is recorded
+    assert!(jsb.create_document_if_not_exists(document("new.json")).unwrap().exists);
+    let all = records.all();
+    let last: Vec<_> = all[all.len() - 2..].iter().map(|metrics| (metrics.operation, metrics.error)).collect();
+    assert_eq!(last, vec![("create_document", Some(ErrorKind::AlreadyExists)), ("get_own_document_meta", None)]);
+}
+
+#[test]
+fn records_errors() {
+    let (_server, jsb, records) = init();
+
+    jsb.get_own_content_as_string("sdk-test/missing.json").unwrap_err();
+    let metrics = records.last();
+    assert_eq!(metrics.status, Some(404));
+    assert_eq!(metrics.status_class, StatusClass::ClientError);
+    assert_eq!(metrics.error, Some(ErrorKind::NotFound));
+
+    // a 304 answers a conditional request
+    let etag = match jsb.get_own_content_if_changed::<JsonValue>("sdk-test/config.json", None).unwrap() {
+        ConditionalContent::Changed(_, etag) => etag,
+        ConditionalContent::NotModified => panic!("Expected content"),
+    };
+    jsb.get_own_content_if_changed::<JsonValue>("sdk-test/config.json", etag.as_deref()).unwrap();
+    let metrics = records.last();
+    assert_eq!(metrics.status, Some(304));
+    assert_eq!(metrics.status_class, StatusClass::Redirection);
+    assert_eq!(metrics.error, None);
+
+    let jsb = JsonBank::builder()
+        .host("http://127.0.0.1:1")
+        .retry(RetryPolicy::none())
+        .metrics(records.clone())
+        .build()
+        .unwrap();
+    jsb.get_content_as_string("sdk-test/config.json").unwrap_err();
+    let metrics = records.last();
+    assert_eq!(metrics.status, None);
+    assert_eq!(metrics.status_class, StatusClass::NoResponse);
+    assert_eq!(metrics.error, Some(ErrorKind::Transport));
+}
+
+#[test]
+fn status_class() {
+    assert_eq!(StatusClass::from_status(204).as_str(), "2xx");
+    assert_eq!(StatusClass::from_status(429), StatusClass::ClientError);
+    assert_eq!(StatusClass::from_status(503).as_str(), "5xx");
+    assert_eq!(StatusClass::NoResponse.as_str(), "none");
+}
+
+#[test]
+fn metrics_crate_sink() {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+    server.add_document("sdk-test", "config.json", "{}");
+
+    let jsb = mock_builder(&server)
+        .metrics(MetricsCrateSink::new())
+        .build()
+        .unwrap();
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+        jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+        jsb.get_own_content_as_string("sdk-test/missing.json").unwrap_err();
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let find = |kind: MetricKind, name: &str, error: &str| {
+        snapshot.iter()
+            .find(|(key, _, _, _)| {
+                key.kind() == kind
+                    && key.key().name() == name
+                    && key.key().labels().any(|label| label.key() == "error" && label.value() == error)
+            })
+            .map(|(_, _, _, value)| value)
+    };
+
+    assert_eq!(find(MetricKind::Counter, "jsonbank_requests_total", "none"), Some(&DebugValue::Counter(2)));
+    assert_eq!(find(MetricKind::Counter, "jsonbank_requests_total", "NotFound"), Some(&DebugValue::Counter(1)));
+    match find(MetricKind::Histogram, "jsonbank_request_duration_seconds", "none") {
+        Some(DebugValue::Histogram(values)) => assert_eq!(values.len(), 2),
+        value => panic!("Expected a histogram: {:?}", value),
+    }
+
+    let received = snapshot.iter().find(|(key, _, _, _)| key.key().name() == "jsonbank_request_bytes_received_total");
+    assert!(matches!(received, Some((_, _, _, DebugValue::Counter(bytes))) if *bytes > 0));
+}
+
+#[tokio::test]
+async fn async_records_requests() {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+    server.add_document("sdk-test", "config.json", "{}");
+
+    let records = Records::default();
+    let jsb = mock_builder(&server)
+        .metrics(records.clone())
+        .build_async()
+        .unwrap();
+
+    jsb.get_own_document_meta("sdk-test/config.json").await.unwrap();
+    let metrics = records.last();
+    assert_eq!(metrics.operation, "get_own_document_meta");
+    assert_eq!(metrics.status_class, StatusClass::Success);
+}
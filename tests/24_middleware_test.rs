@@ -0,0 +1,211 @@
+mod functions;
+
+use std::sync::{Arc, Mutex};
+use jsonbank::{JsonBank, JsonValue, RetryPolicy};
+use jsonbank::error::{ErrorKind, JsbError};
+use jsonbank::middleware::{ApiRequest, ApiResponse, Middleware, Method, StatusCode};
+use jsonbank::middleware::header::{HeaderMap, HeaderValue};
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
+use functions::mock_builder;
+
+// Log - logs every call, named after the middleware
+struct Log {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Middleware for Log {
+    fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+        // the api keys are added before any other middleware
+        let has_key = req.headers.contains_key("jsb-pub-key");
+        self.log.lock().unwrap().push(format!("{}:request:{}:{}", self.name, req.operation, has_key));
+        Ok(None)
+    }
+
+    fn on_response(&self, _req: &ApiRequest, res: Result<ApiResponse, JsbError>) -> Result<ApiResponse, JsbError> {
+        let status = res.as_ref().map_or(0, |res| res.status.as_u16());
+        self.log.lock().unwrap().push(format!("{}:response:{}", self.name, status));
+        res
+    }
+}
+
+// Answer - answers every request without sending it
+struct Answer(&'static str);
+
+impl Middleware for Answer {
+    fn on_request(&self, _req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+        Ok(Some(ApiResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: self.0.as_bytes().to_vec() }))
+    }
+}
+
+// ReadOnly - rejects every write before it is sent
+struct ReadOnly;
+
+impl Middleware for ReadOnly {
+    fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+        if req.method == Method::POST && req.operation != "authenticate" {
+            return Err(JsbError::new(ErrorKind::Validation, "read_only", "Writes are disabled"));
+        }
+        Ok(None)
+    }
+}
+
+fn server() -> MockServer {
+    let server = MockServer::start();
+    server.add_project("sdk-test", false);
+    server.add_document("sdk-test", "config.json", r#"{"debug": true}"#);
+    server
+}
+
+fn logs() -> (Arc<Mutex<Vec<String>>>, Log, Log) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    (log.clone(), Log { name: "a", log: log.clone() }, Log { name: "b", log })
+}
+
+#[test]
+fn order() {
+    let server = server();
+    let (log, a, b) = logs();
+
+    let jsb = mock_builder(&server)
+        .middleware(a)
+        .middleware(b)
+        .build()
+        .unwrap();
+    jsb.get_own_content_as_string("sdk-test/config.json").unwrap();
+
+    assert_eq!(*log.lock().unwrap(), vec![
+        "a:request:get_own_content:true",
+        "b:request:get_own_content:true",
+        "b:response:200",
+        "a:response:200",
+    ]);
+}
+
+#[test]
+fn change_request() {
+    let server = server();
+
+    // serves `latest.json` from `config.json` and removes the public key of reads
+    struct Rewrite;
+
+    impl Middleware for Rewrite {
+        fn on_request(&self, req: &mut ApiRequest) -> Result<Option<ApiResponse>, JsbError> {
+            req.url = req.url.replace("latest.json", "config.json");
+            req.headers.insert("X-Request-Id", HeaderValue::from_static("abc"));
+
+            if req.operation == "get_own_document_meta" {
+                req.headers.remove("jsb-pub-key");
+            }
+
+            // writes are stamped
+            if let Some(JsonValue::String(content)) = req.body.as_mut().and_then(|body| body.get_mut("content")) {
+                let mut value: JsonValue = serde_json::from_str(content).unwrap();
+                value["stamped"] = true.into();
+                *content = value.to_string();
+            }
+
+            Ok(None)
+        }
+    }
+
+    let jsb = mock_builder(&server)
+        .middleware(Rewrite)
+        .build()
+        .unwrap();
+
+    let content: JsonValue = jsb.get_own_content("sdk-test/latest.json").unwrap();
+    assert_eq!(content["debug"], true);
+
+    jsb.update_own_document("sdk-test/config.json", r#"{"debug": false}"#.to_string()).unwrap();
+    assert_eq!(server.document_content("sdk-test/config.json").unwrap(), r#"{"debug":false,"stamped":true}"#);
+
+    let err = jsb.get_own_document_meta("sdk-test/config.json").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Unauthorized);
+}
+
+#[test]
+fn short_circuit() {
+    let (log, a, b) = logs();
+
+    // nothing listens on the host, the answer never reaches it
+    let jsb = JsonBank::builder()
+        .host("http://127.0.0.1:1")
+        .public_key(MOCK_PUBLIC_KEY)
+        .retry(RetryPolicy::none())
+        .middleware(a)
+        .middleware(Answer(r#"{"cached": true}"#))
+        .middleware(b)
+        .build()
+        .unwrap();
+
+    let content: JsonValue = jsb.get_own_content("sdk-test/config.json").unwrap();
+    assert_eq!(content["cached"], true);
+    assert_eq!(*log.lock().unwrap(), vec!["a:request:get_own_content:true", "a:response:200"]);
+
+    // an error answers the request as well
+    let jsb = JsonBank::builder()
+        .host("http://127.0.0.1:1")
+        .keys(MOCK_PUBLIC_KEY, MOCK_PRIVATE_KEY)
+        .middleware(ReadOnly)
+        .middleware(Answer("{}"))
+        .build()
+        .unwrap();
+
+    let err = jsb.update_own_document("sdk-test/config.json", "{}".to_string()).unwrap_err();
+    assert_eq!(err.code, "read_only");
+    assert!(jsb.get_own_content_as_string("sdk-test/config.json").is_ok());
+}
+
+#[test]
+fn change_response() {
+    let server = server();
+
+    // a missing document reads as an empty object
+    struct EmptyIfMissing;
+
+    impl Middleware for EmptyIfMissing {
+        fn on_response(&self, _req: &ApiRequest, res: Result<ApiResponse, JsbError>) -> Result<ApiResponse, JsbError> {
+            match res {
+                Ok(res) if res.status == StatusCode::NOT_FOUND => {
+                    Ok(ApiResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: b"{}".to_vec() })
+                }
+                res => res,
+            }
+        }
+    }
+
+    let jsb = mock_builder(&server)
+        .middleware(EmptyIfMissing)
+        .build()
+        .unwrap();
+
+    assert_eq!(jsb.get_own_content_as_string("sdk-test/missing.json").unwrap(), "{}");
+    assert_eq!(jsb.get_own_content_as_string("sdk-test/config.json").unwrap(), r#"{"debug": true}"#);
+}
+
+#[tokio::test]
+async fn async_middleware() {
+    let server = server();
+    let (log, a, b) = logs();
+
+    let jsb = mock_builder(&server)
+        .middleware(a)
+        .middleware(ReadOnly)
+        .middleware(b)
+        .build_async()
+        .unwrap();
+
+    assert!(jsb.has_own_document("sdk-test/config.json").await.unwrap());
+    let err = jsb.update_own_document("sdk-test/config.json", "{}".to_string()).await.unwrap_err();
+    assert_eq!(err.code, "read_only");
+
+    assert_eq!(*log.lock().unwrap(), vec![
+        "a:request:get_own_document_meta:true",
+        "b:request:get_own_document_meta:true",
+        "b:response:200",
+        "a:response:200",
+        "a:request:update_own_document:false",
+        "a:response:0",
+    ]);
+}
@@ -1,10 +1,11 @@
-use jsonbank::{JsonBank};
+// shared helpers, not every test file uses all of them
+#![allow(dead_code)]
 
-pub struct Env {
-    pub host: String,
-    pub public_key: String,
-    pub private_key: String,
-}
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use jsonbank::{CacheConfig, JsonBank, JsonBankBuilder, ENV_PUBLIC_KEY};
+use jsonbank::testing::{MockServer, MOCK_PRIVATE_KEY, MOCK_PUBLIC_KEY};
 
 #[derive(Debug)]
 pub struct TestData {
@@ -48,14 +49,100 @@ pub fn prepare_instance(jsb: JsonBank, authenticated: bool) -> (JsonBank, TestDa
 }
 
 
+thread_local! {
+    // mock server of the current test, stopped when the test thread ends
+    static SERVER: RefCell<Option<MockServer>> = const { RefCell::new(None) };
+}
+
 // load env
-// this function loads the public and private keys from the environment file
-// at the root of the project
-pub fn load_env() -> Env {
+// this function loads the host, public and private keys from the environment file
+// at the root of the project and returns a builder using them.
+// When no keys are set, a local mock server is started for the current test.
+pub fn load_env() -> JsonBankBuilder {
     dotenv::dotenv().ok();
-    Env {
-        host: std::env::var("JSB_HOST").unwrap_or("https://api.jsonbank.io".to_string()),
-        public_key: std::env::var("JSB_PUBLIC_KEY").unwrap_or("".to_string()),
-        private_key: std::env::var("JSB_PRIVATE_KEY").unwrap_or("".to_string())
+
+    if std::env::var(ENV_PUBLIC_KEY).unwrap_or_default().is_empty() {
+        let server = start_mock_server();
+        let builder = mock_builder(&server);
+        SERVER.with(|s| *s.borrow_mut() = Some(server));
+
+        return builder;
+    }
+
+    match JsonBankBuilder::from_env() {
+        Ok(builder) => builder,
+        Err(err) => panic!("Invalid environment: {}", err),
     }
 }
+
+// start_mock_server - starts a mock server with the documents used by the tests
+pub fn start_mock_server() -> MockServer {
+    let server = MockServer::start();
+
+    server.add_project("sdk-test", true);
+    server.add_document("sdk-test", "index.json", r#"{
+        "name": "JsonBank SDK Test File",
+        "author": "jsonbank"
+    }"#);
+    server.add_folder("sdk-test", "folder");
+
+    server.add_github_file("jsonbankio/jsonbank-js/package.json", r#"{
+        "name": "jsonbank",
+        "author": "jsonbankio",
+        "scripts": { "prepublishOnly": "npm run build" }
+    }"#);
+    server.add_github_file("jsonbankio/documentation/github-test-array.json", r#"[
+        1,
+        "MultiType Array",
+        { "name": "github-test-array.json" }
+    ]"#);
+
+    server
+}
+
+// mock_client - starts a mock server with an empty private project and returns it with an instance using it
+pub fn mock_client(project: &str) -> (MockServer, JsonBank) {
+    let server = MockServer::start();
+    server.add_project(project, false);
+
+    let jsb = match mock_builder(&server).build() {
+        Ok(jsb) => jsb,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    (server, jsb)
+}
+
+// mock_cached_client - same as mock_client with a content cache
+pub fn mock_cached_client(project: &str, cache: CacheConfig) -> (MockServer, JsonBank) {
+    let server = MockServer::start();
+    server.add_project(project, false);
+
+    let jsb = match mock_builder(&server).cache(cache).build() {
+        Ok(jsb) => jsb,
+        Err(err) => panic!("{:?}", err),
+    };
+
+    (server, jsb)
+}
+
+// add_public_index - adds the public project `public-project` with an `index.json` document
+pub fn add_public_index(server: &MockServer) {
+    server.add_project("public-project", true);
+    server.add_document("public-project", "index.json", r#"{"public": true}"#);
+}
+
+// mock_builder - returns a builder using the host and keys of a mock server
+pub fn mock_builder(server: &MockServer) -> JsonBankBuilder {
+    JsonBank::builder()
+        .host(&server.url())
+        .keys(MOCK_PUBLIC_KEY, MOCK_PRIVATE_KEY)
+}
+
+// temp_dir - creates an empty directory for a test in the system temp directory
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jsonbank-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}